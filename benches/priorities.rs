@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use criterion::{Criterion, criterion_group, criterion_main};
+use schminput::{prelude::*, testing::headless_app};
+
+const KEYS: [KeyCode; 8] = [
+    KeyCode::KeyW,
+    KeyCode::KeyA,
+    KeyCode::KeyS,
+    KeyCode::KeyD,
+    KeyCode::Space,
+    KeyCode::KeyE,
+    KeyCode::KeyQ,
+    KeyCode::Escape,
+];
+
+/// Sets with different priorities whose actions share keys, returns an action of the last set.
+fn setup(sets: u32, actions_per_set: usize) -> (App, Entity) {
+    let mut app = headless_app();
+    let mut last = Entity::PLACEHOLDER;
+    for priority in 0..sets {
+        let name = format!("set_{priority}");
+        let set = app
+            .world_mut()
+            .spawn(ActionSet::new(name.clone(), name, priority))
+            .id();
+        for i in 0..actions_per_set {
+            let name = format!("action_{i}");
+            last = app
+                .world_mut()
+                .spawn((
+                    Action::new(name.clone(), name, set),
+                    KeyboardBindings::new().bind(KeyboardBinding::new(KEYS[i % KEYS.len()])),
+                    GamepadBindings::new().bind(GamepadBinding::new(GamepadBindingSource::South)),
+                    BoolActionValue::new(),
+                ))
+                .id();
+        }
+    }
+    app.update();
+    (app, last)
+}
+
+fn priorities(c: &mut Criterion) {
+    let mut group = c.benchmark_group("priorities");
+    let (mut app, _) = setup(10, 30);
+    group.bench_function("300 actions, unchanged", |b| b.iter(|| app.update()));
+    // every frame recomputes the blocked inputs, like before they were updated incrementally
+    let (mut app, action) = setup(10, 30);
+    group.bench_function("300 actions, bindings changed every frame", |b| {
+        b.iter(|| {
+            app.world_mut()
+                .get_mut::<KeyboardBindings>(action)
+                .unwrap()
+                .set_changed();
+            app.update()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, priorities);
+criterion_main!(benches);
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use criterion::{Criterion, criterion_group, criterion_main};
+use schminput::{
+    binding_modification::BindingModifications,
+    prelude::*,
+    processors::{InputProcessors, Scale},
+    testing::headless_app,
+};
+
+const PATHS: [&str; 4] = [
+    "/keyboard",
+    "/mouse/button",
+    "/gamepad/*",
+    "/gamepad/*/dpad",
+];
+
+/// Actions with bindings for every provider that request 4 subaction paths,
+/// `modified` adds a per path modification with processors to each of them.
+fn setup(actions: usize, modified: bool) -> App {
+    let mut app = headless_app();
+    app.add_systems(
+        Startup,
+        move |mut cmds: Commands, mut paths: ResMut<SubactionPaths>| {
+            let set = cmds.spawn(ActionSet::new("bench", "Bench", 0)).id();
+            let modification = cmds
+                .spawn(related!(InputProcessors[Scale::splat(2.0)]))
+                .id();
+            let dpad = paths.get_or_create_path(PATHS[3], &mut cmds);
+            for i in 0..actions {
+                let name = format!("action_{i}");
+                let sub_paths = RequestedSubactionPaths::new()
+                    .mutate(&mut paths, cmds.reborrow())
+                    .push_many(PATHS)
+                    .end();
+                let mut modifications = BindingModifications::default();
+                if modified {
+                    modifications.path_modification(dpad, modification);
+                }
+                cmds.spawn((
+                    Action::new(name.clone(), name, set),
+                    Vec2ActionValue::new(),
+                    sub_paths,
+                    modifications,
+                    KeyboardBindings::new().add_dpad(
+                        KeyCode::KeyW,
+                        KeyCode::KeyS,
+                        KeyCode::KeyA,
+                        KeyCode::KeyD,
+                    ),
+                    MouseBindings::new()
+                        .bind(MouseButtonBinding::new(MouseButton::Left))
+                        .delta_motion(),
+                    GamepadBindings::new()
+                        .bind(GamepadBinding::new(GamepadBindingSource::DPadUp).y_axis())
+                        .bind(GamepadBinding::new(GamepadBindingSource::DPadRight).x_axis())
+                        .bind(GamepadBinding::new(GamepadBindingSource::LeftStickX).x_axis())
+                        .bind(GamepadBinding::new(GamepadBindingSource::LeftStickY).y_axis()),
+                ));
+            }
+        },
+    );
+    app.update();
+    app
+}
+
+fn providers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("providers");
+    let mut app = setup(1000, false);
+    group.bench_function("1k actions, 4 subaction paths", |b| b.iter(|| app.update()));
+    let mut app = setup(1000, true);
+    group.bench_function("1k actions, 4 subaction paths, per path processors", |b| {
+        b.iter(|| app.update())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, providers);
+criterion_main!(benches);
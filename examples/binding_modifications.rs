@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use schminput::{
     binding_modification::{BindingModifications, PremultiplyDeltaSecsModification},
     prelude::*,
+    processors::{InputProcessors, RadialDeadzone, ResponseCurve},
 };
 fn main() -> AppExit {
     App::new()
@@ -29,6 +30,12 @@ fn setup(mut cmds: Commands, mut paths: ResMut<SubactionPaths>) {
     let set = cmds.spawn(ActionSet::new("core", "Core", 0)).id();
     let thumbstick_path = paths.get_or_create_path("/gamepad/*/thumbstick", &mut cmds);
     let modification_entity = cmds.spawn(PremultiplyDeltaSecsModification).id();
+    let processors_entity = cmds
+        .spawn(related!(InputProcessors[
+            RadialDeadzone::new(0.15),
+            ResponseCurve::Power(2.0),
+        ]))
+        .id();
     let action_1 = cmds
         .spawn((
             Action::new("action_1", "Test Action 1", set),
@@ -46,6 +53,7 @@ fn setup(mut cmds: Commands, mut paths: ResMut<SubactionPaths>) {
             GamepadBindings::new()
                 .bind(GamepadBinding::new(GamepadBindingSource::LeftStickX).x_axis())
                 .bind(GamepadBinding::new(GamepadBindingSource::LeftStickY).y_axis()),
+            BindingModifications::new().with_all_paths_modification(processors_entity),
             Vec2ActionValue::new(),
         ))
         .id();
@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+use schminput::prelude::*;
+
+fn main() -> AppExit {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(DefaultSchminputPlugins)
+        .add_systems(Startup, setup)
+        .add_systems(Update, print_output)
+        .run()
+}
+
+#[derive(Resource)]
+struct Actions {
+    save: Entity,
+    back: Entity,
+}
+
+fn print_output(actions: Res<Actions>, query: Query<&BoolActionValue>) {
+    let save = query.get(actions.save).unwrap();
+    let back = query.get(actions.back).unwrap();
+    info!("save: {}, back: {}", save.any, back.any);
+}
+
+fn setup(mut cmds: Commands) {
+    let set = cmds.spawn(ActionSet::new("core", "Core", 0)).id();
+    let save = cmds
+        .spawn((
+            Action::new("save", "Save", set),
+            ChordBindings::new()
+                .bind(
+                    ChordBinding::new(KeyCode::KeyS)
+                        .modifier(KeyCode::ControlLeft)
+                        .block_plain()
+                        .just_pressed(),
+                )
+                .bind(
+                    ChordBinding::new(GamepadButton::RightTrigger)
+                        .modifier(GamepadButton::LeftTrigger)
+                        .just_pressed(),
+                ),
+            BoolActionValue::new(),
+        ))
+        .id();
+    // doesn't fire while Ctrl+S is held
+    let back = cmds
+        .spawn((
+            Action::new("back", "Move Back", set),
+            KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::KeyS)),
+            BoolActionValue::new(),
+        ))
+        .id();
+    cmds.insert_resource(Actions { save, back });
+}
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use schminput::{ActionsInSet, players::PlayerActionSets, prelude::*};
+
+fn main() -> AppExit {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(DefaultSchminputPlugins)
+        .add_systems(Startup, setup)
+        .add_systems(Update, (join_and_leave, print_output))
+        .run()
+}
+
+#[derive(Resource)]
+struct Template(Entity);
+
+fn setup(mut cmds: Commands) {
+    // the template is copied for every player, so it stays disabled
+    let mut set = ActionSet::new("core", "Core", 0);
+    set.enabled = false;
+    let set = cmds.spawn(set).id();
+    cmds.spawn((
+        Action::new("move", "Move", set),
+        KeyboardBindings::new().add_dpad(
+            KeyCode::KeyW,
+            KeyCode::KeyS,
+            KeyCode::KeyA,
+            KeyCode::KeyD,
+        ),
+        GamepadBindings::new().add_stick(
+            GamepadBindingSource::LeftStickX,
+            GamepadBindingSource::LeftStickY,
+        ),
+        Vec2ActionValue::new(),
+    ));
+    cmds.insert_resource(Template(set));
+}
+
+/// Enter adds a player, the first one owns the keyboard, Backspace removes the last player
+fn join_and_leave(
+    input: Res<ButtonInput<KeyCode>>,
+    players: Query<(Entity, &Player)>,
+    template: Res<Template>,
+    mut cmds: Commands,
+) {
+    if input.just_pressed(KeyCode::Enter) {
+        let slot = players.iter().map(|(_, p)| p.slot + 1).max().unwrap_or(0);
+        let mut devices = PlayerDevices::new().auto_assign_gamepad();
+        if slot == 0 {
+            devices = devices.with_keyboard();
+        }
+        let player = cmds.spawn((Player::new(slot), devices)).id();
+        cmds.copy_action_set_for_player(template.0, player);
+        info!("player {slot} joined");
+    }
+    if input.just_pressed(KeyCode::Backspace)
+        && let Some((player, p)) = players.iter().max_by_key(|(_, p)| p.slot)
+    {
+        cmds.entity(player).despawn();
+        info!("player {} left", p.slot);
+    }
+}
+
+fn print_output(
+    players: Query<(&Player, &PlayerActionSets)>,
+    sets: Query<&ActionsInSet>,
+    values: Query<&Vec2ActionValue>,
+) {
+    for (player, owned) in &players {
+        for value in owned
+            .iter()
+            .filter_map(|set| sets.get(set).ok())
+            .flat_map(|actions| actions.iter())
+            .filter_map(|action| values.get(action).ok())
+        {
+            info!("player {}: {}", player.slot, value.any);
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Run with `cargo run --example manifest --features manifest,bevy/file_watcher`
+//! and edit `assets/basic.actions.ron` while it's running.
+
+use bevy::prelude::*;
+use schminput::{
+    manifest::{ActionManifestRoot, ManifestEntities, ManifestPlugin},
+    prelude::*,
+};
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins);
+    app.add_plugins(DefaultSchminputPlugins);
+    app.add_plugins(ManifestPlugin);
+    app.add_systems(Startup, setup);
+    app.add_systems(Update, read_actions);
+
+    app.run();
+}
+
+fn setup(mut cmds: Commands, asset_server: Res<AssetServer>) {
+    cmds.spawn(ActionManifestRoot(asset_server.load("basic.actions.ron")));
+    cmds.spawn(Camera3d::default());
+}
+
+fn read_actions(
+    manifest: Query<&ManifestEntities>,
+    vec2_actions: Query<&Vec2ActionValue>,
+    bool_actions: Query<&BoolActionValue>,
+) {
+    let Ok(entities) = manifest.single() else {
+        return;
+    };
+    let value = |action| entities.action("core", action);
+    if let Some(v) = value("move").and_then(|e| vec2_actions.get(e).ok()) {
+        info!("move: {}", v.any);
+    }
+    if let Some(v) = value("look").and_then(|e| vec2_actions.get(e).ok()) {
+        info!("look: {}", v.any);
+    }
+    if let Some(v) = value("jump").and_then(|e| bool_actions.get(e).ok()) {
+        info!("jump: {}", v.any);
+    }
+}
@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use schminput::{prelude::*, recording::InputPlaybackSettings};
+
+const RECORDING_PATH: &str = "recording.txt";
+
+fn main() -> AppExit {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(DefaultSchminputPlugins)
+        .add_systems(Startup, setup)
+        .add_systems(Update, (control_recorder, print_output))
+        .run()
+}
+
+#[derive(Resource)]
+struct MoveAction(Entity);
+
+/// F5 starts and stops recording, F6 plays back the last recording
+fn control_recorder(input: Res<ButtonInput<KeyCode>>, mut recorder: ResMut<InputRecorder>) {
+    if input.just_pressed(KeyCode::F5) {
+        match recorder.stop_recording() {
+            Some(recording) => match recording.save(RECORDING_PATH) {
+                Ok(()) => info!("saved recording to {RECORDING_PATH}"),
+                Err(err) => error!("unable to save recording: {err}"),
+            },
+            None => recorder.start_recording(),
+        }
+    }
+    if input.just_pressed(KeyCode::F6) {
+        match InputRecording::load(RECORDING_PATH) {
+            Ok(recording) => recorder.start_playback(recording, InputPlaybackSettings::default()),
+            Err(err) => error!("unable to load recording: {err}"),
+        }
+    }
+}
+
+fn print_output(action: Res<MoveAction>, query: Query<&Vec2ActionValue>) {
+    info!("move: {}", query.get(action.0).unwrap().any);
+}
+
+fn setup(mut cmds: Commands) {
+    let set = cmds.spawn(ActionSet::new("core", "Core", 0)).id();
+    let action = cmds
+        .spawn((
+            Action::new("move", "Move", set),
+            KeyboardBindings::new().add_dpad(
+                KeyCode::KeyW,
+                KeyCode::KeyS,
+                KeyCode::KeyA,
+                KeyCode::KeyD,
+            ),
+            GamepadBindings::new().add_stick(
+                GamepadBindingSource::LeftStickX,
+                GamepadBindingSource::LeftStickY,
+            ),
+            Vec2ActionValue::new(),
+        ))
+        .id();
+    cmds.insert_resource(MoveAction(action));
+}
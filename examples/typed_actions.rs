@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use schminput::prelude::*;
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins);
+    app.add_plugins(DefaultSchminputPlugins);
+    app.add_action_set::<CoreActions>();
+    app.add_systems(Startup, |mut cmds: Commands| {
+        cmds.spawn(Camera3d::default());
+    });
+    app.add_systems(Update, read_actions);
+
+    app.run();
+}
+
+#[derive(Resource, ActionSet)]
+#[action_set(name = "core", localized = "Core")]
+struct CoreActions {
+    #[action_set]
+    _set: Entity,
+    #[action(bindings = (
+        KeyboardBindings::new().add_dpad(KeyCode::KeyW, KeyCode::KeyS, KeyCode::KeyA, KeyCode::KeyD),
+        GamepadBindings::new().add_stick(
+            GamepadBindingSource::LeftStickX,
+            GamepadBindingSource::LeftStickY,
+        ),
+    ))]
+    move_: Vec2Action,
+    #[action(bindings = (
+        MouseBindings::new().delta_motion(),
+        GamepadBindings::new().add_stick(
+            GamepadBindingSource::RightStickX,
+            GamepadBindingSource::RightStickY,
+        ),
+    ))]
+    look: Vec2Action,
+    #[action(key = KeyCode::Space, gamepad = GamepadBindingSource::South)]
+    jump: BoolAction,
+    #[action(mouse_button = MouseButton::Left, gamepad = GamepadBindingSource::RightTrigger)]
+    fire: F32Action,
+}
+
+fn read_actions(actions: CoreActionsParam) {
+    info!("move: {}", actions.move_().value());
+    info!("look: {}", actions.look().value());
+    info!("fire: {}", actions.fire().value());
+    if actions.jump().just_pressed() {
+        info!("jump!");
+    }
+}
@@ -0,0 +1,263 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Expr, Fields, Ident, LitInt, LitStr, parse_macro_input};
+
+/// Declares an action set from a struct of typed actions.
+///
+/// Generates the code spawning the set and its actions, see
+/// `schminput::typed::ActionSetDeclaration`, and a `SystemParam` named after the struct
+/// with a `Param` suffix that reads the actions by field name, the struct itself has to
+/// be a `Resource` for that.
+///
+/// ```ignore
+/// #[derive(Resource, ActionSet)]
+/// #[action_set(name = "core", localized = "Core", priority = 0)]
+/// struct CoreActions {
+///     #[action_set]
+///     set: Entity,
+///     #[action(key = KeyCode::Space, gamepad = GamepadBindingSource::South)]
+///     jump: BoolAction,
+///     #[action(localized = "Move", bindings = KeyboardBindings::new().add_dpad(
+///         KeyCode::KeyW, KeyCode::KeyS, KeyCode::KeyA, KeyCode::KeyD,
+///     ))]
+///     move_: Vec2Action,
+/// }
+///
+/// fn jump(actions: CoreActionsParam) {
+///     if actions.jump().just_pressed() {}
+/// }
+/// ```
+///
+/// Set attributes:
+/// - `name`: defaults to the struct name in snake case, `HTTPActions` becomes `http_actions`
+/// - `localized`: defaults to the struct name
+/// - `priority`: defaults to 0
+///
+/// Action attributes, all optional:
+/// - `name`: defaults to the field name without leading and trailing underscores
+/// - `localized`: defaults to the name with spaces and capitalized words
+/// - `key`, `mouse_button`, `gamepad`: a default binding, can be repeated
+/// - `bindings`: any bundle of bindings, inserted after the bindings above
+#[proc_macro_derive(ActionSet, attributes(action_set, action))]
+pub fn derive_action_set(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match action_set(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[derive(Default)]
+struct ActionAttrs {
+    name: Option<LitStr>,
+    localized: Option<LitStr>,
+    keys: Vec<Expr>,
+    mouse_buttons: Vec<Expr>,
+    gamepad: Vec<Expr>,
+    bindings: Vec<Expr>,
+}
+
+fn action_set(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let vis = &input.vis;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "ActionSet can't be derived for generic structs",
+        ));
+    }
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "ActionSet can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "ActionSet can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut set_name = None;
+    let mut set_localized = None;
+    let mut priority = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("action_set"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                set_name = Some(meta.value()?.parse::<LitStr>()?);
+            } else if meta.path.is_ident("localized") {
+                set_localized = Some(meta.value()?.parse::<LitStr>()?);
+            } else if meta.path.is_ident("priority") {
+                priority = Some(meta.value()?.parse::<LitInt>()?);
+            } else {
+                return Err(meta.error("expected `name`, `localized` or `priority`"));
+            }
+            Ok(())
+        })?;
+    }
+    let set_name = set_name
+        .map(|n| n.value())
+        .unwrap_or_else(|| snake_case(&ident.to_string()));
+    let set_localized = set_localized
+        .map(|n| n.value())
+        .unwrap_or_else(|| ident.to_string());
+    let priority = priority.map(|p| quote!(#p)).unwrap_or(quote!(0));
+
+    let mut set_field: Option<&Ident> = None;
+    let mut spawn_fields = Vec::new();
+    let mut accessors = Vec::new();
+    for field in fields.named.iter() {
+        let field_ident = field.ident.as_ref().unwrap();
+        if field.attrs.iter().any(|a| a.path().is_ident("action_set")) {
+            if set_field.is_some() {
+                return Err(syn::Error::new_spanned(
+                    field_ident,
+                    "only one field can be marked with `#[action_set]`",
+                ));
+            }
+            set_field = Some(field_ident);
+            continue;
+        }
+        let mut attrs = ActionAttrs::default();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("action")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    attrs.name = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("localized") {
+                    attrs.localized = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("key") {
+                    attrs.keys.push(meta.value()?.parse()?);
+                } else if meta.path.is_ident("mouse_button") {
+                    attrs.mouse_buttons.push(meta.value()?.parse()?);
+                } else if meta.path.is_ident("gamepad") {
+                    attrs.gamepad.push(meta.value()?.parse()?);
+                } else if meta.path.is_ident("bindings") {
+                    attrs.bindings.push(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error(
+                        "expected `name`, `localized`, `key`, `mouse_button`, `gamepad` or `bindings`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+        let ty = &field.ty;
+        let name = attrs
+            .name
+            .map(|n| n.value())
+            .unwrap_or_else(|| field_ident.to_string().trim_matches('_').to_string());
+        let localized = attrs
+            .localized
+            .map(|n| n.value())
+            .unwrap_or_else(|| localized_name(&name));
+
+        let mut inserts = Vec::new();
+        if !attrs.keys.is_empty() {
+            let keys = &attrs.keys;
+            inserts.push(quote! {
+                ::schminput::keyboard::KeyboardBindings::new()
+                    #(.bind(::schminput::keyboard::KeyboardBinding::new(#keys)))*
+            });
+        }
+        if !attrs.mouse_buttons.is_empty() {
+            let buttons = &attrs.mouse_buttons;
+            inserts.push(quote! {
+                ::schminput::mouse::MouseBindings::new()
+                    #(.bind(::schminput::mouse::MouseButtonBinding::new(#buttons)))*
+            });
+        }
+        if !attrs.gamepad.is_empty() {
+            let sources = &attrs.gamepad;
+            inserts.push(quote! {
+                ::schminput::gamepad::GamepadBindings::new()
+                    #(.bind(::schminput::gamepad::GamepadBinding::new(#sources)))*
+            });
+        }
+        inserts.extend(attrs.bindings.iter().map(|b| quote!(#b)));
+
+        spawn_fields.push(quote! {
+            #field_ident: {
+                let mut action = cmds.spawn((
+                    ::schminput::Action::new(#name, #localized, set),
+                    <#ty>::value(),
+                ));
+                #(action.insert(#inserts);)*
+                <#ty>::new(action.id())
+            }
+        });
+        accessors.push(quote! {
+            pub fn #field_ident(&self) -> <#ty as ::schminput::typed::ActionHandle>::Ref<'_> {
+                self.actions.get(self.handles.#field_ident)
+            }
+        });
+    }
+    let set_field = set_field.map(|f| quote!(#f: set,));
+
+    let param_ident = format_ident!("{}Param", ident);
+    let param_doc = format!("Typed read access to the actions of [`{ident}`].");
+
+    Ok(quote! {
+        impl ::schminput::typed::ActionSetDeclaration for #ident {
+            fn spawn(cmds: &mut ::bevy::prelude::Commands) -> Self {
+                let set = cmds
+                    .spawn(::schminput::ActionSet::new(#set_name, #set_localized, #priority))
+                    .id();
+                Self {
+                    #(#spawn_fields,)*
+                    #set_field
+                }
+            }
+        }
+
+        #[doc = #param_doc]
+        #[derive(::bevy::ecs::system::SystemParam)]
+        #vis struct #param_ident<'w, 's> {
+            handles: ::bevy::prelude::Res<'w, #ident>,
+            actions: ::schminput::typed::TypedActions<'w, 's>,
+        }
+
+        impl #param_ident<'_, '_> {
+            pub fn handles(&self) -> &#ident {
+                &self.handles
+            }
+            #(#accessors)*
+        }
+    })
+}
+
+fn snake_case(ident: &str) -> String {
+    let chars = ident.chars().collect::<Vec<_>>();
+    let mut out = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i != 0 {
+            let prev = chars[i - 1];
+            let next_is_lowercase = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            // runs of capitals are one word, the last one starts the next word if it continues
+            if prev != '_' && (!prev.is_uppercase() || next_is_lowercase) {
+                out.push('_');
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+fn localized_name(name: &str) -> String {
+    name.split('_')
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
@@ -1,5 +1,6 @@
 #[cfg(feature = "xr")]
 use std::borrow::Cow;
+use std::{mem, time::Duration};
 
 use bevy::prelude::*;
 use bevy_egui::egui::{
@@ -201,6 +202,33 @@ pub fn draw_rebinding_ui(
                                     }
                                 }
                             );
+                            collapsable!(
+                                ui,
+                                entity,
+                                "Mouse Scroll:",
+                                {
+                                    mouse_rebind.write(RequestMouseRebinding::NewScrollBinding {
+                                        action: entity,
+                                    });
+                                },
+                                |ui| {
+                                    // always triggers change detection
+                                    if let Some(mouse) = mouse.as_mut() {
+                                        for (binding_index, binding) in
+                                            mouse.scroll.iter_mut().enumerate()
+                                        {
+                                            draw_mouse_scroll_binding(
+                                                ui,
+                                                binding,
+                                                is_bool_action,
+                                                binding_index,
+                                                entity,
+                                                &mut mouse_rebind,
+                                            )
+                                        }
+                                    }
+                                }
+                            );
                         }
                         if action_type != ActionType::GamepadHaptic {
                             collapsable!(
@@ -491,6 +519,90 @@ pub fn draw_mouse_moiton_binding(
     });
 }
 
+pub fn draw_mouse_scroll_binding(
+    ui: &mut Ui,
+    binding: &mut MouseScrollBinding,
+    is_bool_action: bool,
+    binding_index: usize,
+    action: Entity,
+    mouse_rebind: &mut MessageWriter<RequestMouseRebinding>,
+) {
+    CollapsingState::load_with_default_open(
+        ui.ctx(),
+        Id::new(BindingIdHash {
+            binding_index,
+            action,
+            id: "mouse scroll advanced",
+        }),
+        false,
+    )
+    .show_header(ui, |ui: &mut Ui| {
+        ui.horizontal(|ui| {
+            if ui
+                .button(RichText::new(binding.source.to_string()).monospace())
+                .clicked()
+            {
+                mouse_rebind.write(RequestMouseRebinding::RebindScroll {
+                    binding_index,
+                    action,
+                });
+            }
+            if ui.button(get_delete_text()).clicked() {
+                mouse_rebind.write(RequestMouseRebinding::DeleteScrollBinding {
+                    binding_index,
+                    action,
+                });
+            }
+        });
+    })
+    .body(|ui| {
+        egui::ComboBox::new(
+            BindingIdHash {
+                binding_index,
+                action,
+                id: "scroll source",
+            },
+            "source",
+        )
+        .width(0.0)
+        .selected_text(RichText::new(binding.source.to_string()).monospace())
+        .show_ui(ui, |ui| {
+            for source in [
+                MouseScrollSource::Both,
+                MouseScrollSource::Vertical,
+                MouseScrollSource::Horizontal,
+                MouseScrollSource::Up,
+                MouseScrollSource::Down,
+                MouseScrollSource::Left,
+                MouseScrollSource::Right,
+            ] {
+                ui.selectable_value(
+                    &mut binding.source,
+                    source,
+                    RichText::new(source.to_string()).monospace(),
+                );
+            }
+        });
+        if !is_bool_action {
+            if binding.source != MouseScrollSource::Both {
+                draw_input_axis(ui, &mut binding.axis, binding_index, action);
+            }
+            draw_input_axis_dir(ui, &mut binding.axis_dir, binding_index, action);
+        }
+        if binding.source.is_discrete() {
+            draw_button_behavior(ui, &mut binding.behavior, binding_index, action)
+        }
+        ui.horizontal(|ui| {
+            ui.label("multiplier: ");
+            ui.add(
+                DragValue::new(&mut binding.multiplier)
+                    .speed(0.05)
+                    .update_while_editing(false),
+            );
+        });
+    });
+}
+
 pub fn draw_keyboard_binding(
     ui: &mut Ui,
     binding: &mut KeyboardBinding,
@@ -575,12 +687,61 @@ fn draw_button_behavior(
             ButtonInputBeheavior::JustReleased,
             RichText::new(ButtonInputBeheavior::JustReleased.to_string()).monospace(),
         );
+        ui.selectable_value(
+            &mut b,
+            ButtonInputBeheavior::Toggle,
+            RichText::new(ButtonInputBeheavior::Toggle.to_string()).monospace(),
+        );
+        // the timed behaviors only get their default values when switching to them
+        for default in [
+            ButtonInputBeheavior::Hold(Duration::from_millis(500)),
+            ButtonInputBeheavior::Tap(Duration::from_millis(200)),
+            ButtonInputBeheavior::DoubleTap(Duration::from_millis(300)),
+            ButtonInputBeheavior::Repeat {
+                delay: Duration::from_millis(500),
+                rate: Duration::from_millis(100),
+            },
+        ] {
+            let selected = mem::discriminant(&b) == mem::discriminant(&default);
+            if ui
+                .selectable_label(selected, RichText::new(default.to_string()).monospace())
+                .clicked()
+                && !selected
+            {
+                b = default;
+            }
+        }
     });
+    match &mut b {
+        ButtonInputBeheavior::Hold(duration)
+        | ButtonInputBeheavior::Tap(duration)
+        | ButtonInputBeheavior::DoubleTap(duration) => draw_duration(ui, duration, "duration"),
+        ButtonInputBeheavior::Repeat { delay, rate } => {
+            draw_duration(ui, delay, "delay");
+            draw_duration(ui, rate, "rate");
+        }
+        _ => {}
+    }
     // needed for correct change detection
     if b != *behavior {
         *behavior = b
     }
 }
+fn draw_duration(ui: &mut Ui, duration: &mut Duration, label: &str) {
+    let mut secs = duration.as_secs_f32();
+    ui.label(label);
+    if ui
+        .add(
+            DragValue::new(&mut secs)
+                .speed(0.01)
+                .range(0.0..=10.0)
+                .suffix("s"),
+        )
+        .changed()
+    {
+        *duration = Duration::from_secs_f32(secs);
+    }
+}
 fn draw_input_axis(ui: &mut Ui, axis: &mut InputAxis, binding_index: usize, action: Entity) {
     egui::ComboBox::new(
         BindingIdHash {
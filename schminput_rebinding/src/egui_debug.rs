@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use bevy_egui::{
+    EguiContexts, EguiPrimaryContextPass,
+    egui::{self, CollapsingHeader, Color32, Grid, RichText},
+};
+use schminput::debug::{ActionSetDebugInfo, action_debug_info, track_contributing_bindings};
+
+/// Shows the live values of all actions and the state of their sets, for debugging
+/// bindings in game. Headless apps can use [`LogActionValues`](schminput::debug::LogActionValues)
+/// instead.
+#[derive(Clone, Copy, Resource, Debug, PartialEq, Eq)]
+pub struct ActionDebugOverlay {
+    pub show: bool,
+    /// Toggles the overlay, F12 by default.
+    pub toggle_key: Option<KeyCode>,
+}
+
+impl Default for ActionDebugOverlay {
+    fn default() -> Self {
+        Self {
+            show: false,
+            toggle_key: Some(KeyCode::F12),
+        }
+    }
+}
+
+/// The info drawn by the overlay, collected every frame while it is shown.
+#[derive(Clone, Resource, Debug, Default)]
+struct ActionDebugOverlayInfo(Vec<ActionSetDebugInfo>);
+
+pub struct ActionDebugOverlayPlugin;
+
+impl Plugin for ActionDebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionDebugOverlay>();
+        app.init_resource::<ActionDebugOverlayInfo>();
+        app.add_systems(Update, toggle_overlay);
+        app.add_systems(PreUpdate, track_contributing_bindings.run_if(overlay_shown));
+        app.add_systems(
+            EguiPrimaryContextPass,
+            (collect_info, draw_overlay).chain().run_if(overlay_shown),
+        );
+    }
+}
+
+fn overlay_shown(overlay: Res<ActionDebugOverlay>) -> bool {
+    overlay.show
+}
+
+fn toggle_overlay(
+    mut overlay: ResMut<ActionDebugOverlay>,
+    input: Option<Res<ButtonInput<KeyCode>>>,
+) {
+    let (Some(key), Some(input)) = (overlay.toggle_key, input) else {
+        return;
+    };
+    if input.just_pressed(key) {
+        overlay.show = !overlay.show;
+    }
+}
+
+fn collect_info(world: &mut World) {
+    let info = action_debug_info(world);
+    world.resource_mut::<ActionDebugOverlayInfo>().0 = info;
+}
+
+fn draw_overlay(mut ctxs: EguiContexts, info: Res<ActionDebugOverlayInfo>) {
+    let Ok(ctx) = ctxs.ctx_mut() else {
+        return;
+    };
+    egui::Window::new("Schminput Debug").show(ctx, |ui| {
+        for set in info.0.iter() {
+            let mut title = format!("{} (priority {}", set.name, set.priority);
+            if set.transparent {
+                title.push_str(", transparent");
+            }
+            title.push(')');
+            let color = match set.enabled {
+                true => Color32::LIGHT_GREEN,
+                false => Color32::GRAY,
+            };
+            CollapsingHeader::new(RichText::new(title).color(color))
+                .id_salt(set.set)
+                .default_open(set.enabled)
+                .show(ui, |ui| {
+                    Grid::new(set.set).striped(true).show(ui, |ui| {
+                        for action in set.actions.iter() {
+                            ui.label(&action.name);
+                            ui.vertical(|ui| {
+                                for value in action.values.iter() {
+                                    let path = value.path.as_deref().unwrap_or("any");
+                                    ui.label(
+                                        RichText::new(format!("{path}: {}", value.value))
+                                            .monospace(),
+                                    );
+                                }
+                            });
+                            ui.label(
+                                RichText::new(action.contributing.join(", "))
+                                    .monospace()
+                                    .color(Color32::LIGHT_BLUE),
+                            );
+                            ui.end_row();
+                        }
+                    });
+                });
+        }
+    });
+}
@@ -3,6 +3,8 @@ pub mod default_bindings;
 #[cfg(feature = "egui")]
 pub mod egui;
 #[cfg(feature = "egui")]
+pub mod egui_debug;
+#[cfg(feature = "egui")]
 pub mod egui_window;
 pub mod persistent_bindings;
 pub mod runtime_rebinding;
@@ -14,6 +16,8 @@ use bevy::{app::PluginGroupBuilder, prelude::*};
 use config::SchminputConfigPlugin;
 use default_bindings::RebindingDefaultBindingsPlugin;
 #[cfg(feature = "egui")]
+use egui_debug::ActionDebugOverlayPlugin;
+#[cfg(feature = "egui")]
 use egui_window::RebindingEguiWindowPlugin;
 use persistent_bindings::PersistentBindingsPlugin;
 use runtime_rebinding::RuntimeRebindingPlugin;
@@ -34,7 +38,9 @@ impl PluginGroup for DefaultSchminputRebindingPlugins {
         #[cfg(feature = "egui")]
         #[allow(clippy::unnecessary_operation)]
         {
-            plugins = plugins.add(RebindingEguiWindowPlugin)
+            plugins = plugins
+                .add(RebindingEguiWindowPlugin)
+                .add(ActionDebugOverlayPlugin)
         };
         #[cfg(feature = "xr")]
         #[allow(clippy::unnecessary_operation)]
@@ -106,7 +106,7 @@ fn serialize_v1(
                         table.insert("axis", input_axis_to_str(binding.axis).into());
                         table.insert(
                             "button_behavior",
-                            button_behavior_to_str(binding.behavior).into(),
+                            (&*button_behavior_to_cow_str(binding.behavior)).into(),
                         );
                         bindings_list.push(table);
                     }
@@ -135,13 +135,34 @@ fn serialize_v1(
                             table.insert("axis", input_axis_to_str(binding.axis).into());
                             table.insert(
                                 "button_behavior",
-                                button_behavior_to_str(binding.behavior).into(),
+                                (&*button_behavior_to_cow_str(binding.behavior)).into(),
                             );
                             bindings_list.push(table);
                         }
                         bindings_list.fmt();
                         doc_bindings["mouse_button"] = toml_edit::value(bindings_list);
                     }
+                    if !mouse.scroll.is_empty() {
+                        let mut bindings_list = toml_edit::Array::new();
+                        for binding in mouse.scroll.iter() {
+                            let mut table = toml_edit::InlineTable::new();
+                            table.insert(
+                                "source",
+                                mouse_scroll_source_to_str(binding.source).into(),
+                            );
+                            table.insert("multiplier", (binding.multiplier as f64).into());
+                            table
+                                .insert("axis_dir", input_axis_dir_to_str(binding.axis_dir).into());
+                            table.insert("axis", input_axis_to_str(binding.axis).into());
+                            table.insert(
+                                "button_behavior",
+                                (&*button_behavior_to_cow_str(binding.behavior)).into(),
+                            );
+                            bindings_list.push(table);
+                        }
+                        bindings_list.fmt();
+                        doc_bindings["mouse_scroll"] = toml_edit::value(bindings_list);
+                    }
                 }
                 if let Some(gamepad) = gamepad {
                     let mut bindings_list = toml_edit::Array::new();
@@ -155,7 +176,7 @@ fn serialize_v1(
                         table.insert("axis", input_axis_to_str(binding.axis).into());
                         table.insert(
                             "button_behavior",
-                            button_behavior_to_str(binding.button_behavior).into(),
+                            (&*button_behavior_to_cow_str(binding.button_behavior)).into(),
                         );
                         bindings_list.push(table);
                     }
@@ -500,6 +521,75 @@ fn parse_mouse(
             });
         }
     }
+    if let Some(mouse_scroll) = bindings.get("mouse_scroll") {
+        let Some(mouse_scroll) = mouse_scroll.as_array() else {
+            error!("mouse_scroll field on {name}.{action_name} is not an array");
+            return mouse_bindings;
+        };
+        for binding_table in mouse_scroll.iter() {
+            let Some(binding_table) = binding_table.as_inline_table() else {
+                error!("mouse scroll binding array doesn't contain inline tables");
+                continue;
+            };
+            let source = {
+                let Some(val) = str_from_table(binding_table, "source") else {
+                    error!("cannot get string for {name}.{action_name}.mouse_scroll.source");
+                    continue;
+                };
+                let Some(w) = str_to_mouse_scroll_source(val) else {
+                    error!("unable to parse {val} as mouse scroll source");
+                    continue;
+                };
+                w
+            };
+            let Some(multiplier) = f32_from_table(binding_table, "multiplier") else {
+                error!("cannot get number for {name}.{action_name}.mouse_scroll.multiplier");
+                continue;
+            };
+            let axis_dir = {
+                let Some(val) = str_from_table(binding_table, "axis_dir") else {
+                    error!("cannot get string for {name}.{action_name}.mouse_scroll.axis_dir");
+                    continue;
+                };
+                let Some(w) = str_to_input_axis_dir(val) else {
+                    error!("unable to parse {val} as axis direction");
+                    continue;
+                };
+                w
+            };
+            let axis = {
+                let Some(val) = str_from_table(binding_table, "axis") else {
+                    error!("cannot get string for {name}.{action_name}.mouse_scroll.axis");
+                    continue;
+                };
+                let Some(w) = str_to_input_axis(val) else {
+                    error!("unable to parse {val} as axis");
+                    continue;
+                };
+                w
+            };
+            let behavior = {
+                let Some(val) = str_from_table(binding_table, "button_behavior") else {
+                    error!(
+                        "cannot get string for {name}.{action_name}.mouse_scroll.button_behavior"
+                    );
+                    continue;
+                };
+                let Some(w) = str_to_button_behavior(val) else {
+                    error!("unable to parse {val} as button behavior");
+                    continue;
+                };
+                w
+            };
+            mouse_bindings = mouse_bindings.scroll(MouseScrollBinding {
+                source,
+                axis,
+                axis_dir,
+                behavior,
+                multiplier,
+            });
+        }
+    }
     mouse_bindings
 }
 fn parse_keyboard(
@@ -3,19 +3,23 @@ use std::borrow::Cow;
 
 use bevy::{
     input::{
+        ButtonState,
         gamepad::{GamepadAxisChangedEvent, GamepadButtonChangedEvent},
         keyboard::KeyboardInput,
-        mouse::MouseButtonInput,
-        ButtonState,
+        mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel},
     },
     prelude::*,
 };
 #[cfg(feature = "xr")]
 use schminput::openxr::OxrBindings;
 use schminput::{
+    BoolActionValue,
     gamepad::{GamepadBinding, GamepadBindingSource, GamepadBindings},
     keyboard::KeyboardBindings,
-    mouse::{MouseBindings, MouseButtonBinding, MouseMotionBinding},
+    mouse::{
+        MouseBindings, MouseButtonBinding, MouseMotionBinding, MouseScrollBinding,
+        MouseScrollSource,
+    },
 };
 #[cfg(feature = "xr")]
 #[derive(Message)]
@@ -93,6 +97,16 @@ enum PendingMouseButtonRebinding {
         action: Entity,
     },
 }
+#[derive(Resource)]
+enum PendingMouseScrollRebinding {
+    Rebind {
+        binding_index: usize,
+        action: Entity,
+    },
+    New {
+        action: Entity,
+    },
+}
 #[derive(Message, Clone, Copy)]
 pub enum RequestMouseRebinding {
     RebindButton {
@@ -112,6 +126,18 @@ pub enum RequestMouseRebinding {
     DeleteMotionBinding {
         action: Entity,
     },
+    /// Waits for the next scroll, the scrolled direction or axis becomes the source.
+    RebindScroll {
+        binding_index: usize,
+        action: Entity,
+    },
+    DeleteScrollBinding {
+        binding_index: usize,
+        action: Entity,
+    },
+    NewScrollBinding {
+        action: Entity,
+    },
 }
 
 #[derive(Clone, Copy, Resource)]
@@ -144,6 +170,10 @@ impl Plugin for RuntimeRebindingPlugin {
             PreUpdate,
             handle_mouse_rebinding.run_if(resource_exists::<PendingMouseButtonRebinding>),
         );
+        app.add_systems(
+            PreUpdate,
+            handle_mouse_scroll_rebinding.run_if(resource_exists::<PendingMouseScrollRebinding>),
+        );
         app.add_systems(
             PreUpdate,
             handle_gamepad_rebinding.run_if(resource_exists::<PendingGamepadRebinding>),
@@ -257,10 +287,9 @@ fn handle_gamepad_rebinding(
                         GamepadBindingSource::from_button(&input.button),
                     )),
                     None => {
-                        cmds.entity(action)
-                            .insert(GamepadBindings::new().bind(GamepadBinding::new(
-                                GamepadBindingSource::from_button(&input.button),
-                            )));
+                        cmds.entity(action).insert(GamepadBindings::new().bind(
+                            GamepadBinding::new(GamepadBindingSource::from_button(&input.button)),
+                        ));
                     }
                 }
             }
@@ -298,10 +327,9 @@ fn handle_gamepad_rebinding(
                         GamepadBindingSource::from_axis(&input.axis),
                     )),
                     None => {
-                        cmds.entity(action)
-                            .insert(GamepadBindings::new().bind(GamepadBinding::new(
-                                GamepadBindingSource::from_axis(&input.axis),
-                            )));
+                        cmds.entity(action).insert(GamepadBindings::new().bind(
+                            GamepadBinding::new(GamepadBindingSource::from_axis(&input.axis)),
+                        ));
                     }
                 }
             }
@@ -316,10 +344,11 @@ fn handle_mouse_request(
     mut message: MessageReader<RequestMouseRebinding>,
     mut cmds: Commands,
     pending: Option<Res<PendingMouseButtonRebinding>>,
+    pending_scroll: Option<Res<PendingMouseScrollRebinding>>,
     mut action_query: Query<&mut MouseBindings>,
     mut waiting: ResMut<WaitingForInput>,
 ) {
-    if pending.is_some() {
+    if pending.is_some() || pending_scroll.is_some() {
         return;
     }
     match message.read().next().copied() {
@@ -358,6 +387,29 @@ fn handle_mouse_request(
             };
             v.movement = Some(MouseMotionBinding::new());
         }
+        Some(RequestMouseRebinding::RebindScroll {
+            binding_index,
+            action,
+        }) => {
+            cmds.insert_resource(PendingMouseScrollRebinding::Rebind {
+                binding_index,
+                action,
+            });
+            waiting.0 += 1;
+        }
+        Some(RequestMouseRebinding::DeleteScrollBinding {
+            binding_index,
+            action,
+        }) => {
+            let Ok(mut v) = action_query.get_mut(action) else {
+                return;
+            };
+            v.scroll.remove(binding_index);
+        }
+        Some(RequestMouseRebinding::NewScrollBinding { action }) => {
+            cmds.insert_resource(PendingMouseScrollRebinding::New { action });
+            waiting.0 += 1;
+        }
         None => {}
     }
 }
@@ -411,6 +463,70 @@ fn handle_mouse_rebinding(
     }
 }
 
+/// The source for a scroll, a direction for discrete bindings and an axis otherwise.
+fn scroll_source(scroll: Vec2, discrete: bool) -> MouseScrollSource {
+    let vertical = scroll.y.abs() >= scroll.x.abs();
+    match (discrete, vertical) {
+        (false, true) => MouseScrollSource::Vertical,
+        (false, false) => MouseScrollSource::Horizontal,
+        (true, true) if scroll.y > 0.0 => MouseScrollSource::Up,
+        (true, true) => MouseScrollSource::Down,
+        (true, false) if scroll.x > 0.0 => MouseScrollSource::Right,
+        (true, false) => MouseScrollSource::Left,
+    }
+}
+
+fn handle_mouse_scroll_rebinding(
+    rebinding: Res<PendingMouseScrollRebinding>,
+    mut action_query: Query<(Option<&mut MouseBindings>, Has<BoolActionValue>)>,
+    mut input: MessageReader<MouseWheel>,
+    mut cmds: Commands,
+    mut waiting: ResMut<WaitingForInput>,
+) {
+    for input in input.read() {
+        let scroll = Vec2::new(input.x, input.y);
+        // tiny pixel scrolls from touchpads are too easy to trigger by accident
+        if scroll == Vec2::ZERO || (input.unit == MouseScrollUnit::Pixel && scroll.length() < 1.0) {
+            continue;
+        }
+        match *rebinding {
+            PendingMouseScrollRebinding::Rebind {
+                binding_index,
+                action,
+            } => {
+                let Ok((Some(mut bindings), _)) = action_query.get_mut(action) else {
+                    error!("mouse scroll rebinding request with invalid action entity");
+                    return;
+                };
+                let Some(binding) = bindings.scroll.get_mut(binding_index) else {
+                    error!("mouse scroll rebinding request with invalid binding index");
+                    return;
+                };
+                if binding.source != MouseScrollSource::Both {
+                    binding.source = scroll_source(scroll, binding.source.is_discrete());
+                }
+            }
+            PendingMouseScrollRebinding::New { action } => {
+                let Ok((bindings, is_bool)) = action_query.get_mut(action) else {
+                    error!("mouse scroll rebinding request with invalid action entity");
+                    return;
+                };
+                let binding = MouseScrollBinding::new(scroll_source(scroll, is_bool));
+                match bindings {
+                    Some(mut bindings) => bindings.scroll.push(binding),
+                    None => {
+                        cmds.entity(action)
+                            .insert(MouseBindings::new().scroll(binding));
+                    }
+                }
+            }
+        }
+        cmds.remove_resource::<PendingMouseScrollRebinding>();
+        waiting.0 = waiting.0.saturating_sub(1);
+        break;
+    }
+}
+
 fn handle_keyboard_request(
     mut message: MessageReader<RequestKeyboardRebinding>,
     mut cmds: Commands,
@@ -1,12 +1,13 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, time::Duration};
 
 use bevy::{
     input::{keyboard::KeyCode, mouse::MouseButton},
     log::error,
 };
 use schminput::{
-    gamepad::{GamepadBindingSource, GamepadHapticType},
     ButtonInputBeheavior, InputAxis, InputAxisDirection,
+    gamepad::{GamepadBindingSource, GamepadHapticType},
+    mouse::MouseScrollSource,
 };
 pub(crate) fn gamepad_haptics_type_to_str(haptics: GamepadHapticType) -> &'static str {
     match haptics {
@@ -21,18 +22,50 @@ pub(crate) fn str_to_gamepad_haptics_type(str: &str) -> Option<GamepadHapticType
         _ => return None,
     })
 }
-pub(crate) fn button_behavior_to_str(behavior: ButtonInputBeheavior) -> &'static str {
+pub(crate) fn button_behavior_to_cow_str(behavior: ButtonInputBeheavior) -> Cow<'static, str> {
     match behavior {
         ButtonInputBeheavior::JustPressed => "JustPressed",
         ButtonInputBeheavior::Pressed => "Pressed",
         ButtonInputBeheavior::JustReleased => "JustReleased",
+        ButtonInputBeheavior::Toggle => "Toggle",
+        ButtonInputBeheavior::Hold(duration) => {
+            return format!("Hold?{}", duration.as_secs_f32()).into();
+        }
+        ButtonInputBeheavior::Tap(duration) => {
+            return format!("Tap?{}", duration.as_secs_f32()).into();
+        }
+        ButtonInputBeheavior::DoubleTap(duration) => {
+            return format!("DoubleTap?{}", duration.as_secs_f32()).into();
+        }
+        ButtonInputBeheavior::Repeat { delay, rate } => {
+            return format!("Repeat?{}?{}", delay.as_secs_f32(), rate.as_secs_f32()).into();
+        }
     }
+    .into()
 }
 pub(crate) fn str_to_button_behavior(str: &str) -> Option<ButtonInputBeheavior> {
-    Some(match str {
+    let mut parts = str.split('?');
+    let name = parts.next()?;
+    let mut duration = || {
+        let w = parts.next()?;
+        let Ok(secs) = w.parse() else {
+            error!("unable to parce {name} duration: {}", str);
+            return None;
+        };
+        Duration::try_from_secs_f32(secs).ok()
+    };
+    Some(match name {
         "JustPressed" => ButtonInputBeheavior::JustPressed,
         "Pressed" => ButtonInputBeheavior::Pressed,
         "JustReleased" => ButtonInputBeheavior::JustReleased,
+        "Toggle" => ButtonInputBeheavior::Toggle,
+        "Hold" => ButtonInputBeheavior::Hold(duration()?),
+        "Tap" => ButtonInputBeheavior::Tap(duration()?),
+        "DoubleTap" => ButtonInputBeheavior::DoubleTap(duration()?),
+        "Repeat" => ButtonInputBeheavior::Repeat {
+            delay: duration()?,
+            rate: duration()?,
+        },
         _ => return None,
     })
 }
@@ -59,6 +92,29 @@ pub(crate) fn str_to_mouse_button(str: &str) -> Option<MouseButton> {
         }
     })
 }
+pub(crate) fn mouse_scroll_source_to_str(source: MouseScrollSource) -> &'static str {
+    match source {
+        MouseScrollSource::Both => "Both",
+        MouseScrollSource::Vertical => "Vertical",
+        MouseScrollSource::Horizontal => "Horizontal",
+        MouseScrollSource::Up => "Up",
+        MouseScrollSource::Down => "Down",
+        MouseScrollSource::Left => "Left",
+        MouseScrollSource::Right => "Right",
+    }
+}
+pub(crate) fn str_to_mouse_scroll_source(str: &str) -> Option<MouseScrollSource> {
+    Some(match str {
+        "Both" => MouseScrollSource::Both,
+        "Vertical" => MouseScrollSource::Vertical,
+        "Horizontal" => MouseScrollSource::Horizontal,
+        "Up" => MouseScrollSource::Up,
+        "Down" => MouseScrollSource::Down,
+        "Left" => MouseScrollSource::Left,
+        "Right" => MouseScrollSource::Right,
+        _ => return None,
+    })
+}
 pub(crate) fn input_axis_dir_to_str(axis: InputAxisDirection) -> &'static str {
     match axis {
         InputAxisDirection::Positive => "+",
@@ -115,7 +171,7 @@ pub(crate) fn gamepad_binding_source_to_cow_str(source: GamepadBindingSource) ->
         GamepadBindingSource::Z => "Z",
         GamepadBindingSource::OtherAxis(axis) => return format!("OtherAxis?{}", axis).into(),
         GamepadBindingSource::OtherButton(button) => {
-            return format!("OtherButton?{}", button).into()
+            return format!("OtherButton?{}", button).into();
         }
     }
     .into()
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+
+use crate::{
+    Action, ActionSet, SchminputSystems,
+    action_state::{ActionState, ActionStateData, ActionValueType},
+    subaction_paths::SubactionPath,
+};
+
+pub struct ActionEventsPlugin;
+
+impl Plugin for ActionEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (
+                trigger_events::<bool>,
+                trigger_events::<f32>,
+                trigger_events::<Vec2>,
+            )
+                .in_set(SchminputSystems::TriggerActionEvents),
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Reflect, PartialEq)]
+pub enum ActionEventValue {
+    Bool(bool),
+    F32(f32),
+    Vec2(Vec2),
+}
+
+impl From<bool> for ActionEventValue {
+    fn from(value: bool) -> Self {
+        ActionEventValue::Bool(value)
+    }
+}
+impl From<f32> for ActionEventValue {
+    fn from(value: f32) -> Self {
+        ActionEventValue::F32(value)
+    }
+}
+impl From<Vec2> for ActionEventValue {
+    fn from(value: Vec2) -> Self {
+        ActionEventValue::Vec2(value)
+    }
+}
+
+/// Triggered on the [`Action`] entity when it becomes active.
+#[derive(Clone, Copy, Debug, EntityEvent)]
+pub struct ActionStarted {
+    #[event_target]
+    pub action: Entity,
+    /// [`None`] for the value of all paths combined
+    pub path: Option<SubactionPath>,
+    pub value: ActionEventValue,
+}
+
+/// Triggered on the [`Action`] entity every frame it stays active.
+#[derive(Clone, Copy, Debug, EntityEvent)]
+pub struct ActionOngoing {
+    #[event_target]
+    pub action: Entity,
+    /// [`None`] for the value of all paths combined
+    pub path: Option<SubactionPath>,
+    pub value: ActionEventValue,
+}
+
+/// Triggered on the [`Action`] entity when it stops being active,
+/// carries the last active value.
+#[derive(Clone, Copy, Debug, EntityEvent)]
+pub struct ActionCompleted {
+    #[event_target]
+    pub action: Entity,
+    /// [`None`] for the value of all paths combined
+    pub path: Option<SubactionPath>,
+    pub value: ActionEventValue,
+}
+
+/// Triggered on the [`Action`] entity instead of [`ActionCompleted`]
+/// when it stops being active because its [`ActionSet`] got disabled.
+#[derive(Clone, Copy, Debug, EntityEvent)]
+pub struct ActionCanceled {
+    #[event_target]
+    pub action: Entity,
+    /// [`None`] for the value of all paths combined
+    pub path: Option<SubactionPath>,
+    pub value: ActionEventValue,
+}
+
+fn trigger_events<T: ActionValueType + Into<ActionEventValue>>(
+    query: Query<(Entity, &Action, &ActionState<T>)>,
+    set_query: Query<&ActionSet>,
+    mut cmds: Commands,
+) {
+    for (action, action_data, state) in &query {
+        let set_enabled = set_query.get(action_data.set).is_ok_and(|set| set.enabled);
+        trigger_for_path(&mut cmds, action, None, &state.any, set_enabled);
+        for (path, data) in state.paths.iter() {
+            trigger_for_path(&mut cmds, action, Some(*path), data, set_enabled);
+        }
+    }
+}
+
+fn trigger_for_path<T: ActionValueType + Into<ActionEventValue>>(
+    cmds: &mut Commands,
+    action: Entity,
+    path: Option<SubactionPath>,
+    data: &ActionStateData<T>,
+    set_enabled: bool,
+) {
+    if data.started() {
+        cmds.trigger(ActionStarted {
+            action,
+            path,
+            value: data.value.into(),
+        });
+    } else if data.ongoing() {
+        cmds.trigger(ActionOngoing {
+            action,
+            path,
+            value: data.value.into(),
+        });
+    } else if data.completed() {
+        if set_enabled {
+            cmds.trigger(ActionCompleted {
+                action,
+                path,
+                value: data.previous_value.into(),
+            });
+        } else {
+            cmds.trigger(ActionCanceled {
+                action,
+                path,
+                value: data.previous_value.into(),
+            });
+        }
+    }
+}
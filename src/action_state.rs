@@ -0,0 +1,161 @@
+use std::{ops::Deref, time::Duration};
+
+use bevy::{
+    ecs::{change_detection::Tick, component::Mutable, system::SystemChangeTick},
+    prelude::*,
+};
+
+use crate::{
+    BoolActionValue, F32ActionValue, SchminputSystems, Vec2ActionValue,
+    subaction_paths::{SubactionPath, SubactionPathMap},
+};
+
+pub struct ActionStatePlugin;
+
+impl Plugin for ActionStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (
+                update_states::<bool, BoolActionValue>,
+                update_states::<f32, F32ActionValue>,
+                update_states::<Vec2, Vec2ActionValue>,
+            )
+                .in_set(SchminputSystems::UpdateActionStates),
+        );
+    }
+}
+
+/// Implemented for the types an action can have as its value.
+pub trait ActionValueType: Copy + Default + PartialEq + Send + Sync + 'static {
+    /// An action counts as active while its value is true or non zero.
+    fn is_active(&self) -> bool;
+}
+
+impl ActionValueType for bool {
+    fn is_active(&self) -> bool {
+        *self
+    }
+}
+impl ActionValueType for f32 {
+    fn is_active(&self) -> bool {
+        *self != 0.0
+    }
+}
+impl ActionValueType for Vec2 {
+    fn is_active(&self) -> bool {
+        *self != Vec2::ZERO
+    }
+}
+
+pub type BoolActionState = ActionState<bool>;
+pub type F32ActionState = ActionState<f32>;
+pub type Vec2ActionState = ActionState<Vec2>;
+
+/// The state of an action across frames, automatically added to actions with a value.
+/// The state is updated in [`SchminputSystems::UpdateActionStates`].
+#[derive(Debug, Clone, Component, Reflect, Deref, DerefMut, Default)]
+pub struct ActionState<T: ActionValueType>(pub SubactionPathMap<ActionStateData<T>>);
+
+impl<T: ActionValueType> ActionState<T> {
+    /// The subaction paths whose value changed this frame.
+    pub fn changed_paths(&self) -> impl Iterator<Item = SubactionPath> + '_ {
+        self.paths
+            .iter()
+            .filter(|(_, state)| state.changed())
+            .map(|(path, _)| *path)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Reflect, Default, PartialEq)]
+pub struct ActionStateData<T: ActionValueType> {
+    pub value: T,
+    pub previous_value: T,
+    /// How long the action has been active, keeps the value after the action completed.
+    pub held_for: Duration,
+    /// How long the action has been inactive since it last completed.
+    pub released_for: Duration,
+    /// The tick at which the value last changed.
+    pub last_changed: Tick,
+}
+
+impl<T: ActionValueType> ActionStateData<T> {
+    pub fn active(&self) -> bool {
+        self.value.is_active()
+    }
+    /// The action became active this frame.
+    pub fn started(&self) -> bool {
+        self.value.is_active() && !self.previous_value.is_active()
+    }
+    /// The action was active last frame and still is.
+    pub fn ongoing(&self) -> bool {
+        self.value.is_active() && self.previous_value.is_active()
+    }
+    /// The action stopped being active this frame.
+    pub fn completed(&self) -> bool {
+        !self.value.is_active() && self.previous_value.is_active()
+    }
+    pub fn changed(&self) -> bool {
+        self.value != self.previous_value
+    }
+
+    fn update(&mut self, value: T, delta: Duration, tick: Tick) {
+        self.previous_value = self.value;
+        self.value = value;
+        if self.changed() {
+            self.last_changed = tick;
+        }
+        match (self.previous_value.is_active(), self.value.is_active()) {
+            (false, true) => self.held_for = Duration::ZERO,
+            (true, true) => self.held_for += delta,
+            (true, false) => self.released_for = Duration::ZERO,
+            (false, false) => self.released_for += delta,
+        }
+    }
+}
+
+/// Also restores the change tick of values that ended up the same as last frame, so
+/// `Changed<BoolActionValue>` etc. only match values that actually changed.
+fn update_states<
+    T: ActionValueType,
+    V: Component<Mutability = Mutable> + Deref<Target = SubactionPathMap<T>>,
+>(
+    mut query: Query<(&mut V, &mut ActionState<T>)>,
+    time: Res<Time>,
+    ticks: SystemChangeTick,
+) {
+    let delta = time.delta();
+    let tick = ticks.this_run();
+    for (mut value, mut state) in &mut query {
+        state.any.update(value.any, delta, tick);
+        for (path, path_value) in value.paths.iter() {
+            state
+                .entry_with_path(*path)
+                .or_default()
+                .update(*path_value, delta, tick);
+        }
+        // paths that didn't get a value this frame fall back to the default value
+        for (path, path_state) in state.paths.iter_mut() {
+            if !value.paths.contains_key(path) {
+                path_state.update(T::default(), delta, tick);
+            }
+        }
+        if state.any.changed() || state.changed_paths().next().is_some() {
+            continue;
+        }
+        // the value was cleared and written again this frame, go back to when it last changed,
+        // values that never changed since they were added stay changed from when they were added
+        let last_changed = state
+            .paths
+            .values()
+            .map(|path_state| path_state.last_changed)
+            .chain([state.any.last_changed])
+            .fold(value.added(), |newest, last_changed| {
+                match last_changed.is_newer_than(newest, tick) {
+                    true => last_changed,
+                    false => newest,
+                }
+            });
+        value.set_last_changed(last_changed);
+    }
+}
@@ -0,0 +1,249 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    SchminputSystems,
+    impl_helpers::BindingValue,
+    subaction_paths::{SubactionPath, SubactionPathMap},
+};
+
+pub struct AggregationPlugin;
+
+impl Plugin for AggregationPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ValueAggregation>();
+        app.add_systems(
+            PreUpdate,
+            clear_aggregation_state.in_set(SchminputSystems::ClearValues),
+        );
+    }
+}
+
+fn clear_aggregation_state(mut query: Query<&mut AggregationState>) {
+    for mut state in &mut query {
+        state.counts.any = 0;
+        state.counts.paths.clear();
+        state.sums.any = BindingValue::default();
+        state.sums.paths.clear();
+        state.latest.any.seen.clear();
+        for latest in state.latest.paths.values_mut() {
+            latest.seen.clear();
+        }
+    }
+}
+
+/// How the values of multiple input sources are combined into the value of an action.
+///
+/// Bindings from the same source (e.g. the keyboard) are always summed first,
+/// so that dpad style bindings and separate stick axes compose into one value,
+/// the aggregation is then applied between sources (keyboard, mouse, gamepad, OpenXR, ...).
+/// Bool values are always or'ed together, except for [`ValueAggregation::Latest`].
+/// The OpenXR runtime combines the bindings of an action with the longest vector and
+/// largest float, as the spec requires, before they are aggregated with the other sources.
+///
+/// The default is [`ValueAggregation::Sum`], the same behavior as before aggregation modes
+/// existed, so e.g. a keyboard dpad and a gamepad stick used at the same time can add up to
+/// a vector longer than 1. Insert [`ValueAggregation::ClampedSum`] or
+/// [`ValueAggregation::LongestVector`] on the action to prevent that.
+#[derive(Clone, Copy, Debug, Reflect, Default, PartialEq, Eq, Hash, Component)]
+#[require(AggregationState)]
+pub enum ValueAggregation {
+    /// Add all values together.
+    #[default]
+    Sum,
+    /// Add all values together, then clamp floats to -1..=1 and vectors to a length of 1.
+    ClampedSum,
+    /// Pick the float with the largest absolute value, vectors are handled per axis.
+    MaxMagnitude,
+    /// Pick the longest vector and the float with the largest absolute value.
+    LongestVector,
+    /// Average the values of all sources.
+    Average,
+    /// Use the value of the source that changed last, out of the ones with a value this frame.
+    Latest,
+}
+
+impl ValueAggregation {
+    /// Combines the value of one more source, [`ValueAggregation::ClampedSum`] expects
+    /// the unclamped sum as `current`, see [`ValueAggregation::finish_f32`].
+    pub fn combine_f32(&self, current: f32, new: f32, count: u32) -> f32 {
+        match self {
+            ValueAggregation::Sum | ValueAggregation::ClampedSum => current + new,
+            ValueAggregation::MaxMagnitude | ValueAggregation::LongestVector => {
+                max_magnitude(current, new)
+            }
+            ValueAggregation::Average => (current * count as f32 + new) / (count + 1) as f32,
+            ValueAggregation::Latest => new,
+        }
+    }
+    pub fn combine_vec2(&self, current: Vec2, new: Vec2, count: u32) -> Vec2 {
+        match self {
+            ValueAggregation::Sum | ValueAggregation::ClampedSum => current + new,
+            ValueAggregation::MaxMagnitude => Vec2::new(
+                max_magnitude(current.x, new.x),
+                max_magnitude(current.y, new.y),
+            ),
+            ValueAggregation::LongestVector => {
+                if new.length_squared() > current.length_squared() {
+                    new
+                } else {
+                    current
+                }
+            }
+            ValueAggregation::Average => (current * count as f32 + new) / (count + 1) as f32,
+            ValueAggregation::Latest => new,
+        }
+    }
+    pub fn combine_bool(&self, current: bool, new: bool) -> bool {
+        match self {
+            ValueAggregation::Latest => new,
+            _ => current | new,
+        }
+    }
+    /// Turns the combined value of all sources so far into the action value.
+    pub fn finish_f32(&self, value: f32) -> f32 {
+        match self {
+            ValueAggregation::ClampedSum => value.clamp(-1.0, 1.0),
+            _ => value,
+        }
+    }
+    pub fn finish_vec2(&self, value: Vec2) -> Vec2 {
+        match self {
+            ValueAggregation::ClampedSum => value.clamp_length_max(1.0),
+            _ => value,
+        }
+    }
+}
+
+fn max_magnitude(current: f32, new: f32) -> f32 {
+    if new.abs() > current.abs() {
+        new
+    } else {
+        current
+    }
+}
+
+/// Per action bookkeeping needed by [`ValueAggregation::Average`],
+/// [`ValueAggregation::ClampedSum`] and [`ValueAggregation::Latest`].
+#[derive(Clone, Debug, Default, Component)]
+pub struct AggregationState {
+    counts: SubactionPathMap<u32>,
+    /// the unclamped sums for [`ValueAggregation::ClampedSum`]
+    sums: SubactionPathMap<BindingValue>,
+    latest: SubactionPathMap<LatestSource>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct LatestSource {
+    /// the last value of every source and when it last changed
+    last_values: HashMap<u64, (BindingValue, u64)>,
+    changes: u64,
+    /// the sources with a value this frame
+    seen: Vec<u64>,
+}
+
+impl AggregationState {
+    /// returns how many sources already contributed to the value this frame and counts this one
+    fn next_count(&mut self, path: Option<SubactionPath>) -> u32 {
+        let count = match path {
+            Some(path) => self.counts.entry_with_path(path).or_default(),
+            None => &mut self.counts.any,
+        };
+        let out = *count;
+        *count += 1;
+        out
+    }
+    /// returns the value of the source that changed last out of the ones seen this frame
+    fn update_latest(
+        &mut self,
+        path: Option<SubactionPath>,
+        source: u64,
+        value: BindingValue,
+    ) -> BindingValue {
+        let latest = match path {
+            Some(path) => self.latest.entry_with_path(path).or_default(),
+            None => &mut self.latest.any,
+        };
+        if latest
+            .last_values
+            .get(&source)
+            .is_none_or(|(last, _)| *last != value)
+        {
+            latest.changes += 1;
+            latest.last_values.insert(source, (value, latest.changes));
+        }
+        if !latest.seen.contains(&source) {
+            latest.seen.push(source);
+        }
+        latest
+            .seen
+            .iter()
+            .filter_map(|source| latest.last_values.get(source))
+            .max_by_key(|(_, changed)| *changed)
+            .map_or(value, |(value, _)| *value)
+    }
+    /// adds the value to the unclamped sum and returns the sum
+    fn update_sum(&mut self, path: Option<SubactionPath>, value: BindingValue) -> BindingValue {
+        let sum = match path {
+            Some(path) => self.sums.entry_with_path(path).or_default(),
+            None => &mut self.sums.any,
+        };
+        if let Some(v) = value.f32 {
+            *sum.f32.get_or_insert_default() += v;
+        }
+        if let Some(v) = value.vec2 {
+            *sum.vec2.get_or_insert_default() += v;
+        }
+        *sum
+    }
+}
+
+/// Combines the already composed value of one source into the action values.
+pub(crate) fn aggregate_value(
+    source: u64,
+    sub_path: Option<SubactionPath>,
+    value: BindingValue,
+    aggregation: ValueAggregation,
+    state: &mut AggregationState,
+    vec2: Option<&mut Mut<'_, crate::Vec2ActionValue>>,
+    f32: Option<&mut Mut<'_, crate::F32ActionValue>>,
+    bool: Option<&mut Mut<'_, crate::BoolActionValue>>,
+) {
+    let count = state.next_count(sub_path);
+    let mut value = value;
+    if aggregation == ValueAggregation::Latest {
+        value = state.update_latest(sub_path, source, value);
+    }
+    // the value of the action is the clamped sum of all sources so far
+    let sum = match aggregation == ValueAggregation::ClampedSum {
+        true => Some(state.update_sum(sub_path, value)),
+        false => None,
+    };
+
+    if let Some(vec2) = vec2 {
+        let v = match sub_path {
+            Some(path) => vec2.entry_with_path(path).or_default(),
+            None => &mut vec2.0.any,
+        };
+        *v = match sum {
+            Some(sum) => aggregation.finish_vec2(sum.vec2.unwrap_or_default()),
+            None => aggregation.combine_vec2(*v, value.vec2.unwrap_or_default(), count),
+        };
+    }
+    if let Some(f32) = f32 {
+        let v = match sub_path {
+            Some(path) => f32.entry_with_path(path).or_default(),
+            None => &mut f32.0.any,
+        };
+        *v = match sum {
+            Some(sum) => aggregation.finish_f32(sum.f32.unwrap_or_default()),
+            None => aggregation.combine_f32(*v, value.f32.unwrap_or_default(), count),
+        };
+    }
+    if let Some(bool) = bool {
+        let v = match sub_path {
+            Some(path) => bool.entry_with_path(path).or_default(),
+            None => &mut bool.0.any,
+        };
+        *v = aggregation.combine_bool(*v, value.bool.unwrap_or_default());
+    }
+}
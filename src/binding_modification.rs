@@ -21,6 +21,13 @@ impl BindingModifications {
         self.per_path
             .push((path, BindingModifiaction(modification)));
     }
+    pub fn with_all_paths_modification(mut self, modification: Entity) -> Self {
+        self.all_paths_modification(modification);
+        self
+    }
+    pub fn all_paths_modification(&mut self, modification: Entity) {
+        self.all_paths = Some(BindingModifiaction(modification));
+    }
     pub fn new() -> Self {
         Self::default()
     }
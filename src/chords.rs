@@ -0,0 +1,508 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    mem,
+};
+
+use bevy::{ecs::system::SystemChangeTick, platform::collections::HashSet, prelude::*};
+
+use crate::{
+    Action, ActionSet, ButtonInputBeheavior, InputAxis, InputAxisDirection, SchminputSystems,
+    contexts::ContextLayer,
+    debug::BlockingAction,
+    gamepad::{GamepadBindingSource, source_binding_id},
+    impl_helpers::{BindingValue, ProviderParam, label_id},
+    interactions::{BindingInteractions, interaction_key},
+    keyboard::key_binding_id,
+    mouse::button_binding_id,
+    players::{OwnedByPlayer, PlayerDevices, allows_gamepad, allows_keyboard, allows_mouse},
+    priorities::{self, BlockedInputs, ConsumedInputs, EffectivePriority, PriorityAppExt as _},
+    subaction_paths::{SubactionPathCreated, SubactionPathStr},
+};
+
+pub struct ChordPlugin;
+
+impl Plugin for ChordPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HeldChords>();
+        app.add_systems(
+            PreUpdate,
+            sync_actions.in_set(SchminputSystems::SyncInputActions),
+        );
+        app.add_systems(
+            PreUpdate,
+            handle_new_subaction_paths.in_set(SchminputSystems::HandleNewSubactionPaths),
+        );
+        app.add_systems(
+            PreUpdate,
+            (update_held_chords, consume_chord_inputs)
+                .chain()
+                .in_set(SchminputSystems::CalculateBindingCollisions)
+                .after(priorities::system),
+        );
+        app.add_tracked_binding_id_system::<ChordBindings, _>(
+            "schminput:chord",
+            |entity: In<Entity>, query: Query<&ChordBindings>| {
+                let Ok(bindings) = query.get(entity.0) else {
+                    return Vec::new();
+                };
+                bindings.0.iter().map(get_binding_id).collect()
+            },
+        );
+        app.add_binding_description_system(
+            "schminput:chord",
+            |entity: In<Entity>, query: Query<&ChordBindings>| {
+                let Ok(bindings) = query.get(entity.0) else {
+                    return Vec::new();
+                };
+                bindings
+                    .0
+                    .iter()
+                    .map(|binding| {
+                        let members = binding
+                            .members()
+                            .map(|input| input.describe())
+                            .collect::<Vec<_>>();
+                        (
+                            get_binding_id(binding),
+                            format!("chord:{}", members.join("+")),
+                        )
+                    })
+                    .collect()
+            },
+        );
+    }
+}
+
+fn get_binding_id(binding: &ChordBinding) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    binding.modifiers.hash(&mut hasher);
+    binding.input.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn handle_new_subaction_paths(
+    query: Query<&SubactionPathStr>,
+    mut reader: MessageReader<SubactionPathCreated>,
+    mut cmds: Commands,
+) {
+    for (e, str) in reader
+        .read()
+        .filter_map(|e| Some((e.0.0, query.get(e.0.0).ok()?)))
+    {
+        if str.0.strip_prefix("/chord").is_some() {
+            cmds.entity(e).insert(ChordSubactionPath);
+        }
+    }
+}
+
+/// The chords that are held on a gamepad, or without one for chords without gamepad inputs,
+/// a chord is only held if its modifiers were held before the input was pressed.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct HeldChords(HashSet<(u64, Option<Entity>)>);
+
+impl HeldChords {
+    /// is the chord held on the gamepad and can the player use all of its inputs
+    fn is_held(
+        &self,
+        binding: &ChordBinding,
+        gamepad: Option<Entity>,
+        devices: Option<&PlayerDevices>,
+    ) -> bool {
+        binding.members().all(|input| allowed(input, devices))
+            && self.0.contains(&(get_binding_id(binding), gamepad))
+    }
+}
+
+fn update_held_chords(
+    query: Query<&ChordBindings>,
+    inputs: ChordInputs,
+    mut held: ResMut<HeldChords>,
+) {
+    let previous = mem::take(&mut held.0);
+    for binding in query.iter().flat_map(|bindings| bindings.0.iter()) {
+        inputs.for_each_device(binding, None, |device| {
+            let key = (get_binding_id(binding), device.map(|(e, _)| e));
+            let gamepad = device.map(|(_, g)| g);
+            let pressed_in_order = inputs.just_pressed(binding.input, gamepad)
+                && !binding
+                    .modifiers
+                    .iter()
+                    .any(|modifier| inputs.just_pressed(*modifier, gamepad));
+            if binding
+                .members()
+                .all(|input| inputs.pressed(input, gamepad))
+                && (previous.contains(&key) || pressed_in_order)
+            {
+                held.0.insert(key);
+            }
+        });
+    }
+}
+
+/// The raw button state of every input a chord can use.
+#[derive(bevy::ecs::system::SystemParam)]
+pub(crate) struct ChordInputs<'w, 's> {
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    gamepads: Query<'w, 's, (Entity, &'static Gamepad)>,
+}
+
+impl ChordInputs<'_, '_> {
+    fn pressed(&self, input: ChordInput, gamepad: Option<&Gamepad>) -> bool {
+        match input {
+            ChordInput::Key(key) => self.keyboard.pressed(key),
+            ChordInput::MouseButton(button) => self.mouse.pressed(button),
+            ChordInput::GamepadButton(button) => gamepad.is_some_and(|g| g.pressed(button)),
+        }
+    }
+    fn just_pressed(&self, input: ChordInput, gamepad: Option<&Gamepad>) -> bool {
+        match input {
+            ChordInput::Key(key) => self.keyboard.just_pressed(key),
+            ChordInput::MouseButton(button) => self.mouse.just_pressed(button),
+            ChordInput::GamepadButton(button) => gamepad.is_some_and(|g| g.just_pressed(button)),
+        }
+    }
+    /// the gamepads the player owns, all of them without a player
+    fn gamepads(
+        &self,
+        devices: Option<&PlayerDevices>,
+    ) -> impl Iterator<Item = (Entity, &Gamepad)> {
+        self.gamepads
+            .iter()
+            .filter(move |(entity, _)| allows_gamepad(devices, *entity))
+    }
+    /// is the input pressed on any device of the player
+    pub(crate) fn any_pressed(&self, input: ChordInput, devices: Option<&PlayerDevices>) -> bool {
+        match input {
+            ChordInput::GamepadButton(button) => {
+                self.gamepads(devices).any(|(_, g)| g.pressed(button))
+            }
+            _ => allowed(input, devices) && self.pressed(input, None),
+        }
+    }
+    /// was the input pressed this frame on any device of the player
+    pub(crate) fn any_just_pressed(
+        &self,
+        input: ChordInput,
+        devices: Option<&PlayerDevices>,
+    ) -> bool {
+        match input {
+            ChordInput::GamepadButton(button) => {
+                self.gamepads(devices).any(|(_, g)| g.just_pressed(button))
+            }
+            _ if !allowed(input, devices) => false,
+            ChordInput::Key(key) => self.keyboard.just_pressed(key),
+            ChordInput::MouseButton(button) => self.mouse.just_pressed(button),
+        }
+    }
+    /// The keys and gamepad buttons pressed this frame on the devices of the player.
+    pub(crate) fn just_pressed_keys_and_buttons(
+        &self,
+        devices: Option<&PlayerDevices>,
+    ) -> impl Iterator<Item = ChordInput> {
+        let keys = allows_keyboard(devices)
+            .then(|| {
+                self.keyboard
+                    .get_just_pressed()
+                    .copied()
+                    .map(ChordInput::Key)
+            })
+            .into_iter()
+            .flatten();
+        let buttons = self.gamepads(devices).flat_map(|(_, gamepad)| {
+            gamepad
+                .digital()
+                .get_just_pressed()
+                .copied()
+                .map(ChordInput::GamepadButton)
+        });
+        keys.chain(buttons)
+    }
+    /// Calls `f` for every device of the player the chord could be pressed on,
+    /// chords with gamepad inputs need all of them to be on the same gamepad.
+    fn for_each_device(
+        &self,
+        binding: &ChordBinding,
+        devices: Option<&PlayerDevices>,
+        mut f: impl FnMut(Option<(Entity, &Gamepad)>),
+    ) {
+        if binding
+            .members()
+            .any(|input| matches!(input, ChordInput::GamepadButton(_)))
+        {
+            for gamepad in self.gamepads(devices) {
+                f(Some(gamepad));
+            }
+        } else {
+            f(None);
+        }
+    }
+}
+
+/// can the player use the keyboard or mouse input, gamepads are checked per gamepad
+fn allowed(input: ChordInput, devices: Option<&PlayerDevices>) -> bool {
+    match input {
+        ChordInput::Key(_) => allows_keyboard(devices),
+        ChordInput::MouseButton(_) => allows_mouse(devices),
+        ChordInput::GamepadButton(_) => true,
+    }
+}
+
+fn is_blocked(blocked: Option<&BlockedInputs>, binding: &ChordBinding) -> bool {
+    blocked.is_some_and(|blocked| {
+        binding
+            .members()
+            .any(|input| blocked.is_blocked(input.label_id(), input.binding_id()))
+    })
+}
+
+/// Held chords use up their inputs for lower priority action sets,
+/// and their main input for every set when blocking plain bindings.
+fn consume_chord_inputs(
+    query: Query<(Entity, &Action, &ChordBindings)>,
+    set_query: Query<(
+        &ActionSet,
+        Option<&BlockedInputs>,
+        Option<&OwnedByPlayer>,
+        Option<&ContextLayer>,
+    )>,
+    player_query: Query<&PlayerDevices>,
+    inputs: ChordInputs,
+    held: Res<HeldChords>,
+    mut consumed: ResMut<ConsumedInputs>,
+) {
+    for (entity, action, bindings) in &query {
+        let Ok((set, blocked, owner, layer)) = set_query.get(action.set) else {
+            continue;
+        };
+        if !set.enabled {
+            continue;
+        }
+        let devices = match owner {
+            Some(owner) => match player_query.get(owner.0) {
+                Ok(devices) => Some(devices),
+                Err(_) => continue,
+            },
+            None => None,
+        };
+        let priority = EffectivePriority::of(set, layer);
+        let by = BlockingAction {
+            set: action.set,
+            action: entity,
+        };
+        for binding in bindings.0.iter() {
+            if is_blocked(blocked, binding) {
+                continue;
+            }
+            let mut pressed = false;
+            inputs.for_each_device(binding, devices, |device| {
+                pressed |= held.is_held(binding, device.map(|(e, _)| e), devices);
+            });
+            if !pressed {
+                continue;
+            }
+            for input in binding.members() {
+                consumed.consume_below(
+                    input.label_id(),
+                    input.binding_id(),
+                    priority,
+                    owner.map(|owner| owner.0),
+                    by,
+                );
+            }
+            if binding.block_plain {
+                consumed.consume(binding.input.label_id(), binding.input.binding_id(), by);
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn sync_actions(
+    mut query: ProviderParam<&ChordBindings, &mut BindingInteractions, Has<ChordSubactionPath>>,
+    inputs: ChordInputs,
+    held: Res<HeldChords>,
+    time: Res<Time>,
+    ticks: SystemChangeTick,
+) {
+    query.run_with_deltas(
+        "schminput:chord",
+        |binding| binding.behavior.is_momentary(),
+        get_binding_id,
+        |_, v| *v,
+        |_| false,
+        |bindings, visit| bindings.0.iter().for_each(visit),
+        |binding, interactions, _, data, out| {
+            if is_blocked(data.blocked_inputs, binding) {
+                return;
+            }
+            let delta_multiplier = data.modifications.delta_multiplier();
+            inputs.for_each_device(binding, data.devices, |device| {
+                let active = interactions.evaluate(
+                    interaction_key(
+                        get_binding_id(binding),
+                        binding.behavior,
+                        device.map(|(e, _)| e),
+                    ),
+                    binding.behavior,
+                    held.is_held(binding, device.map(|(e, _)| e), data.devices),
+                    &time,
+                    ticks.this_run(),
+                );
+                let val = active as u8 as f32 * binding.axis_dir.as_multipier() * delta_multiplier;
+                out.push(BindingValue {
+                    bool: data.is_bool.then_some(active),
+                    f32: data.is_f32.then_some(val),
+                    vec2: data.is_vec2.then(|| binding.axis.new_vec(val)),
+                });
+            });
+        },
+    );
+}
+
+#[derive(Clone, Copy, Debug, Reflect, Component)]
+pub struct ChordSubactionPath;
+
+/// A single button that can be part of a chord.
+#[derive(Clone, Copy, Debug, Reflect, PartialEq, Eq, Hash)]
+pub enum ChordInput {
+    Key(KeyCode),
+    MouseButton(MouseButton),
+    GamepadButton(GamepadButton),
+}
+
+impl ChordInput {
+    /// The provider label that this input would use as a normal binding.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChordInput::Key(_) => "schminput:keyboard",
+            ChordInput::MouseButton(_) => "schminput:mouse",
+            ChordInput::GamepadButton(_) => "schminput:gamepad",
+        }
+    }
+    pub(crate) fn label_id(&self) -> u64 {
+        label_id(self.label())
+    }
+    /// A readable description like `keyboard:KeyW`.
+    pub fn describe(&self) -> String {
+        match self {
+            ChordInput::Key(key) => format!("keyboard:{key:?}"),
+            ChordInput::MouseButton(button) => format!("mouse:{button:?}"),
+            ChordInput::GamepadButton(button) => format!("gamepad:{button:?}"),
+        }
+    }
+    /// The binding id that this input would have as a normal binding.
+    pub(crate) fn binding_id(&self) -> u64 {
+        match *self {
+            ChordInput::Key(key) => key_binding_id(key),
+            ChordInput::MouseButton(button) => button_binding_id(button),
+            ChordInput::GamepadButton(button) => {
+                source_binding_id(GamepadBindingSource::from_button(&button))
+            }
+        }
+    }
+}
+
+impl From<KeyCode> for ChordInput {
+    fn from(value: KeyCode) -> Self {
+        ChordInput::Key(value)
+    }
+}
+impl From<MouseButton> for ChordInput {
+    fn from(value: MouseButton) -> Self {
+        ChordInput::MouseButton(value)
+    }
+}
+impl From<GamepadButton> for ChordInput {
+    fn from(value: GamepadButton) -> Self {
+        ChordInput::GamepadButton(value)
+    }
+}
+
+#[derive(Clone, Debug, Default, Component, Reflect)]
+#[require(BindingInteractions)]
+pub struct ChordBindings(pub Vec<ChordBinding>);
+
+impl ChordBindings {
+    pub fn bind(mut self, binding: ChordBinding) -> Self {
+        self.0.push(binding);
+        self
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Fires when `input` is activated while all `modifiers` are held, e.g. Ctrl+S, the modifiers
+/// have to be pressed before the input. While it is held its inputs are ignored in action
+/// sets with a lower priority.
+/// The inputs can be on different devices, gamepad inputs have to be on the same gamepad.
+#[derive(Clone, Debug, Reflect, PartialEq, Eq, Hash)]
+pub struct ChordBinding {
+    pub modifiers: Vec<ChordInput>,
+    pub input: ChordInput,
+    pub behavior: ButtonInputBeheavior,
+    /// While the chord is held, bindings that only use `input` are ignored in every action set.
+    pub block_plain: bool,
+    pub axis: InputAxis,
+    pub axis_dir: InputAxisDirection,
+}
+
+impl ChordBinding {
+    pub fn new(input: impl Into<ChordInput>) -> ChordBinding {
+        ChordBinding {
+            modifiers: Vec::new(),
+            input: input.into(),
+            behavior: default(),
+            block_plain: false,
+            axis: default(),
+            axis_dir: default(),
+        }
+    }
+
+    pub fn modifier(mut self, modifier: impl Into<ChordInput>) -> Self {
+        self.modifiers.push(modifier.into());
+        self
+    }
+
+    pub fn block_plain(mut self) -> Self {
+        self.block_plain = true;
+        self
+    }
+
+    pub fn behavior(mut self, behavior: ButtonInputBeheavior) -> Self {
+        self.behavior = behavior;
+        self
+    }
+
+    pub fn just_pressed(mut self) -> Self {
+        self.behavior = ButtonInputBeheavior::JustPressed;
+        self
+    }
+
+    pub fn x_axis(mut self) -> Self {
+        self.axis = InputAxis::X;
+        self
+    }
+
+    pub fn y_axis(mut self) -> Self {
+        self.axis = InputAxis::Y;
+        self
+    }
+
+    pub fn positive_axis_dir(mut self) -> Self {
+        self.axis_dir = InputAxisDirection::Positive;
+        self
+    }
+
+    pub fn negative_axis_dir(mut self) -> Self {
+        self.axis_dir = InputAxisDirection::Negative;
+        self
+    }
+
+    /// The modifiers followed by the input.
+    pub fn members(&self) -> impl Iterator<Item = ChordInput> + '_ {
+        self.modifiers.iter().copied().chain([self.input])
+    }
+}
@@ -0,0 +1,156 @@
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+
+use crate::ActionSet;
+
+/// Adds the [`ContextStack`], contexts are pushed and popped through [`ContextCommandsExt`].
+pub struct ContextPlugin;
+
+impl Plugin for ContextPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ContextStack>();
+        app.register_type::<ContextLayer>();
+        app.add_message::<ContextPushed>();
+        app.add_message::<ContextPopped>();
+    }
+}
+
+/// What happens to the action sets below a context while it is on top of them.
+#[derive(Clone, Copy, Debug, Default, Reflect, PartialEq, Eq, Hash)]
+pub enum ContextPolicy {
+    /// The sets below are disabled until the context is popped.
+    #[default]
+    Disable,
+    /// The sets below stay enabled, but inputs used by the sets of the context are blocked
+    /// for them no matter their priority. The OpenXR runtime can't block single inputs,
+    /// so OpenXR action sets below an OpenXR set of the context aren't synced at all.
+    Shadow,
+}
+
+/// A named group of [`ActionSet`]s that are enabled while the context is on the stack,
+/// popping it restores the enabled state and [`ContextLayer`] the sets had before the push.
+#[derive(Clone, Debug)]
+pub struct InputContext {
+    pub name: Cow<'static, str>,
+    pub sets: Vec<Entity>,
+    pub policy: ContextPolicy,
+}
+
+impl InputContext {
+    pub fn new(name: impl Into<Cow<'static, str>>, policy: ContextPolicy) -> Self {
+        Self {
+            name: name.into(),
+            sets: Vec::new(),
+            policy,
+        }
+    }
+    pub fn with_set(mut self, set: Entity) -> Self {
+        self.sets.push(set);
+        self
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ContextEntry {
+    context: InputContext,
+    /// the enabled state and layer of the sets this context changed, restored when it is popped
+    previous: Vec<(Entity, bool, Option<ContextLayer>)>,
+}
+
+/// The pushed contexts, the last one is on top.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ContextStack(Vec<ContextEntry>);
+
+impl ContextStack {
+    pub fn top(&self) -> Option<&InputContext> {
+        self.0.last().map(|entry| &entry.context)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &InputContext> {
+        self.0.iter().map(|entry| &entry.context)
+    }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    pub fn contains(&self, name: &str) -> bool {
+        self.iter().any(|context| context.name == name)
+    }
+}
+
+/// The sets of contexts with [`ContextPolicy::Shadow`] block sets with a lower layer,
+/// before their priorities are compared.
+#[derive(Clone, Copy, Debug, Component, Reflect, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ContextLayer(pub u32);
+
+#[derive(Clone, Debug, Message)]
+pub struct ContextPushed(pub Cow<'static, str>);
+
+#[derive(Clone, Debug, Message)]
+pub struct ContextPopped(pub Cow<'static, str>);
+
+pub trait ContextCommandsExt {
+    fn push_context(&mut self, context: InputContext);
+    /// Pops the context on top of the stack.
+    fn pop_context(&mut self);
+}
+
+impl ContextCommandsExt for Commands<'_, '_> {
+    fn push_context(&mut self, context: InputContext) {
+        self.queue(move |world: &mut World| push_context(world, context));
+    }
+    fn pop_context(&mut self) {
+        self.queue(pop_context);
+    }
+}
+
+fn push_context(world: &mut World, context: InputContext) {
+    let layer = world.resource::<ContextStack>().len() as u32 + 1;
+    let mut previous = Vec::new();
+    let mut query = world.query::<(Entity, &mut ActionSet, Option<&ContextLayer>)>();
+    for (entity, mut set, set_layer) in query.iter_mut(world) {
+        let in_context = context.sets.contains(&entity);
+        if in_context
+            || (context.policy == ContextPolicy::Disable && set.enabled && !set.transparent)
+        {
+            previous.push((entity, set.enabled, set_layer.copied()));
+            set.enabled = in_context;
+        }
+    }
+    if context.policy == ContextPolicy::Shadow {
+        for set in context.sets.iter() {
+            if let Ok(mut entity) = world.get_entity_mut(*set) {
+                entity.insert(ContextLayer(layer));
+            }
+        }
+    }
+    world.write_message(ContextPushed(context.name.clone()));
+    world
+        .resource_mut::<ContextStack>()
+        .0
+        .push(ContextEntry { context, previous });
+}
+
+fn pop_context(world: &mut World) {
+    let Some(ContextEntry { context, previous }) = world.resource_mut::<ContextStack>().0.pop()
+    else {
+        warn!("tried to pop a context from an empty context stack");
+        return;
+    };
+    // only the top context is popped, so this is the state the contexts below left behind
+    for (set, enabled, layer) in previous {
+        let Ok(mut entity) = world.get_entity_mut(set) else {
+            continue;
+        };
+        if let Some(mut set) = entity.get_mut::<ActionSet>() {
+            set.enabled = enabled;
+        }
+        match layer {
+            Some(layer) => entity.insert(layer),
+            None => entity.remove::<ContextLayer>(),
+        };
+    }
+    world.write_message(ContextPopped(context.name));
+}
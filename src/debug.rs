@@ -0,0 +1,483 @@
+use std::fmt::{Display, Write as _};
+
+use bevy::{ecs::entity::EntityHashMap, platform::collections::HashMap, prelude::*};
+
+use crate::{
+    Action, ActionSet, ActionsInSet, BoolActionValue, F32ActionValue, SchminputSystems,
+    Vec2ActionValue,
+    contexts::ContextLayer,
+    players::OwnedByPlayer,
+    priorities::{
+        BindingIdSystems, BlockedInputs, BlockingMode, ConsumedInputs, EffectivePriority, blocks,
+        priority_groups,
+    },
+    subaction_paths::{RequestedSubactionPaths, SubactionPath, SubactionPathMap, SubactionPathStr},
+    validation::action_names,
+};
+
+/// Logs which bindings get blocked while [`LogBlockedBindings`] is set.
+pub struct BlockingDebugPlugin;
+
+impl Plugin for BlockingDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LogBlockedBindings>();
+        app.add_systems(
+            PreUpdate,
+            log_blocked_bindings
+                .after(SchminputSystems::SyncInputActions)
+                .run_if(|log: Res<LogBlockedBindings>| log.0),
+        );
+    }
+}
+
+/// Logs the [`ActionDebugInfo`] of all action sets while [`LogActionValues`] is set,
+/// used for headless runs, [`schminput_rebinding`](https://docs.rs/schminput_rebinding)
+/// can draw the same info with egui.
+pub struct ActionDebugPlugin;
+
+impl Plugin for ActionDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LogActionValues>();
+        app.add_systems(
+            PreUpdate,
+            clear_contributing_bindings.in_set(SchminputSystems::ClearValues),
+        );
+        app.add_systems(
+            PreUpdate,
+            (
+                track_contributing_bindings.before(SchminputSystems::ClearValues),
+                log_action_values.after(SchminputSystems::UpdateActionStates),
+            )
+                .run_if(|log: Res<LogActionValues>| log.0),
+        );
+    }
+}
+
+/// Logs the values of all actions every frame they change, also tracks the
+/// [`ContributingBindings`] of all actions.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct LogActionValues(pub bool);
+
+/// The bindings that had a non zero value this frame, only tracked for actions with this
+/// component and for providers using [`ProviderParam`](crate::impl_helpers::ProviderParam).
+#[derive(Component, Clone, Debug, Default)]
+pub struct ContributingBindings(Vec<(u64, u64)>);
+
+impl ContributingBindings {
+    pub(crate) fn push(&mut self, label_id: u64, binding_id: u64) {
+        if !self.contains(label_id, binding_id) {
+            self.0.push((label_id, binding_id));
+        }
+    }
+    pub fn contains(&self, label_id: u64, binding_id: u64) -> bool {
+        self.0.contains(&(label_id, binding_id))
+    }
+    /// The label and binding ids.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+/// Adds [`ContributingBindings`] to all actions that don't have it yet,
+/// add this to your app when showing the [`ActionDebugInfo`] yourself.
+pub fn track_contributing_bindings(
+    query: Query<Entity, (With<Action>, Without<ContributingBindings>)>,
+    mut cmds: Commands,
+) {
+    for action in &query {
+        cmds.entity(action).insert(ContributingBindings::default());
+    }
+}
+
+fn clear_contributing_bindings(mut query: Query<&mut ContributingBindings>) {
+    for mut contributing in &mut query {
+        contributing.0.clear();
+    }
+}
+
+/// The state of an [`ActionSet`] and its actions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActionSetDebugInfo {
+    pub set: Entity,
+    pub name: String,
+    pub enabled: bool,
+    pub priority: u32,
+    pub transparent: bool,
+    pub actions: Vec<ActionDebugInfo>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActionDebugInfo {
+    pub action: Entity,
+    pub name: String,
+    /// The value for `any` first, followed by the values of the requested subaction paths.
+    pub values: Vec<PathDebugValue>,
+    /// The descriptions of the [`ContributingBindings`], e.g. `keyboard:KeyW`.
+    pub contributing: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathDebugValue {
+    /// [`None`] for the `any` value.
+    pub path: Option<String>,
+    pub value: DebugValue,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DebugValue {
+    Bool(bool),
+    F32(f32),
+    Vec2(Vec2),
+}
+
+impl Display for DebugValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DebugValue::Bool(v) => write!(f, "{v}"),
+            DebugValue::F32(v) => write!(f, "{v:.3}"),
+            DebugValue::Vec2(v) => write!(f, "({:.3}, {:.3})", v.x, v.y),
+        }
+    }
+}
+
+impl Display for ActionSetDebugInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (priority {}", self.name, self.priority)?;
+        if !self.enabled {
+            f.write_str(", disabled")?;
+        }
+        if self.transparent {
+            f.write_str(", transparent")?;
+        }
+        f.write_str(")")?;
+        for action in self.actions.iter() {
+            write!(f, "\n  {}:", action.name)?;
+            for value in action.values.iter() {
+                let path = value.path.as_deref().unwrap_or("any");
+                write!(f, " {path}={}", value.value)?;
+            }
+            if !action.contributing.is_empty() {
+                write!(f, " [{}]", action.contributing.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Collects the state of all action sets, highest priority first.
+pub fn action_debug_info(world: &mut World) -> Vec<ActionSetDebugInfo> {
+    let binding_id_systems = world.remove_resource::<BindingIdSystems>();
+    let mut sets = world
+        .query::<(Entity, &ActionSet, Option<&ActionsInSet>)>()
+        .iter(world)
+        .map(|(set, info, actions)| {
+            let mut actions = actions
+                .map(|actions| actions.iter().collect::<Vec<_>>())
+                .unwrap_or_default();
+            actions.sort_by_key(|action| world.get::<Action>(*action).map(|a| a.name.clone()));
+            (
+                ActionSetDebugInfo {
+                    set,
+                    name: info.name.to_string(),
+                    enabled: info.enabled,
+                    priority: info.priority,
+                    transparent: info.transparent,
+                    actions: Vec::new(),
+                },
+                actions,
+            )
+        })
+        .collect::<Vec<_>>();
+    for (info, actions) in sets.iter_mut() {
+        info.actions = actions
+            .iter()
+            .filter_map(|action| action_info(world, binding_id_systems.as_ref(), *action))
+            .collect();
+    }
+    if let Some(binding_id_systems) = binding_id_systems {
+        world.insert_resource(binding_id_systems);
+    }
+    let mut sets = sets.into_iter().map(|(info, _)| info).collect::<Vec<_>>();
+    sets.sort_by(|a, b| {
+        b.priority
+            .cmp(&a.priority)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    sets
+}
+
+/// The state of all action sets as text, one line per set and action.
+pub fn dump_actions(world: &mut World) -> String {
+    let mut out = String::new();
+    for set in action_debug_info(world) {
+        let _ = writeln!(out, "{set}");
+    }
+    out
+}
+
+fn action_info(
+    world: &mut World,
+    binding_id_systems: Option<&BindingIdSystems>,
+    action: Entity,
+) -> Option<ActionDebugInfo> {
+    let name = world.get::<Action>(action)?.name.to_string();
+    let contributing = world
+        .get::<ContributingBindings>(action)
+        .map(|c| c.0.clone())
+        .unwrap_or_default();
+    let contributing = match binding_id_systems {
+        Some(systems) if !contributing.is_empty() => {
+            let descriptions = systems.descriptions(world, action);
+            contributing
+                .into_iter()
+                .map(|id| {
+                    descriptions
+                        .get(&id)
+                        .cloned()
+                        .unwrap_or_else(|| systems.fallback_description(id.0, id.1))
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+    let world: &World = world;
+    let paths = world
+        .get::<RequestedSubactionPaths>(action)
+        .map(|paths| paths.0.as_slice())
+        .unwrap_or_default();
+    let mut values = Vec::new();
+    if let Some(v) = world.get::<BoolActionValue>(action) {
+        path_values(world, paths, v, DebugValue::Bool, &mut values);
+    }
+    if let Some(v) = world.get::<F32ActionValue>(action) {
+        path_values(world, paths, v, DebugValue::F32, &mut values);
+    }
+    if let Some(v) = world.get::<Vec2ActionValue>(action) {
+        path_values(world, paths, v, DebugValue::Vec2, &mut values);
+    }
+    Some(ActionDebugInfo {
+        action,
+        name,
+        values,
+        contributing,
+    })
+}
+
+fn path_values<T: Copy + Default>(
+    world: &World,
+    paths: &[SubactionPath],
+    map: &SubactionPathMap<T>,
+    value: fn(T) -> DebugValue,
+    out: &mut Vec<PathDebugValue>,
+) {
+    out.push(PathDebugValue {
+        path: None,
+        value: value(map.any),
+    });
+    for path in paths {
+        out.push(PathDebugValue {
+            path: Some(path_name(world, *path)),
+            value: value(map.get_with_path_or_default(path)),
+        });
+    }
+}
+
+fn path_name(world: &World, path: SubactionPath) -> String {
+    world
+        .get::<SubactionPathStr>(*path)
+        .map(|str| str.0.to_string())
+        .unwrap_or_else(|| path.0.to_string())
+}
+
+fn log_action_values(world: &mut World, mut last: Local<String>) {
+    let dump = dump_actions(world);
+    if dump != *last {
+        info!("{dump}");
+        *last = dump;
+    }
+}
+
+/// Logs when bindings get blocked or unblocked, this checks every action every frame,
+/// so only use it for debugging.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct LogBlockedBindings(pub bool);
+
+/// A binding of an action that gets no input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockedBinding {
+    pub action: Entity,
+    pub label_id: u64,
+    pub binding_id: u64,
+    /// e.g. `keyboard:KeyW` or `gamepad:South`.
+    pub description: String,
+    /// The actions in the [`ActionSet`](crate::ActionSet)s with a higher priority
+    /// that use the same input.
+    pub blocked_by: Vec<BlockingAction>,
+    /// The actions that consumed the input this frame, e.g. a chord or an action with
+    /// [`BlockingMode::WhenTriggered`].
+    pub consumed_by: Vec<BlockingAction>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BlockingAction {
+    pub set: Entity,
+    pub action: Entity,
+}
+
+impl BlockedBinding {
+    /// e.g. `the keyboard:KeyW binding of game/move is blocked by menu/up`.
+    pub fn describe(&self, world: &World) -> String {
+        let action = action_names(world, &[self.action]);
+        let mut reasons = Vec::new();
+        if !self.blocked_by.is_empty() {
+            let blockers = self
+                .blocked_by
+                .iter()
+                .map(|blocker| blocker.action)
+                .collect::<Vec<_>>();
+            reasons.push(format!("blocked by {}", action_names(world, &blockers)));
+        }
+        if !self.consumed_by.is_empty() {
+            let consumers = self
+                .consumed_by
+                .iter()
+                .map(|consumer| consumer.action)
+                .collect::<Vec<_>>();
+            reasons.push(format!("consumed by {}", action_names(world, &consumers)));
+        }
+        format!(
+            "the {} binding of {action} is {}",
+            self.description,
+            reasons.join(" and ")
+        )
+    }
+}
+
+/// Explains why bindings of the action get no input from their provider, bindings that are
+/// not blocked are left out. Consumed inputs are the ones of the current frame.
+pub fn explain_blocked(world: &mut World, action: Entity) -> Vec<BlockedBinding> {
+    let Some(binding_id_systems) = world.remove_resource::<BindingIdSystems>() else {
+        return Vec::new();
+    };
+    let out = explain(world, &binding_id_systems, action);
+    world.insert_resource(binding_id_systems);
+    out
+}
+
+fn explain(
+    world: &mut World,
+    binding_id_systems: &BindingIdSystems,
+    action: Entity,
+) -> Vec<BlockedBinding> {
+    let Some(set) = world.get::<Action>(action).map(|action| action.set) else {
+        return Vec::new();
+    };
+    let groups = priority_groups(world);
+    // sets without a group are disabled or transparent, they are never blocked
+    let Some(group) = groups.iter().position(|group| group.contains(&set)) else {
+        return Vec::new();
+    };
+    let owner = world.get::<OwnedByPlayer>(set).map(|owner| owner.0);
+    let mut users = HashMap::<(u64, u64), Vec<BlockingAction>>::new();
+    for higher_set in groups[..group].iter().flatten().copied() {
+        let higher_owner = world.get::<OwnedByPlayer>(higher_set).map(|owner| owner.0);
+        if !blocks(higher_owner, owner) {
+            continue;
+        }
+        let set_mode = world.get::<BlockingMode>(higher_set).copied();
+        let actions = world
+            .get::<ActionsInSet>(higher_set)
+            .map(|actions| actions.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        for higher_action in actions {
+            let mode = world
+                .get::<BlockingMode>(higher_action)
+                .copied()
+                .or(set_mode);
+            if mode == Some(BlockingMode::WhenTriggered) {
+                continue;
+            }
+            for (label_id, ids) in binding_id_systems.binding_ids(world, higher_action) {
+                for binding_id in ids {
+                    users
+                        .entry((label_id, binding_id))
+                        .or_default()
+                        .push(BlockingAction {
+                            set: higher_set,
+                            action: higher_action,
+                        });
+                }
+            }
+        }
+    }
+    let descriptions = binding_id_systems.descriptions(world, action);
+    let ids = binding_id_systems.binding_ids(world, action);
+    let blocked = world.get::<BlockedInputs>(set);
+    let priority = world
+        .get::<ActionSet>(set)
+        .and_then(|s| EffectivePriority::of(s, world.get::<ContextLayer>(set)));
+    let consumed = world.resource::<ConsumedInputs>();
+    let mut out = Vec::new();
+    for (label_id, mut ids) in ids {
+        ids.sort_unstable();
+        ids.dedup();
+        for binding_id in ids {
+            let is_blocked = blocked.is_some_and(|b| b.is_blocked(label_id, binding_id));
+            let consumed_by = consumed.consumed_by(label_id, binding_id, priority, owner);
+            if !is_blocked && consumed_by.is_empty() {
+                continue;
+            }
+            out.push(BlockedBinding {
+                action,
+                label_id,
+                binding_id,
+                description: descriptions
+                    .get(&(label_id, binding_id))
+                    .cloned()
+                    .unwrap_or_else(|| {
+                        binding_id_systems.fallback_description(label_id, binding_id)
+                    }),
+                blocked_by: match is_blocked {
+                    true => users.remove(&(label_id, binding_id)).unwrap_or_default(),
+                    false => Vec::new(),
+                },
+                consumed_by,
+            });
+        }
+    }
+    out.sort_by(|a, b| a.description.cmp(&b.description));
+    out
+}
+
+fn log_blocked_bindings(world: &mut World, mut last: Local<EntityHashMap<Vec<BlockedBinding>>>) {
+    let actions = world
+        .query_filtered::<Entity, With<Action>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    last.retain(|action, _| actions.contains(action));
+    let Some(binding_id_systems) = world.remove_resource::<BindingIdSystems>() else {
+        return;
+    };
+    for action in actions {
+        let blocked = explain(world, &binding_id_systems, action);
+        let previous = last.remove(&action).unwrap_or_default();
+        for binding in blocked.iter().filter(|b| !previous.contains(b)) {
+            info!("{}", binding.describe(world));
+        }
+        for binding in previous.iter().filter(|b| {
+            !blocked
+                .iter()
+                .any(|n| n.label_id == b.label_id && n.binding_id == b.binding_id)
+        }) {
+            info!(
+                "the {} binding of {} is no longer blocked",
+                binding.description,
+                action_names(world, &[action])
+            );
+        }
+        if !blocked.is_empty() {
+            last.insert(action, blocked);
+        }
+    }
+    world.insert_resource(binding_id_systems);
+}
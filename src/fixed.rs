@@ -0,0 +1,270 @@
+use std::ops::Deref;
+
+use bevy::prelude::*;
+
+use crate::{
+    BoolActionValue, F32ActionValue, SchminputSystems, Vec2ActionValue,
+    action_state::ActionValueType,
+    aggregation::ValueAggregation,
+    impl_helpers::BindingValue,
+    subaction_paths::{SubactionPath, SubactionPathMap},
+};
+
+/// Collects the values of actions with a [`FixedActionValue`] between fixed timesteps,
+/// so that systems in [`FixedUpdate`] see every press exactly once.
+pub struct FixedActionValuesPlugin;
+
+impl Plugin for FixedActionValuesPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<FixedAccumulation>();
+        app.add_systems(
+            PreUpdate,
+            clear_deltas.in_set(SchminputSystems::ClearValues),
+        );
+        app.add_systems(
+            PreUpdate,
+            (
+                accumulate::<bool, BoolActionValue>,
+                accumulate::<f32, F32ActionValue>,
+                accumulate::<Vec2, Vec2ActionValue>,
+            )
+                .in_set(SchminputSystems::AccumulateFixedValues),
+        );
+        app.add_systems(
+            FixedPreUpdate,
+            (tick::<bool>, tick::<f32>, tick::<Vec2>).in_set(SchminputSystems::TickFixedValues),
+        );
+    }
+}
+
+/// Implemented for the types an action can have as its value.
+pub trait FixedValueType: ActionValueType {
+    /// combines the values of multiple frames without a fixed timestep in between
+    fn accumulate(self, new: Self) -> Self;
+    /// the value of this type in the value of a binding
+    fn from_binding_value(value: &BindingValue) -> Self;
+    /// The value for the next fixed timestep, `sum` and `delta_sum` are the values of all frames
+    /// since the last timestep added up, they are only seen by one timestep.
+    fn tick_value(
+        latest: Self,
+        sum: Self,
+        latest_delta: Self,
+        delta_sum: Self,
+        accumulation: FixedAccumulation,
+    ) -> Self;
+}
+
+impl FixedValueType for bool {
+    fn accumulate(self, new: Self) -> Self {
+        self | new
+    }
+    fn from_binding_value(value: &BindingValue) -> Self {
+        value.bool.unwrap_or_default()
+    }
+    // presses between timesteps are seen once, held buttons are seen by every timestep,
+    // momentary bindings like JustPressed only by the first timestep after the frame
+    fn tick_value(
+        latest: Self,
+        sum: Self,
+        latest_delta: Self,
+        _: Self,
+        _: FixedAccumulation,
+    ) -> Self {
+        sum | (latest & !latest_delta)
+    }
+}
+impl FixedValueType for f32 {
+    fn accumulate(self, new: Self) -> Self {
+        self + new
+    }
+    fn from_binding_value(value: &BindingValue) -> Self {
+        value.f32.unwrap_or_default()
+    }
+    fn tick_value(
+        latest: Self,
+        sum: Self,
+        latest_delta: Self,
+        delta_sum: Self,
+        accumulation: FixedAccumulation,
+    ) -> Self {
+        match accumulation {
+            FixedAccumulation::Auto => latest - latest_delta + delta_sum,
+            FixedAccumulation::Latest => latest,
+            FixedAccumulation::Sum => sum,
+        }
+    }
+}
+impl FixedValueType for Vec2 {
+    fn accumulate(self, new: Self) -> Self {
+        self + new
+    }
+    fn from_binding_value(value: &BindingValue) -> Self {
+        value.vec2.unwrap_or_default()
+    }
+    fn tick_value(
+        latest: Self,
+        sum: Self,
+        latest_delta: Self,
+        delta_sum: Self,
+        accumulation: FixedAccumulation,
+    ) -> Self {
+        match accumulation {
+            FixedAccumulation::Auto => latest - latest_delta + delta_sum,
+            FixedAccumulation::Latest => latest,
+            FixedAccumulation::Sum => sum,
+        }
+    }
+}
+
+pub type FixedBoolActionValue = FixedActionValue<bool>;
+pub type FixedF32ActionValue = FixedActionValue<f32>;
+pub type FixedVec2ActionValue = FixedActionValue<Vec2>;
+
+/// The value of an action for the current fixed timestep, read this in [`FixedUpdate`].
+/// Needs the normal value component of the same type on the action.
+///
+/// Actions with a fixed value use the fixed timestep for
+/// [`PremultiplyDeltaSecsModification`](crate::binding_modification::PremultiplyDeltaSecsModification).
+#[derive(Debug, Clone, Component, Reflect, Deref, DerefMut, Default)]
+#[require(FixedAccumulator<T>, FixedAccumulation, FixedDeltas)]
+pub struct FixedActionValue<T: FixedValueType>(pub SubactionPathMap<T>);
+
+impl<T: FixedValueType> FixedActionValue<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// How float and vector values of multiple frames are combined for a fixed timestep,
+/// bool values are always true for one timestep after a press and while held,
+/// bindings that are only active for one frame, like
+/// [`ButtonInputBeheavior::JustPressed`](crate::ButtonInputBeheavior::JustPressed),
+/// are seen by one timestep.
+#[derive(Clone, Copy, Debug, Reflect, Default, PartialEq, Eq, Hash, Component)]
+pub enum FixedAccumulation {
+    /// [`FixedAccumulation::Sum`] for the values of delta bindings, like mouse motion, scroll
+    /// and momentary buttons, [`FixedAccumulation::Latest`] for the values of all other bindings.
+    ///
+    /// This needs the delta part of the value to be added onto the rest, so actions with a
+    /// [`ValueAggregation`] other than [`ValueAggregation::Sum`] use
+    /// [`FixedAccumulation::Latest`] for float and vector values instead.
+    #[default]
+    Auto,
+    /// Every timestep sees the value of the latest frame.
+    Latest,
+    /// The values of all frames since the last timestep are added up and only seen
+    /// by the next timestep, use this for deltas.
+    Sum,
+}
+
+/// The part of the value of a fixed action that came from delta bindings this frame,
+/// after processors, e.g. mouse motion or a just pressed button, see [`FixedAccumulation::Auto`].
+#[derive(Debug, Clone, Component, Default)]
+pub struct FixedDeltas(pub SubactionPathMap<BindingValue>);
+
+impl FixedDeltas {
+    pub(crate) fn add(&mut self, path: Option<SubactionPath>, value: Option<BindingValue>) {
+        let Some(value) = value else {
+            return;
+        };
+        let delta = match path {
+            Some(path) => self.0.entry_with_path(path).or_default(),
+            None => &mut self.0.any,
+        };
+        if let Some(v) = value.vec2 {
+            *delta.vec2.get_or_insert_default() += v;
+        }
+        if let Some(v) = value.f32 {
+            *delta.f32.get_or_insert_default() += v;
+        }
+        if let Some(v) = value.bool {
+            *delta.bool.get_or_insert_default() |= v;
+        }
+    }
+}
+
+fn clear_deltas(mut query: Query<&mut FixedDeltas>) {
+    for mut deltas in &mut query {
+        deltas.0.any = BindingValue::default();
+        deltas.0.paths.clear();
+    }
+}
+
+/// The values collected since the last fixed timestep.
+#[derive(Debug, Clone, Component, Default)]
+pub struct FixedAccumulator<T: FixedValueType> {
+    sum: SubactionPathMap<T>,
+    latest: SubactionPathMap<T>,
+    delta_sum: SubactionPathMap<T>,
+    latest_delta: SubactionPathMap<T>,
+}
+
+fn accumulate<T: FixedValueType, V: Component + Deref<Target = SubactionPathMap<T>>>(
+    mut query: Query<(&V, &FixedDeltas, &mut FixedAccumulator<T>)>,
+) {
+    for (value, deltas, mut accumulator) in &mut query {
+        let accumulator = accumulator.as_mut();
+        accumulator.latest = (**value).clone();
+        accumulator.sum.any = accumulator.sum.any.accumulate(value.any);
+        for (path, value) in value.paths.iter() {
+            let sum = accumulator.sum.entry_with_path(*path).or_default();
+            *sum = sum.accumulate(*value);
+        }
+        accumulator.latest_delta = SubactionPathMap::new();
+        accumulator.latest_delta.any = T::from_binding_value(&deltas.0.any);
+        accumulator.delta_sum.any = accumulator
+            .delta_sum
+            .any
+            .accumulate(accumulator.latest_delta.any);
+        for (path, delta) in deltas.0.paths.iter() {
+            let delta = T::from_binding_value(delta);
+            accumulator.latest_delta.set_value_for_path(*path, delta);
+            let sum = accumulator.delta_sum.entry_with_path(*path).or_default();
+            *sum = sum.accumulate(delta);
+        }
+    }
+}
+
+fn tick<T: FixedValueType>(
+    mut query: Query<(
+        &mut FixedActionValue<T>,
+        &mut FixedAccumulator<T>,
+        &FixedAccumulation,
+        Option<&ValueAggregation>,
+    )>,
+) {
+    for (mut value, mut accumulator, accumulation, aggregation) in &mut query {
+        let accumulator = accumulator.as_mut();
+        let mut accumulation = *accumulation;
+        // the delta part can only be taken out of summed values
+        if accumulation == FixedAccumulation::Auto
+            && aggregation.is_some_and(|aggregation| *aggregation != ValueAggregation::Sum)
+        {
+            accumulation = FixedAccumulation::Latest;
+        }
+        let sum = std::mem::take(&mut accumulator.sum);
+        let delta_sum = std::mem::take(&mut accumulator.delta_sum);
+        let get = |map: &SubactionPathMap<T>, path| map.get_with_path_or_default(path);
+        let mut out = SubactionPathMap::<T>::new();
+        out.any = T::tick_value(
+            accumulator.latest.any,
+            sum.any,
+            accumulator.latest_delta.any,
+            delta_sum.any,
+            accumulation,
+        );
+        for path in sum.paths.keys().chain(accumulator.latest.paths.keys()) {
+            out.set_value_for_path(
+                *path,
+                T::tick_value(
+                    get(&accumulator.latest, path),
+                    get(&sum, path),
+                    get(&accumulator.latest_delta, path),
+                    get(&delta_sum, path),
+                    accumulation,
+                ),
+            );
+        }
+        value.0 = out;
+    }
+}
@@ -5,6 +5,7 @@ use std::{
 
 use atomicow::CowArc;
 use bevy::{
+    ecs::{change_detection::Tick, system::SystemChangeTick},
     input::gamepad::{GamepadInput, GamepadRumbleIntensity, GamepadRumbleRequest},
     prelude::*,
 };
@@ -12,6 +13,8 @@ use bevy::{
 use crate::{
     Action, ActionSet, ButtonInputBeheavior, InputAxis, InputAxisDirection, SchminputSystems,
     impl_helpers::{BindingValue, GenericBindingData, ProviderParam},
+    interactions::{BindingInteractions, interaction_key},
+    players::{OwnedByPlayer, PlayerDevices, allows_gamepad},
     prelude::RequestedSubactionPaths,
     priorities::PriorityAppExt as _,
     subaction_paths::{SubactionPath, SubactionPathCreated, SubactionPathMap, SubactionPathStr},
@@ -19,11 +22,28 @@ use crate::{
 
 pub struct GamepadPlugin;
 
-/// Use the index of a gamepad in this resource in a subaction path to referebce
-/// a specific gamepad
+/// Use this in a subaction path to reference a specific gamepad, e.g. `/gamepad/1`.
+/// Gamepads without one get the lowest free number starting at 1 when they connect.
 #[derive(Component, Clone, Debug, Deref)]
 pub struct GamepadIdentifier(pub CowArc<'static, str>);
 
+fn assign_gamepad_identifiers(
+    new_gamepads: Query<Entity, (Added<Gamepad>, Without<GamepadIdentifier>)>,
+    identifiers: Query<&GamepadIdentifier>,
+    mut cmds: Commands,
+) {
+    let mut used = identifiers
+        .iter()
+        .filter_map(|id| id.0.parse::<u32>().ok())
+        .collect::<Vec<_>>();
+    for gamepad in &new_gamepads {
+        let id = (1..).find(|id| !used.contains(id)).unwrap_or_default();
+        used.push(id);
+        cmds.entity(gamepad)
+            .insert(GamepadIdentifier(id.to_string().into()));
+    }
+}
+
 impl Plugin for GamepadPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
@@ -34,6 +54,10 @@ impl Plugin for GamepadPlugin {
             PreUpdate,
             clear_haptic.in_set(SchminputSystems::ClearValues),
         );
+        app.add_systems(
+            PreUpdate,
+            assign_gamepad_identifiers.before(SchminputSystems::HandleNewSubactionPaths),
+        );
         app.add_systems(
             PostUpdate,
             sync_haptics.in_set(SchminputSystems::SyncOutputActions),
@@ -42,7 +66,7 @@ impl Plugin for GamepadPlugin {
             PreUpdate,
             handle_new_subaction_paths.in_set(SchminputSystems::HandleNewSubactionPaths),
         );
-        app.add_binding_id_system(
+        app.add_tracked_binding_id_system::<GamepadBindings, _>(
             "schminput:gamepad",
             |entity: In<Entity>, query: Query<&GamepadBindings>| {
                 let Ok(bindings) = query.get(entity.0) else {
@@ -51,12 +75,29 @@ impl Plugin for GamepadPlugin {
                 bindings.bindings.iter().map(get_binding_id).collect()
             },
         );
+        app.add_binding_description_system(
+            "schminput:gamepad",
+            |entity: In<Entity>, query: Query<&GamepadBindings>| {
+                let Ok(bindings) = query.get(entity.0) else {
+                    return Vec::new();
+                };
+                bindings
+                    .bindings
+                    .iter()
+                    .map(|b| (get_binding_id(b), format!("gamepad:{:?}", b.source)))
+                    .collect()
+            },
+        );
     }
 }
 
 fn get_binding_id(binding: &GamepadBinding) -> u64 {
+    source_binding_id(binding.source)
+}
+
+pub(crate) fn source_binding_id(source: GamepadBindingSource) -> u64 {
     let mut hasher = DefaultHasher::new();
-    binding.source.hash(&mut hasher);
+    source.hash(&mut hasher);
     hasher.finish()
 }
 
@@ -85,14 +126,10 @@ fn handle_new_subaction_paths(
             "*" | "" => {
                 cmds.entity(e).insert(GamepadPathSelector::All);
             }
+            // matched against the GamepadIdentifier of the gamepads
             v => {
                 cmds.entity(e)
                     .insert(GamepadPathSelector::Gamepad(v.to_owned()));
-                error!(
-                    "unable to parse gamepad id, use a positive integer or *: {}",
-                    v
-                );
-                continue;
             }
         }
 
@@ -175,15 +212,26 @@ fn sync_haptics(
         &RequestedSubactionPaths,
     )>,
     path_query: Query<&GamepadPathSelector>,
-    set_query: Query<&ActionSet>,
+    set_query: Query<(&ActionSet, Option<&OwnedByPlayer>)>,
+    player_query: Query<&PlayerDevices>,
     gamepads: Query<(Entity, &Gamepad, Option<&GamepadIdentifier>)>,
 ) {
     for (bindings, out, action, sub_paths) in &haptic_query {
-        if !(set_query.get(action.set).is_ok_and(|v| v.enabled)) {
+        let Ok((set, owner)) = set_query.get(action.set) else {
+            continue;
+        };
+        if !set.enabled {
             continue;
         };
+        let devices = owner.and_then(|owner| player_query.get(owner.0).ok());
+        // only rumble the gamepads of the player owning the set
+        let gamepads = || {
+            gamepads
+                .iter()
+                .filter(move |(gamepad, _, _)| allows_gamepad(devices, *gamepad))
+        };
         for binding in bindings.bindings.iter() {
-            for (gamepad, _, _) in gamepads.iter() {
+            for (gamepad, _, _) in gamepads() {
                 for e in &out.haptic_feedbacks.any {
                     gamepad_haptic_event.write(match e {
                         GamepadHapticValue::Add {
@@ -206,7 +254,7 @@ fn sync_haptics(
             for binding in bindings.bindings.iter() {
                 match device {
                     GamepadPathSelector::All => {
-                        for (gamepad, _, _) in gamepads.iter() {
+                        for (gamepad, _, _) in gamepads() {
                             for e in out
                                 .haptic_feedbacks
                                 .get_with_path(sub_path)
@@ -234,8 +282,7 @@ fn sync_haptics(
                             .get_with_path(sub_path)
                             .unwrap_or(&Vec::new())
                         {
-                            let Some((gamepad, _)) = gamepads
-                                .iter()
+                            let Some((gamepad, _)) = gamepads()
                                 .filter_map(|(e, _, v)| Some((e, v?)))
                                 .find(|(_, v)| v.as_ref() == gamepad.as_str())
                             else {
@@ -265,6 +312,7 @@ fn sync_actions(
     gamepads: Query<(Entity, &Gamepad, Option<&GamepadIdentifier>)>,
     mut query: ProviderParam<
         &GamepadBindings,
+        &mut BindingInteractions,
         (
             &GamepadPathSelector,
             Option<&GamepadPathTarget>,
@@ -272,53 +320,77 @@ fn sync_actions(
         ),
     >,
     time: Res<Time>,
+    ticks: SystemChangeTick,
 ) {
-    query.run(
+    query.run_with_deltas(
         "schminput:gamepad",
+        |binding| {
+            binding.source.as_button_type().is_some() && binding.button_behavior.is_momentary()
+        },
         get_binding_id,
         |binding: &GamepadBinding, (_, target, target_side)| {
             target.is_none_or(|target| target.matches(&binding.source, *target_side))
         },
-        |bindings| bindings.bindings.clone(),
-        |binding, _, path_data, data| {
+        // the values for all paths come from every gamepad
+        |(gamepad, _, _)| matches!(gamepad, GamepadPathSelector::Gamepad(_)),
+        |bindings, visit| bindings.bindings.iter().for_each(visit),
+        |binding, interactions, path_data, data, out| {
             let device = match path_data {
                 Some((gamepad, _, _)) => (*gamepad).clone(),
                 None => GamepadPathSelector::All,
             };
 
-            let mut out = Vec::new();
             match device {
                 GamepadPathSelector::All => {
-                    for (_, gamepad, _) in gamepads.iter() {
-                        out.push(handle_gamepad_inputs_new(gamepad, binding, data, &time));
+                    for (entity, gamepad, _) in gamepads
+                        .iter()
+                        .filter(|(entity, _, _)| allows_gamepad(data.devices, *entity))
+                    {
+                        out.push(handle_gamepad_inputs_new(
+                            entity,
+                            gamepad,
+                            binding,
+                            data,
+                            interactions,
+                            &time,
+                            ticks.this_run(),
+                        ));
                     }
                 }
                 GamepadPathSelector::Gamepad(gamepad) => {
-                    let Some((gamepad, _)) = gamepads
+                    let Some((entity, gamepad, _)) = gamepads
                         .iter()
-                        .filter_map(|(_, e, v)| Some((e, v?)))
-                        .find(|(_, v)| v.as_ref() == gamepad.as_str())
+                        .filter_map(|(entity, e, v)| Some((entity, e, v?)))
+                        .find(|(_, _, v)| v.as_ref() == gamepad.as_str())
+                        .filter(|(entity, _, _)| allows_gamepad(data.devices, *entity))
                     else {
-                        return vec![];
+                        return;
                     };
-                    out.push(handle_gamepad_inputs_new(gamepad, binding, data, &time));
+                    out.push(handle_gamepad_inputs_new(
+                        entity,
+                        gamepad,
+                        binding,
+                        data,
+                        interactions,
+                        &time,
+                        ticks.this_run(),
+                    ));
                 }
             };
-            out
         },
     );
 }
 
 fn handle_gamepad_inputs_new(
+    entity: Entity,
     gamepad: &Gamepad,
     binding: &GamepadBinding,
     data: &GenericBindingData,
+    interactions: &mut BindingInteractions,
     time: &Time,
+    tick: Tick,
 ) -> BindingValue {
-    let delta_multiplier = match data.modifications.premul_delta_time {
-        true => time.delta_secs(),
-        false => 1.0,
-    };
+    let delta_multiplier = data.modifications.delta_multiplier();
     let Some(v) = (match data.modifications.unbounded {
         true => gamepad.get_unclamped(binding.source),
         false => gamepad.get(binding.source),
@@ -326,6 +398,24 @@ fn handle_gamepad_inputs_new(
         warn!("gamepad has no {}", binding.source);
         return BindingValue::default();
     };
+    // analog buttons keep their value when just checking if they are pressed
+    let v = match binding.source.as_button_type() {
+        Some(button) if binding.button_behavior != ButtonInputBeheavior::Pressed => {
+            binding.button_behavior.apply_with_state(
+                gamepad.digital(),
+                button,
+                interactions,
+                interaction_key(
+                    get_binding_id(binding),
+                    binding.button_behavior,
+                    Some(entity),
+                ),
+                time,
+                tick,
+            ) as u8 as f32
+        }
+        _ => v,
+    };
     let bool = data.is_bool.then_some(v > 0.1);
     let f32 = data
         .is_f32
@@ -431,6 +521,7 @@ impl GamepadHapticOutput {
 }
 
 #[derive(Clone, Component, Debug, Reflect, Default)]
+#[require(BindingInteractions)]
 pub struct GamepadBindings {
     pub bindings: Vec<GamepadBinding>,
 }
@@ -457,6 +548,8 @@ impl GamepadBindings {
 #[derive(Clone, Copy, Debug, Reflect, PartialEq, Eq, Hash)]
 pub struct GamepadBinding {
     pub source: GamepadBindingSource,
+    /// Only used for button sources, analog buttons report their value while
+    /// [`ButtonInputBeheavior::Pressed`] and 0 or 1 for every other behavior.
     pub button_behavior: ButtonInputBeheavior,
     pub axis: InputAxis,
     pub axis_dir: InputAxisDirection,
@@ -487,6 +580,11 @@ impl GamepadBinding {
         self
     }
 
+    pub fn button_behavior(mut self, behavior: ButtonInputBeheavior) -> Self {
+        self.button_behavior = behavior;
+        self
+    }
+
     pub fn x_axis(mut self) -> Self {
         self.axis = InputAxis::X;
         self
@@ -510,6 +608,7 @@ impl GamepadBinding {
 
 // Mashup of bevys GamepadButtonType and GamepadAxisType
 #[derive(Clone, Copy, Debug, Reflect, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "manifest", derive(serde::Deserialize))]
 pub enum GamepadBindingSource {
     /// The horizontal value of the left stick.
     LeftStickX,
@@ -1,22 +1,40 @@
 use std::hash::{DefaultHasher, Hash as _, Hasher};
 
 use crate::{
+    aggregation::{AggregationState, ValueAggregation, aggregate_value},
     binding_modification::{
         BindingModifications, PremultiplyDeltaSecsModification, UnboundedModification,
     },
+    contexts::ContextLayer,
+    debug::{BlockingAction, ContributingBindings},
+    fixed::FixedDeltas,
+    players::{OwnedByPlayer, PlayerDevices},
     prelude::*,
-    priorities::BlockedInputs,
+    priorities::{ActionOrder, BlockedInputs, BlockingMode, ConsumedInputs, EffectivePriority},
+    processors::ProcessorChain,
     subaction_paths::SubactionPath,
 };
 use bevy::{
     ecs::{
-        query::{QueryData, QueryFilter},
+        query::{QueryData, QueryFilter, ReadOnlyQueryData},
         system::SystemParam,
     },
+    platform::collections::HashSet,
     prelude::*,
 };
+use smallvec::SmallVec;
 pub struct GenericBindingData<'s> {
     pub action: &'s Action,
+    /// The inputs blocked by higher priority [`ActionSet`]s.
+    pub blocked_inputs: Option<&'s BlockedInputs>,
+    /// The inputs consumed so far this frame.
+    pub consumed_inputs: &'s ConsumedInputs,
+    /// The priority of the [`ActionSet`], [`None`] if it is transparent.
+    pub priority: Option<EffectivePriority>,
+    /// The player owning the [`ActionSet`].
+    pub owner: Option<Entity>,
+    /// The devices of the player owning the [`ActionSet`], [`None`] if no player owns it.
+    pub devices: Option<&'s PlayerDevices>,
     pub subaction_path: Option<SubactionPath>,
     pub modifications: Modifications<'s>,
     pub is_bool: bool,
@@ -26,22 +44,47 @@ pub struct GenericBindingData<'s> {
 
 pub struct Modifications<'s> {
     pub inner: &'s BindingModifications,
+    /// False if the binding has [`InputProcessors`](crate::processors::InputProcessors),
+    /// they run on the raw value and the delta is applied after them.
     pub premul_delta_time: bool,
     pub unbounded: bool,
+    /// The delta to use for [`PremultiplyDeltaSecsModification`],
+    /// the fixed timestep for actions with a [`FixedActionValue`](crate::fixed::FixedActionValue).
+    pub delta_secs: f32,
 }
 
-#[derive(Default, Clone, Copy)]
+impl Modifications<'_> {
+    /// The delta when premultiplying delta seconds, otherwise 1
+    pub fn delta_multiplier(&self) -> f32 {
+        match self.premul_delta_time {
+            true => self.delta_secs,
+            false => 1.0,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
 pub struct BindingValue {
     pub vec2: Option<Vec2>,
     pub bool: Option<bool>,
     pub f32: Option<f32>,
 }
 
+impl BindingValue {
+    /// If any of the values is non zero or true.
+    pub fn is_triggered(&self) -> bool {
+        self.bool == Some(true)
+            || self.f32.is_some_and(|v| v != 0.0)
+            || self.vec2.is_some_and(|v| v != Vec2::ZERO)
+    }
+}
+
 #[derive(SystemParam)]
 pub struct ProviderParam<
     'w,
     's,
-    ActionData: QueryData + 'static,
+    Bindings: ReadOnlyQueryData + 'static,
+    State: QueryData + 'static,
     PathData: QueryData + 'static,
     PathFilter: QueryFilter + 'static = (),
 > {
@@ -49,234 +92,550 @@ pub struct ProviderParam<
         'w,
         's,
         (
-            ActionData,
+            Bindings,
+            State,
+            Entity,
             &'static Action,
+            Option<&'static BlockingMode>,
             &'static RequestedSubactionPaths,
             &'static BindingModifications,
+            &'static ValueAggregation,
+            &'static mut AggregationState,
+            Option<&'static mut FixedDeltas>,
             Option<&'static mut BoolActionValue>,
             Option<&'static mut F32ActionValue>,
             Option<&'static mut Vec2ActionValue>,
+            Option<&'static mut ContributingBindings>,
         ),
     >,
-    pub action_set_query: Query<'w, 's, (&'static ActionSet, Option<&'static BlockedInputs>)>,
+    pub action_set_query: Query<
+        'w,
+        's,
+        (
+            &'static ActionSet,
+            Option<&'static BlockedInputs>,
+            Option<&'static OwnedByPlayer>,
+            Option<&'static BlockingMode>,
+            Option<&'static ContextLayer>,
+        ),
+    >,
+    pub player_query: Query<'w, 's, &'static PlayerDevices>,
+    pub consumed_inputs: ResMut<'w, ConsumedInputs>,
+    action_order: Res<'w, ActionOrder>,
+    pub disabled_providers: Res<'w, DisabledProviders>,
+    pub time: Res<'w, Time>,
+    pub fixed_time: Res<'w, Time<Fixed>>,
     pub binding_modification_query: Query<
         'w,
         's,
         (
             Has<PremultiplyDeltaSecsModification>,
             Has<UnboundedModification>,
+            Option<&'static ProcessorChain>,
         ),
     >,
     pub path_query: Query<'w, 's, PathData, PathFilter>,
+    buffers: Local<'s, ProviderBuffers>,
 }
+
+/// Reused by every run of a [`ProviderParam`] so the hot path doesn't allocate.
+#[derive(Default)]
+pub struct ProviderBuffers {
+    order: Vec<(Entity, usize)>,
+    /// inputs consumed by the current priority group, they are blocked for the next ones
+    pending_consumed: Vec<(
+        u64,
+        Option<EffectivePriority>,
+        Option<Entity>,
+        BlockingAction,
+    )>,
+    /// the per path modifications of the current action
+    modifications: Vec<ResolvedModification>,
+    /// the values of the bindings for all paths, with the index of their binding
+    /// and the mask of their per path modifications
+    binding_values: Vec<(usize, ModificationMask, BindingValue)>,
+    /// the values that get aggregated next, with the mask of their per path modifications
+    values: Vec<(ModificationMask, BindingValue)>,
+    /// the values of delta bindings out of `values` for actions with a fixed value
+    deltas: Vec<(ModificationMask, BindingValue)>,
+    out: Vec<BindingValue>,
+}
+
+/// One bit per per path modification of the action, only allocates past 64 modifications.
+type ModificationMask = SmallVec<[u64; 1]>;
+
+fn mask_contains(mask: &ModificationMask, i: usize) -> bool {
+    mask.get(i / 64)
+        .is_some_and(|word| word & 1 << (i % 64) != 0)
+}
+
+fn mask_bits(mask: &ModificationMask) -> impl Iterator<Item = usize> + '_ {
+    mask.iter().enumerate().flat_map(|(word_index, word)| {
+        (0..64)
+            .filter(move |bit| word & 1 << bit != 0)
+            .map(move |bit| word_index * 64 + bit)
+    })
+}
+
+struct ResolvedModification {
+    path: SubactionPath,
+    premul_delta_time: bool,
+    unbounded: bool,
+    processors: Option<Entity>,
+}
+
+/// The item of the path query that the closures passed to [`ProviderParam::run`] get.
+pub type PathItem<'w, 's, PathData> =
+    <<PathData as QueryData>::ReadOnly as QueryData>::Item<'w, 's>;
+
 impl<
-    ActionData: QueryData + 'static,
+    Bindings: ReadOnlyQueryData + 'static,
+    State: QueryData + 'static,
     PathData: QueryData + 'static,
     PathFilter: QueryFilter + 'static,
-> ProviderParam<'_, '_, ActionData, PathData, PathFilter>
+> ProviderParam<'_, '_, Bindings, State, PathData, PathFilter>
 {
-    pub fn run<BindingData>(
+    /// Syncs the values of all actions, `bindings` visits the bindings of an action
+    /// and `update_for_binding` writes the values of one binding into the buffer it gets.
+    ///
+    /// The values of requested subaction paths reuse the ones for all paths, unless
+    /// `path_changes_values` returns true for the path, e.g. a path selecting one gamepad.
+    pub fn run<Binding>(
         &mut self,
         label: &str,
-        binding_id: impl Fn(&BindingData) -> u64,
-        path_matches: impl Fn(
-            &BindingData,
-            &<<PathData as QueryData>::ReadOnly as QueryData>::Item<'_, '_>,
-        ) -> bool,
-        bindings: impl Fn(&<ActionData as QueryData>::Item<'_, '_>) -> Vec<BindingData>,
-        mut update_for_binding: impl FnMut(
-            &BindingData,
-            &mut <ActionData as QueryData>::Item<'_, '_>,
+        binding_id: impl Fn(&Binding) -> u64,
+        path_matches: impl Fn(&Binding, &PathItem<'_, '_, PathData>) -> bool,
+        path_changes_values: impl Fn(&PathItem<'_, '_, PathData>) -> bool,
+        bindings: impl Fn(&<Bindings as QueryData>::Item<'_, '_>, &mut dyn FnMut(&Binding)),
+        update_for_binding: impl FnMut(
+            &Binding,
+            &mut <State as QueryData>::Item<'_, '_>,
+            Option<&PathItem<'_, '_, PathData>>,
+            &GenericBindingData,
+            &mut Vec<BindingValue>,
+        ),
+    ) {
+        self.run_with_deltas(
+            label,
+            |_| false,
+            binding_id,
+            path_matches,
+            path_changes_values,
+            bindings,
+            update_for_binding,
+        );
+    }
 
-            Option<&<<PathData as QueryData>::ReadOnly as QueryData>::Item<'_, '_>>,
+    /// [`ProviderParam::run`] for providers with bindings that give deltas, e.g. mouse motion,
+    /// or that are only active for one frame, e.g.
+    /// [`ButtonInputBeheavior::JustPressed`](crate::ButtonInputBeheavior::JustPressed),
+    /// these are added up between timesteps by [`FixedAccumulation::Auto`](crate::fixed::FixedAccumulation::Auto).
+    pub fn run_with_deltas<Binding>(
+        &mut self,
+        label: &str,
+        is_delta: impl Fn(&Binding) -> bool,
+        binding_id: impl Fn(&Binding) -> u64,
+        path_matches: impl Fn(&Binding, &PathItem<'_, '_, PathData>) -> bool,
+        path_changes_values: impl Fn(&PathItem<'_, '_, PathData>) -> bool,
+        bindings: impl Fn(&<Bindings as QueryData>::Item<'_, '_>, &mut dyn FnMut(&Binding)),
+        mut update_for_binding: impl FnMut(
+            &Binding,
+            &mut <State as QueryData>::Item<'_, '_>,
+            Option<&PathItem<'_, '_, PathData>>,
             &GenericBindingData,
-        ) -> Vec<BindingValue>,
+            &mut Vec<BindingValue>,
+        ),
     ) {
-        let label_id = {
-            let mut hasher = DefaultHasher::new();
-            label.hash(&mut hasher);
-            hasher.finish()
-        };
+        let label_id = label_id(label);
+        if self.disabled_providers.is_disabled_id(label_id) {
+            return;
+        }
         let _span = debug_span!("ProviderHelper::run").entered();
-        for (mut data, action, req_sub_paths, modifications, mut bool, mut f32, mut vec2) in
-            self.action_query.iter_mut()
-        {
-            let Ok((set, input)) = self.action_set_query.get(action.set) else {
+        let ProviderBuffers {
+            order,
+            pending_consumed,
+            modifications: resolved,
+            binding_values,
+            values,
+            deltas,
+            out,
+        } = &mut *self.buffers;
+        order.clear();
+        // without consumption the order doesn't matter
+        match self.action_order.0.is_empty() {
+            true => order.extend(self.action_query.iter().map(|(_, _, e, ..)| (e, 0))),
+            false => order.extend_from_slice(&self.action_order.0),
+        }
+        pending_consumed.clear();
+        let mut current_group = 0;
+        for (entity, group) in order.iter().copied() {
+            if group != current_group {
+                for (id, priority, owner, by) in pending_consumed.drain(..) {
+                    self.consumed_inputs
+                        .consume_below(label_id, id, priority, owner, by);
+                }
+                current_group = group;
+            }
+            let Ok((
+                action_bindings,
+                mut state,
+                _,
+                action,
+                action_mode,
+                req_sub_paths,
+                modifications,
+                aggregation,
+                mut aggregation_state,
+                mut fixed_deltas,
+                mut bool,
+                mut f32,
+                mut vec2,
+                mut contributing,
+            )) = self.action_query.get_mut(entity)
+            else {
+                continue;
+            };
+            let Ok((set, input, owner, set_mode, layer)) = self.action_set_query.get(action.set)
+            else {
                 continue;
             };
+            let priority = EffectivePriority::of(set, layer);
+            let consumes = action_mode.or(set_mode) == Some(&BlockingMode::WhenTriggered);
+            // sets of players that don't exist anymore get no input
+            let devices = match owner {
+                Some(owner) => match self.player_query.get(owner.0) {
+                    Ok(devices) => Some(devices),
+                    Err(_) => continue,
+                },
+                None => None,
+            };
+            let owner = owner.map(|owner| owner.0);
             if !set.enabled {
                 continue;
             };
-            let (pre_mul_delta_time_all, unbounded_all) = modifications
+            let delta_secs = match fixed_deltas.is_some() {
+                true => self.fixed_time.timestep().as_secs_f32(),
+                false => self.time.delta_secs(),
+            };
+            let (pre_mul_delta_time_all, unbounded_all, processors_all) = modifications
                 .all_paths
                 .as_ref()
                 .and_then(|v| self.binding_modification_query.get(v.0).ok())
                 .unwrap_or_default();
 
-            let binding_iter = bindings(&data);
-
-            let all_binding_values = binding_iter
-                .iter()
-                .flat_map(|binding_data| {
-                    if let Some(input) = input.as_ref()
-                        && input
-                            .0
-                            .get(&label_id)
-                            .is_some_and(|v| v.contains(&binding_id(binding_data)))
-                    {
-                        return Vec::new();
+            resolved.clear();
+            for (path, modification) in modifications.per_path.iter() {
+                let Ok((premul_delta_time, unbounded, processors)) =
+                    self.binding_modification_query.get(modification.0)
+                else {
+                    continue;
+                };
+                resolved.push(ResolvedModification {
+                    path: *path,
+                    premul_delta_time,
+                    unbounded,
+                    processors: processors.is_some().then_some(modification.0),
+                });
+            }
+            let path_query = &self.path_query;
+            let consumed_inputs = &*self.consumed_inputs;
+            let resolved = &*resolved;
+            // the per path modifications whose path matches the binding
+            let mask_len = resolved.len().div_ceil(64);
+            let modification_mask = |binding: &Binding| {
+                let mut mask = ModificationMask::from_elem(0, mask_len);
+                for (i, _) in resolved.iter().enumerate().filter(|(_, m)| {
+                    path_query
+                        .get(*m.path)
+                        .is_ok_and(|path_data| path_matches(binding, &path_data))
+                }) {
+                    mask[i / 64] |= 1 << (i % 64);
+                }
+                mask
+            };
+            let is_blocked = |binding: &Binding| {
+                let id = binding_id(binding);
+                input.is_some_and(|input| input.is_blocked(label_id, id))
+                    || consumed_inputs.is_consumed(label_id, id, priority, owner)
+            };
+            let (is_bool, is_f32, is_vec2) = (bool.is_some(), f32.is_some(), vec2.is_some());
+            // processors see the raw values, the delta gets applied after them
+            let is_processed = |mask: &ModificationMask| {
+                processors_all.is_some()
+                    || mask_bits(mask).any(|i| resolved[i].processors.is_some())
+            };
+            let deferred_delta = |mask: &ModificationMask| {
+                let premul_delta_time = pre_mul_delta_time_all
+                    || mask_bits(mask).any(|i| resolved[i].premul_delta_time);
+                match premul_delta_time && is_processed(mask) {
+                    true => delta_secs,
+                    false => 1.0,
+                }
+            };
+            let mut evaluate = |binding: &Binding,
+                                mask: &ModificationMask,
+                                path: Option<(SubactionPath, &PathItem<'_, '_, PathData>)>,
+                                out: &mut Vec<BindingValue>| {
+                let mods = resolved
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| mask_contains(mask, *i));
+                let mut binding_modifications = Modifications {
+                    inner: modifications,
+                    premul_delta_time: pre_mul_delta_time_all,
+                    unbounded: unbounded_all,
+                    delta_secs,
+                };
+                for (_, m) in mods {
+                    binding_modifications.premul_delta_time |= m.premul_delta_time;
+                    binding_modifications.unbounded |= m.unbounded;
+                }
+                binding_modifications.premul_delta_time &= !is_processed(mask);
+                out.clear();
+                update_for_binding(
+                    binding,
+                    &mut state,
+                    path.map(|(_, data)| data),
+                    &GenericBindingData {
+                        action,
+                        blocked_inputs: input,
+                        consumed_inputs,
+                        priority,
+                        owner,
+                        devices,
+                        subaction_path: path.map(|(path, _)| path),
+                        modifications: binding_modifications,
+                        is_bool,
+                        is_f32,
+                        is_vec2,
+                    },
+                    out,
+                );
+            };
+            let tracks_deltas = fixed_deltas.is_some();
+            binding_values.clear();
+            deltas.clear();
+            let mut index = 0;
+            bindings(&action_bindings, &mut |binding| {
+                let i = index;
+                index += 1;
+                if is_blocked(binding) {
+                    return;
+                }
+                let mask = modification_mask(binding);
+                evaluate(binding, &mask, None, out);
+                if out.iter().any(BindingValue::is_triggered) {
+                    if consumes {
+                        pending_consumed.push((
+                            binding_id(binding),
+                            priority,
+                            owner,
+                            BlockingAction {
+                                set: action.set,
+                                action: entity,
+                            },
+                        ));
                     }
-                    let mut binding_modifications = Modifications {
-                        inner: modifications,
-                        premul_delta_time: pre_mul_delta_time_all,
-                        unbounded: unbounded_all,
-                    };
-                    for (mod_sub_path, modification) in modifications.per_path.iter().copied() {
-                        let Ok(path_data) = self.path_query.get(*mod_sub_path) else {
-                            continue;
-                        };
-                        if path_matches(binding_data, &path_data) {
-                            let Ok((pre_mul_delta_time, unbounded)) =
-                                self.binding_modification_query.get(modification.0)
-                            else {
-                                continue;
-                            };
-                            binding_modifications.premul_delta_time |= pre_mul_delta_time;
-                            binding_modifications.unbounded |= unbounded;
-                        }
+                    if let Some(contributing) = contributing.as_mut() {
+                        contributing.push(label_id, binding_id(binding));
                     }
-
-                    update_for_binding(
-                        binding_data,
-                        &mut data,
-                        None,
-                        &GenericBindingData {
-                            action,
-                            subaction_path: None,
-                            modifications: binding_modifications,
-                            is_bool: bool.is_some(),
-                            is_f32: f32.is_some(),
-                            is_vec2: vec2.is_some(),
-                        },
-                    )
-                })
-                .collect::<Vec<_>>();
+                }
+                if tracks_deltas && is_delta(binding) {
+                    deltas.extend(out.iter().map(|value| (mask.clone(), *value)));
+                }
+                binding_values.extend(out.drain(..).map(|value| (i, mask.clone(), value)));
+            });
+            values.clear();
+            values.extend(
+                binding_values
+                    .iter()
+                    .map(|(_, mask, value)| (mask.clone(), *value)),
+            );
+            let processors = |i: usize| {
+                self.binding_modification_query
+                    .get(resolved[i].processors?)
+                    .ok()?
+                    .2
+            };
             apply_values(
+                label_id,
                 None,
-                all_binding_values,
+                values,
+                processors_all,
+                processors,
+                deferred_delta,
+                *aggregation,
+                &mut aggregation_state,
                 vec2.as_mut(),
                 f32.as_mut(),
                 bool.as_mut(),
             );
+            if let Some(fixed_deltas) = fixed_deltas.as_mut() {
+                let delta = process_values(deltas, processors_all, processors, deferred_delta);
+                fixed_deltas.add(None, delta);
+            }
 
             for sub_path in req_sub_paths.iter() {
-                let Ok(path_data) = self.path_query.get(**sub_path) else {
+                let Ok(path_data) = path_query.get(**sub_path) else {
                     continue;
                 };
-                let mut out = Vec::<BindingValue>::new();
-                for binding in binding_iter.iter() {
-                    if let Some(input) = input
-                        && input
-                            .0
-                            .get(&label_id)
-                            .is_some_and(|v| v.contains(&binding_id(binding)))
-                    {
-                        continue;
-                    }
-                    if !path_matches(binding, &path_data) {
-                        continue;
-                    }
-                    // TODO: precompute this
-                    let mut binding_modifications = Modifications {
-                        inner: modifications,
-                        premul_delta_time: pre_mul_delta_time_all,
-                        unbounded: unbounded_all,
-                    };
-                    for (mod_sub_path, modification) in modifications.per_path.iter().copied() {
-                        let Ok(path_data) = self.path_query.get(*mod_sub_path) else {
-                            continue;
-                        };
-                        if path_matches(binding, &path_data) {
-                            let Ok((pre_mul_delta_time, unbounded)) =
-                                self.binding_modification_query.get(modification.0)
-                            else {
-                                continue;
-                            };
-                            binding_modifications.premul_delta_time |= pre_mul_delta_time;
-                            binding_modifications.unbounded |= unbounded;
+                values.clear();
+                deltas.clear();
+                let mut index = 0;
+                let mut cursor = 0;
+                match path_changes_values(&path_data) {
+                    false => bindings(&action_bindings, &mut |binding| {
+                        let i = index;
+                        index += 1;
+                        while binding_values.get(cursor).is_some_and(|(b, _, _)| *b < i) {
+                            cursor += 1;
                         }
-                    }
-                    out.extend(update_for_binding(
-                        binding,
-                        &mut data,
-                        Some(&path_data),
-                        &GenericBindingData {
-                            action,
-                            subaction_path: Some(*sub_path),
-                            modifications: binding_modifications,
-                            is_bool: bool.is_some(),
-                            is_f32: f32.is_some(),
-                            is_vec2: vec2.is_some(),
-                        },
-                    ))
+                        if !path_matches(binding, &path_data) {
+                            return;
+                        }
+                        let binding_out = binding_values[cursor..]
+                            .iter()
+                            .take_while(|(b, _, _)| *b == i)
+                            .map(|(_, mask, value)| (mask.clone(), *value));
+                        if tracks_deltas && is_delta(binding) {
+                            deltas.extend(binding_out.clone());
+                        }
+                        values.extend(binding_out);
+                    }),
+                    true => bindings(&action_bindings, &mut |binding| {
+                        if is_blocked(binding) || !path_matches(binding, &path_data) {
+                            return;
+                        }
+                        let mask = modification_mask(binding);
+                        evaluate(binding, &mask, Some((*sub_path, &path_data)), out);
+                        if tracks_deltas && is_delta(binding) {
+                            deltas.extend(out.iter().map(|value| (mask.clone(), *value)));
+                        }
+                        values.extend(out.drain(..).map(|value| (mask.clone(), value)));
+                    }),
                 }
                 apply_values(
+                    label_id,
                     Some(*sub_path),
-                    out,
+                    values,
+                    processors_all,
+                    processors,
+                    deferred_delta,
+                    *aggregation,
+                    &mut aggregation_state,
                     vec2.as_mut(),
                     f32.as_mut(),
                     bool.as_mut(),
                 );
+                if let Some(fixed_deltas) = fixed_deltas.as_mut() {
+                    let delta = process_values(deltas, processors_all, processors, deferred_delta);
+                    fixed_deltas.add(Some(*sub_path), delta);
+                }
             }
         }
+        for (id, priority, owner, by) in pending_consumed.drain(..) {
+            self.consumed_inputs
+                .consume_below(label_id, id, priority, owner, by);
+        }
     }
 }
 
-fn apply_values(
+/// Providers that don't write any values, e.g. while an
+/// [`InputRecording`](crate::recording::InputRecording) is played back.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct DisabledProviders(HashSet<u64>);
+
+impl DisabledProviders {
+    pub fn disable(&mut self, label: &str) {
+        self.0.insert(label_id(label));
+    }
+    pub fn enable(&mut self, label: &str) {
+        self.0.remove(&label_id(label));
+    }
+    pub fn is_disabled(&self, label: &str) -> bool {
+        self.is_disabled_id(label_id(label))
+    }
+    pub(crate) fn is_disabled_id(&self, label_id: u64) -> bool {
+        self.0.contains(&label_id)
+    }
+}
+
+pub(crate) fn label_id(label: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    label.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Processes the values with [`process_values`] and aggregates the result into the action values.
+fn apply_values<'a>(
+    source: u64,
     sub_path: Option<SubactionPath>,
-    iter: impl IntoIterator<Item = BindingValue>,
+    values: &mut [(ModificationMask, BindingValue)],
+    all_paths: Option<&ProcessorChain>,
+    processors: impl Fn(usize) -> Option<&'a ProcessorChain>,
+    delta_multiplier: impl Fn(&ModificationMask) -> f32,
+    aggregation: ValueAggregation,
+    state: &mut AggregationState,
     vec2: Option<&mut Mut<'_, Vec2ActionValue>>,
     f32: Option<&mut Mut<'_, F32ActionValue>>,
     bool: Option<&mut Mut<'_, BoolActionValue>>,
 ) {
-    let mut out_vec2 = Vec2::ZERO;
-    let mut out_bool = false;
-    let mut out_f32 = 0f32;
+    let Some(out) = process_values(values, all_paths, processors, delta_multiplier) else {
+        return;
+    };
+    aggregate_value(source, sub_path, out, aggregation, state, vec2, f32, bool);
+}
+
+/// Composes the values of the bindings with the same per path modifications, runs their
+/// [`ProcessorChain`] on the result, applies the delta held back for the processors
+/// and composes the results.
+fn process_values<'a>(
+    values: &mut [(ModificationMask, BindingValue)],
+    all_paths: Option<&ProcessorChain>,
+    processors: impl Fn(usize) -> Option<&'a ProcessorChain>,
+    delta_multiplier: impl Fn(&ModificationMask) -> f32,
+) -> Option<BindingValue> {
+    if values.is_empty() {
+        return None;
+    }
+    match all_paths.is_none() && values.iter().all(|(mask, _)| mask.iter().all(|w| *w == 0)) {
+        true => compose_values(values.iter().map(|(_, value)| *value)),
+        false => {
+            values.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+            let groups = values
+                .chunk_by(|(a, _), (b, _)| a == b)
+                .filter_map(|group| {
+                    let mut value = compose_values(group.iter().map(|(_, value)| *value))?;
+                    if let Some(all_paths) = all_paths {
+                        value = all_paths.process(value);
+                    }
+                    for processors in mask_bits(&group[0].0).filter_map(&processors) {
+                        value = processors.process(value);
+                    }
+                    let delta = delta_multiplier(&group[0].0);
+                    value.f32 = value.f32.map(|v| v * delta);
+                    value.vec2 = value.vec2.map(|v| v * delta);
+                    Some(value)
+                });
+            compose_values(groups)
+        }
+    }
+}
+
+fn compose_values(iter: impl IntoIterator<Item = BindingValue>) -> Option<BindingValue> {
+    let mut iter = iter.into_iter().peekable();
+    iter.peek()?;
+    let mut out = BindingValue::default();
     for data in iter {
-        // this is incompatible with the OpenXR spec
-        // the spec states that the longest vec should be picked
         if let Some(data) = data.vec2 {
-            out_vec2 += data;
+            *out.vec2.get_or_insert_default() += data;
         }
-        // this is incompatible with the OpenXR spec
-        // the spec states that the float with the largest absolute value should be picked
         if let Some(data) = data.f32 {
-            out_f32 += data;
+            *out.f32.get_or_insert_default() += data;
         }
         if let Some(data) = data.bool {
-            out_bool |= data;
-        }
-    }
-
-    if let Some(path) = sub_path {
-        if let Some(vec2) = vec2 {
-            *vec2.entry_with_path(path).or_default() += out_vec2;
-        }
-        if let Some(f32) = f32 {
-            *f32.entry_with_path(path).or_default() += out_f32;
-        }
-        if let Some(bool) = bool {
-            *bool.entry_with_path(path).or_default() |= out_bool;
-        }
-    } else {
-        if let Some(vec2) = vec2 {
-            *vec2.0 += out_vec2;
-        }
-        if let Some(f32) = f32 {
-            *f32.0 += out_f32;
-        }
-        if let Some(bool) = bool {
-            *bool.0 |= out_bool;
+            *out.bool.get_or_insert_default() |= data;
         }
     }
+    Some(out)
 }
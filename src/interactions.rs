@@ -0,0 +1,145 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    time::Duration,
+};
+
+use bevy::{ecs::change_detection::Tick, platform::collections::HashMap, prelude::*};
+
+use crate::ButtonInputBeheavior;
+
+/// Keeps the state of the bindings of an action that use a stateful [`ButtonInputBeheavior`],
+/// automatically added to actions with keyboard, mouse or gamepad bindings.
+#[derive(Clone, Debug, Default, Component)]
+pub struct BindingInteractions {
+    states: HashMap<u64, InteractionState>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct InteractionState {
+    last_update: Option<Tick>,
+    output: bool,
+    pressed: bool,
+    pressed_at: Duration,
+    last_tap: Option<Duration>,
+    next_repeat: Duration,
+    toggled: bool,
+    hold_progress: Option<f32>,
+    evaluated: bool,
+}
+
+impl BindingInteractions {
+    /// The progress towards the activation of the furthest progressed
+    /// [`ButtonInputBeheavior::Hold`] binding, in the range 0..=1,
+    /// [`None`] if no hold binding is held down.
+    pub fn hold_progress(&self) -> Option<f32> {
+        self.states
+            .values()
+            .filter_map(|state| state.hold_progress)
+            .reduce(f32::max)
+    }
+
+    /// Evaluates a stateful behavior, the state is only advanced once per `tick`
+    pub fn evaluate(
+        &mut self,
+        key: u64,
+        behavior: ButtonInputBeheavior,
+        pressed: bool,
+        time: &Time,
+        tick: Tick,
+    ) -> bool {
+        let state = self.states.entry(key).or_default();
+        if state.last_update == Some(tick) {
+            return state.output;
+        }
+        state.last_update = Some(tick);
+        state.evaluated = true;
+        let now = time.elapsed();
+        let just_pressed = pressed && !state.pressed;
+        let just_released = !pressed && state.pressed;
+        state.pressed = pressed;
+        if just_pressed {
+            state.pressed_at = now;
+        }
+        state.hold_progress = None;
+
+        state.output = match behavior {
+            ButtonInputBeheavior::JustPressed => just_pressed,
+            ButtonInputBeheavior::Pressed => pressed,
+            ButtonInputBeheavior::JustReleased => just_released,
+            ButtonInputBeheavior::Hold(duration) => {
+                if pressed {
+                    let held = now - state.pressed_at;
+                    state.hold_progress = Some(match duration.is_zero() {
+                        true => 1.0,
+                        false => (held.as_secs_f32() / duration.as_secs_f32()).min(1.0),
+                    });
+                    held >= duration
+                } else {
+                    false
+                }
+            }
+            ButtonInputBeheavior::Tap(duration) => {
+                just_released && now - state.pressed_at <= duration
+            }
+            ButtonInputBeheavior::DoubleTap(duration) => {
+                if just_pressed {
+                    match state.last_tap.take() {
+                        Some(last) if now - last <= duration => true,
+                        _ => {
+                            state.last_tap = Some(now);
+                            false
+                        }
+                    }
+                } else {
+                    false
+                }
+            }
+            ButtonInputBeheavior::Toggle => {
+                if just_pressed {
+                    state.toggled = !state.toggled;
+                }
+                state.toggled
+            }
+            ButtonInputBeheavior::Repeat { delay, rate } => {
+                if just_pressed {
+                    state.next_repeat = now + delay;
+                    true
+                } else if pressed && now >= state.next_repeat {
+                    state.next_repeat = now + rate;
+                    true
+                } else {
+                    false
+                }
+            }
+        };
+        state.output
+    }
+}
+
+/// Bindings that weren't evaluated last frame, e.g. because they were blocked by a higher
+/// priority set, start holding again once they are evaluated.
+pub(crate) fn reset_interactions(mut query: Query<&mut BindingInteractions>, time: Res<Time>) {
+    for mut interactions in &mut query {
+        for state in interactions.states.values_mut() {
+            if !state.evaluated {
+                state.pressed_at = time.elapsed();
+            }
+            state.evaluated = false;
+            state.hold_progress = None;
+        }
+    }
+}
+
+/// Builds the key used by [`BindingInteractions`] from the binding id,
+/// the behavior and the device the input came from.
+pub fn interaction_key(
+    binding_id: u64,
+    behavior: ButtonInputBeheavior,
+    device: Option<Entity>,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    binding_id.hash(&mut hasher);
+    behavior.hash(&mut hasher);
+    device.hash(&mut hasher);
+    hasher.finish()
+}
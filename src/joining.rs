@@ -0,0 +1,197 @@
+use bevy::prelude::*;
+
+use crate::{
+    Action, ActionsInSet, BoolActionValue, SchminputSystems,
+    gamepad::{GamepadBindings, GamepadIdentifier},
+    players::{OwnedByPlayer, Player, PlayerActionSets, PlayerCommandsExt as _, PlayerDevices},
+    subaction_paths::{RequestedSubactionPaths, SubactionPaths},
+};
+
+/// While [`JoinFlow::active`] is set, the first press on a device that no player owns
+/// spawns a [`Player`] for it.
+pub struct JoinPlugin;
+
+impl Plugin for JoinPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<JoinFlow>();
+        app.register_type::<LeaveAction>();
+        app.add_message::<PlayerJoined>();
+        app.add_message::<PlayerLeft>();
+        app.add_systems(
+            PreUpdate,
+            join.after(bevy::input::InputSystems)
+                .before(SchminputSystems::HandleNewSubactionPaths),
+        );
+        app.add_systems(PreUpdate, leave.after(SchminputSystems::SyncInputActions));
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct JoinFlow {
+    pub active: bool,
+    pub max_players: u32,
+    /// Copied for every player that joins, keep these disabled.
+    pub action_sets: Vec<Entity>,
+    /// The keyboard and mouse can join together as one device.
+    pub allow_keyboard_mouse: bool,
+}
+
+impl Default for JoinFlow {
+    fn default() -> Self {
+        Self {
+            active: false,
+            max_players: 4,
+            action_sets: Vec::new(),
+            allow_keyboard_mouse: true,
+        }
+    }
+}
+
+/// The device a [`Player`] joined with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum JoinDevice {
+    Gamepad(Entity),
+    KeyboardMouse,
+}
+
+#[derive(Clone, Copy, Debug, Message)]
+pub struct PlayerJoined {
+    pub player: Entity,
+    pub slot: u32,
+    pub device: JoinDevice,
+}
+
+/// Written when a player leaves through a [`LeaveAction`], the player and its action sets
+/// get despawned.
+#[derive(Clone, Copy, Debug, Message)]
+pub struct PlayerLeft {
+    pub player: Entity,
+    pub slot: u32,
+}
+
+/// The player owning the set of this action leaves when it becomes true,
+/// put it on an action in a [`JoinFlow::action_sets`] set.
+#[derive(Clone, Copy, Debug, Default, Component, Reflect)]
+pub struct LeaveAction;
+
+fn join(
+    flow: Res<JoinFlow>,
+    players: Query<(&Player, &PlayerDevices)>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    identifiers: Query<(Entity, &GamepadIdentifier)>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut joined: MessageWriter<PlayerJoined>,
+    mut cmds: Commands,
+) {
+    if !flow.active {
+        return;
+    }
+    let mut slots = players.iter().map(|(p, _)| p.slot).collect::<Vec<_>>();
+    let keyboard_owned = players.iter().any(|(_, d)| d.keyboard || d.mouse);
+    let keyboard_mouse = (flow.allow_keyboard_mouse
+        && !keyboard_owned
+        && (keyboard.get_just_pressed().next().is_some()
+            || mouse.get_just_pressed().next().is_some()))
+    .then_some(JoinDevice::KeyboardMouse);
+    let new_devices = gamepads
+        .iter()
+        .filter(|(e, _)| !players.iter().any(|(_, d)| d.gamepads.contains(e)))
+        .filter(|(_, g)| g.get_just_pressed().next().is_some())
+        .map(|(e, _)| JoinDevice::Gamepad(e))
+        .chain(keyboard_mouse);
+
+    for device in new_devices {
+        let Some(slot) = (0..flow.max_players).find(|slot| !slots.contains(slot)) else {
+            break;
+        };
+        slots.push(slot);
+        let devices = match device {
+            JoinDevice::Gamepad(gamepad) => PlayerDevices::new().with_gamepad(gamepad),
+            JoinDevice::KeyboardMouse => PlayerDevices::new().with_keyboard().with_mouse(),
+        };
+        let player = cmds.spawn((Player::new(slot), devices)).id();
+        for set in flow.action_sets.iter() {
+            cmds.copy_action_set_for_player(*set, player);
+        }
+        if let JoinDevice::Gamepad(gamepad) = device {
+            let id = (slot + 1).to_string();
+            // the gamepad that had the identifier gets the old one of the joining gamepad
+            let old_id = identifiers.get(gamepad).ok().map(|(_, id)| id.clone());
+            if let Some((other, _)) = identifiers
+                .iter()
+                .find(|(e, v)| *e != gamepad && v.0.as_ref() == id.as_str())
+            {
+                match old_id {
+                    Some(old_id) => cmds.entity(other).insert(old_id),
+                    None => cmds.entity(other).remove::<GamepadIdentifier>(),
+                };
+            }
+            cmds.entity(gamepad)
+                .insert(GamepadIdentifier(id.clone().into()));
+            cmds.queue(move |world: &mut World| request_gamepad_path(world, player, id));
+        }
+        joined.write(PlayerJoined {
+            player,
+            slot,
+            device,
+        });
+    }
+}
+
+/// Adds `/gamepad/<id>` to the actions of the player that have gamepad bindings.
+fn request_gamepad_path(world: &mut World, player: Entity, id: String) {
+    let mut actions = Vec::new();
+    let sets = world
+        .get::<PlayerActionSets>(player)
+        .map(|sets| sets.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    for set in sets {
+        let Some(set_actions) = world.get::<ActionsInSet>(set) else {
+            continue;
+        };
+        actions.extend(
+            set_actions
+                .iter()
+                .filter(|action| world.get::<GamepadBindings>(*action).is_some()),
+        );
+    }
+    let path = world.resource_scope(|world, mut paths: Mut<SubactionPaths>| {
+        paths.get_or_create_path(format!("/gamepad/{id}"), &mut world.commands())
+    });
+    for action in actions {
+        if let Some(mut requested) = world.get_mut::<RequestedSubactionPaths>(action)
+            && !requested.contains(&path)
+        {
+            requested.push(path);
+        }
+    }
+}
+
+fn leave(
+    query: Query<(&Action, &BoolActionValue), With<LeaveAction>>,
+    set_query: Query<&OwnedByPlayer>,
+    players: Query<&Player>,
+    mut left: MessageWriter<PlayerLeft>,
+    mut cmds: Commands,
+) {
+    let mut leaving = Vec::new();
+    for (action, value) in &query {
+        let Ok(owner) = set_query.get(action.set) else {
+            continue;
+        };
+        if value.any && !leaving.contains(&owner.0) {
+            leaving.push(owner.0);
+        }
+    }
+    for player in leaving {
+        let Ok(p) = players.get(player) else {
+            continue;
+        };
+        cmds.entity(player).despawn();
+        left.write(PlayerLeft {
+            player,
+            slot: p.slot,
+        });
+    }
+}
@@ -1,12 +1,14 @@
 use std::hash::{DefaultHasher, Hash, Hasher};
 
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemChangeTick, prelude::*};
 
 use crate::{
+    ButtonInputBeheavior, InputAxis, InputAxisDirection, SchminputSystems,
     impl_helpers::{BindingValue, ProviderParam},
+    interactions::{BindingInteractions, interaction_key},
+    players::allows_keyboard,
     priorities::PriorityAppExt,
     subaction_paths::{SubactionPathCreated, SubactionPathStr},
-    ButtonInputBeheavior, InputAxis, InputAxisDirection, SchminputSystems,
 };
 
 impl Plugin for KeyboardPlugin {
@@ -19,7 +21,7 @@ impl Plugin for KeyboardPlugin {
             PreUpdate,
             handle_new_subaction_paths.in_set(SchminputSystems::HandleNewSubactionPaths),
         );
-        app.add_binding_id_system(
+        app.add_tracked_binding_id_system::<KeyboardBindings, _>(
             "schminput:keyboard",
             |entity: In<Entity>, query: Query<&KeyboardBindings>| {
                 let Ok(bindings) = query.get(entity.0) else {
@@ -28,12 +30,29 @@ impl Plugin for KeyboardPlugin {
                 bindings.0.iter().map(get_binding_id).collect()
             },
         );
+        app.add_binding_description_system(
+            "schminput:keyboard",
+            |entity: In<Entity>, query: Query<&KeyboardBindings>| {
+                let Ok(bindings) = query.get(entity.0) else {
+                    return Vec::new();
+                };
+                bindings
+                    .0
+                    .iter()
+                    .map(|b| (get_binding_id(b), format!("keyboard:{:?}", b.key)))
+                    .collect()
+            },
+        );
     }
 }
 
 fn get_binding_id(binding: &KeyboardBinding) -> u64 {
+    key_binding_id(binding.key)
+}
+
+pub(crate) fn key_binding_id(key: KeyCode) -> u64 {
     let mut hasher = DefaultHasher::new();
-    binding.key.hash(&mut hasher);
+    key.hash(&mut hasher);
     hasher.finish()
 }
 
@@ -44,7 +63,7 @@ pub fn handle_new_subaction_paths(
 ) {
     for (e, str) in reader
         .read()
-        .filter_map(|e| Some((e.0 .0, query.get(e.0 .0).ok()?)))
+        .filter_map(|e| Some((e.0.0, query.get(e.0.0).ok()?)))
     {
         if str.0.strip_prefix("/keyboard").is_some() {
             cmds.entity(e).insert(KeyboardSubactionPath);
@@ -54,30 +73,41 @@ pub fn handle_new_subaction_paths(
 
 #[allow(clippy::type_complexity)]
 pub fn sync_actions(
-    mut query: ProviderParam<&KeyboardBindings, Has<KeyboardSubactionPath>>,
+    mut query: ProviderParam<
+        &KeyboardBindings,
+        &mut BindingInteractions,
+        Has<KeyboardSubactionPath>,
+    >,
     time: Res<Time>,
     input: Res<ButtonInput<KeyCode>>,
+    ticks: SystemChangeTick,
 ) {
-    query.run(
+    query.run_with_deltas(
         "schminput:keyboard",
+        |binding| binding.behavior.is_momentary(),
         get_binding_id,
         |_, v| *v,
-        |bindings| bindings.0.clone(),
-        |binding, _, _, data| {
-            let delta_multiplier = match data.modifications.premul_delta_time {
-                true => time.delta_secs(),
-                false => 1.0,
-            };
-            let bool = data
-                .is_bool
-                .then(|| binding.behavior.apply(&input, binding.key));
-            let f32 = data.is_f32.then(|| {
-                binding.behavior.apply(&input, binding.key) as u8 as f32
-                    * binding.axis_dir.as_multipier()
-                    * delta_multiplier
-            });
+        |_| false,
+        |bindings, visit| bindings.0.iter().for_each(visit),
+        |binding, interactions, _, data, out| {
+            if !allows_keyboard(data.devices) {
+                return;
+            }
+            let delta_multiplier = data.modifications.delta_multiplier();
+            let active = binding.behavior.apply_with_state(
+                &input,
+                binding.key,
+                interactions,
+                interaction_key(get_binding_id(binding), binding.behavior, None),
+                &time,
+                ticks.this_run(),
+            );
+            let bool = data.is_bool.then_some(active);
+            let f32 = data
+                .is_f32
+                .then(|| active as u8 as f32 * binding.axis_dir.as_multipier() * delta_multiplier);
             let vec2 = data.is_vec2.then(|| {
-                let val = binding.behavior.apply(&input, binding.key) as u8 as f32;
+                let val = active as u8 as f32;
                 match binding.axis {
                     InputAxis::X => Vec2::new(
                         val * binding.axis_dir.as_multipier() * delta_multiplier,
@@ -90,7 +120,7 @@ pub fn sync_actions(
                 }
             });
 
-            vec![BindingValue { vec2, bool, f32 }]
+            out.push(BindingValue { vec2, bool, f32 });
         },
     );
 }
@@ -100,6 +130,7 @@ pub fn sync_actions(
 pub struct KeyboardSubactionPath;
 
 #[derive(Clone, Debug, Default, Component, Reflect)]
+#[require(BindingInteractions)]
 pub struct KeyboardBindings(pub Vec<KeyboardBinding>);
 
 impl KeyboardBindings {
@@ -178,6 +209,11 @@ impl KeyboardBinding {
         self.behavior = ButtonInputBeheavior::JustReleased;
         self
     }
+
+    pub fn behavior(mut self, behavior: ButtonInputBeheavior) -> KeyboardBinding {
+        self.behavior = behavior;
+        self
+    }
 }
 
 pub struct KeyboardPlugin;
@@ -1,30 +1,69 @@
+pub mod action_events;
+pub mod action_state;
+pub mod aggregation;
 pub mod binding_modification;
+pub mod chords;
+pub mod contexts;
+pub mod debug;
+pub mod fixed;
 pub mod gamepad;
 pub mod impl_helpers;
+pub mod interactions;
+pub mod joining;
 pub mod keyboard;
+pub mod lookup;
+#[cfg(feature = "manifest")]
+pub mod manifest;
 pub mod mouse;
 #[cfg(feature = "xr")]
 pub mod openxr;
+pub mod players;
 pub mod prelude;
 pub mod priorities;
+pub mod processors;
+pub mod recording;
+pub mod sequences;
+pub mod snapshot;
 pub mod subaction_paths;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod typed;
+pub mod validation;
 #[cfg(feature = "xr")]
 pub mod xr;
 
-use std::{borrow::Cow, fmt::Display, hash::Hash, mem};
+use std::{borrow::Cow, fmt::Display, hash::Hash, mem, time::Duration};
 
-use bevy::{app::PluginGroupBuilder, ecs::entity::EntityHashSet, prelude::*};
+use action_events::ActionEventsPlugin;
+use action_state::{ActionStatePlugin, BoolActionState, F32ActionState, Vec2ActionState};
+use aggregation::{AggregationPlugin, ValueAggregation};
+use bevy::{
+    app::PluginGroupBuilder,
+    ecs::{change_detection::Tick, entity::EntityHashSet},
+    prelude::*,
+};
 use binding_modification::BindingModifications;
+use interactions::BindingInteractions;
 use priorities::PrioritiesPlugin;
 use subaction_paths::{RequestedSubactionPaths, SubactionPathMap, SubactionPathPlugin};
 
+#[cfg(feature = "derive")]
+pub use schminput_derive::ActionSet;
+
 #[derive(SystemSet, Clone, Copy, Debug, Reflect, Hash, PartialEq, Eq)]
 pub enum SchminputSystems {
     HandleNewSubactionPaths,
     ClearValues,
     CalculateBindingCollisions,
+    BuildProcessorChains,
     SyncInputActions,
+    AccumulateFixedValues,
+    /// Action values that are the same as last frame are no longer marked as changed after this.
+    UpdateActionStates,
+    TriggerActionEvents,
     SyncOutputActions,
+    /// Runs in [`FixedPreUpdate`]
+    TickFixedValues,
 }
 
 pub struct SchminputPlugin;
@@ -33,25 +72,41 @@ impl Plugin for SchminputPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<InputAxis>();
         app.register_type::<InputAxisDirection>();
+        app.init_resource::<impl_helpers::DisabledProviders>();
         app.configure_sets(
             PreUpdate,
             (
                 SchminputSystems::HandleNewSubactionPaths,
                 SchminputSystems::ClearValues,
                 SchminputSystems::CalculateBindingCollisions,
+                SchminputSystems::BuildProcessorChains,
                 SchminputSystems::SyncInputActions,
+                SchminputSystems::AccumulateFixedValues,
+                SchminputSystems::UpdateActionStates,
+                SchminputSystems::TriggerActionEvents,
             )
                 .chain(),
         );
+        // read the input of this frame, not the one of the last frame
+        app.configure_sets(
+            PreUpdate,
+            SchminputSystems::HandleNewSubactionPaths.after(bevy::input::InputSystems),
+        );
         // Probably not needed, but for reference,
         app.configure_sets(PostUpdate, SchminputSystems::SyncOutputActions);
 
         app.add_systems(PreUpdate, clean_bool.in_set(SchminputSystems::ClearValues));
         app.add_systems(PreUpdate, clean_f32.in_set(SchminputSystems::ClearValues));
         app.add_systems(PreUpdate, clean_vec2.in_set(SchminputSystems::ClearValues));
+        app.add_systems(
+            PreUpdate,
+            interactions::reset_interactions.in_set(SchminputSystems::ClearValues),
+        );
     }
 }
 
+// values are reset every frame so deltas like mouse motion don't accumulate,
+// the change ticks of values that end up the same are restored when updating the states
 fn clean_bool(mut query: Query<&mut BoolActionValue>) {
     for mut val in &mut query {
         let _last = mem::take(val.as_mut());
@@ -76,9 +131,25 @@ impl PluginGroup for DefaultSchminputPlugins {
             .add(SchminputPlugin)
             .add(SubactionPathPlugin)
             .add(PrioritiesPlugin)
+            .add(AggregationPlugin)
+            .add(processors::ProcessorPlugin)
+            .add(ActionStatePlugin)
+            .add(ActionEventsPlugin)
+            .add(fixed::FixedActionValuesPlugin)
+            .add(recording::RecordingPlugin)
+            .add(snapshot::SnapshotPlugin)
+            .add(players::PlayerPlugin)
+            .add(joining::JoinPlugin)
+            .add(contexts::ContextPlugin)
+            .add(validation::ValidationPlugin)
+            .add(debug::BlockingDebugPlugin)
+            .add(debug::ActionDebugPlugin)
+            .add(lookup::ActionIndexPlugin)
             .add(keyboard::KeyboardPlugin)
             .add(mouse::MousePlugin)
-            .add(gamepad::GamepadPlugin);
+            .add(gamepad::GamepadPlugin)
+            .add(chords::ChordPlugin)
+            .add(sequences::SequencePlugin);
         #[cfg(feature = "xr")]
         let g = g.add(xr::GenericXrInputPlugin);
         #[cfg(all(feature = "xr", not(target_family = "wasm")))]
@@ -88,7 +159,7 @@ impl PluginGroup for DefaultSchminputPlugins {
 }
 
 #[derive(Debug, Clone, Reflect, Component)]
-#[require(RequestedSubactionPaths, BindingModifications)]
+#[require(RequestedSubactionPaths, BindingModifications, ValueAggregation)]
 #[relationship(relationship_target = ActionsInSet)]
 pub struct Action {
     #[relationship]
@@ -151,12 +222,15 @@ pub struct ActionsInSet(EntityHashSet);
 
 /// +X: Right, +Y: Up
 #[derive(Debug, Clone, Component, Reflect, Deref, DerefMut, Default)]
+#[require(Vec2ActionState)]
 pub struct Vec2ActionValue(pub SubactionPathMap<Vec2>);
 
 #[derive(Debug, Clone, Component, Reflect, Deref, DerefMut, Default)]
+#[require(F32ActionState)]
 pub struct F32ActionValue(pub SubactionPathMap<f32>);
 
 #[derive(Debug, Clone, Component, Reflect, Deref, DerefMut, Default)]
+#[require(BoolActionState)]
 pub struct BoolActionValue(pub SubactionPathMap<bool>);
 
 impl Vec2ActionValue {
@@ -178,6 +252,7 @@ impl BoolActionValue {
 // there might be a better name for this
 /// +X = Right, +Y = Up
 #[derive(Clone, Copy, Debug, Reflect, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "manifest", derive(serde::Deserialize))]
 pub enum InputAxis {
     X,
     #[default]
@@ -216,6 +291,7 @@ impl InputAxis {
 
 // there might be a better name for this
 #[derive(Clone, Copy, Debug, Reflect, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "manifest", derive(serde::Deserialize))]
 pub enum InputAxisDirection {
     #[default]
     Positive,
@@ -241,27 +317,79 @@ impl InputAxisDirection {
     }
 }
 
-// TODO: add released?
 #[derive(Clone, Copy, Debug, Reflect, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "manifest", derive(serde::Deserialize))]
 pub enum ButtonInputBeheavior {
     JustPressed,
     #[default]
     Pressed,
     JustReleased,
+    /// Active while the button has been held for at least the duration.
+    Hold(Duration),
+    /// Active on release if the button was held for at most the duration.
+    Tap(Duration),
+    /// Active on the second press if it happened within the duration of the first one.
+    DoubleTap(Duration),
+    /// Every press flips the value.
+    Toggle,
+    /// Active on press, then again after `delay` and every `rate` after that while held.
+    Repeat {
+        delay: Duration,
+        rate: Duration,
+    },
 }
 
 impl ButtonInputBeheavior {
+    /// [`None`] for the behaviors that need to keep state between frames,
+    /// use [`ButtonInputBeheavior::apply_with_state`] for those.
     pub fn apply<T: Copy + Eq + Hash + Send + Sync>(
         &self,
         input: &ButtonInput<T>,
         value: T,
-    ) -> bool {
+    ) -> Option<bool> {
         match self {
-            ButtonInputBeheavior::JustPressed => input.just_pressed(value),
-            ButtonInputBeheavior::Pressed => input.pressed(value),
-            ButtonInputBeheavior::JustReleased => input.just_released(value),
+            ButtonInputBeheavior::JustPressed => Some(input.just_pressed(value)),
+            ButtonInputBeheavior::Pressed => Some(input.pressed(value)),
+            ButtonInputBeheavior::JustReleased => Some(input.just_released(value)),
+            _ => None,
         }
     }
+    /// Like [`ButtonInputBeheavior::apply`] but also handles the behaviors that need to keep
+    /// state between frames, `key` has to be unique for the binding and device.
+    pub fn apply_with_state<T: Copy + Eq + Hash + Send + Sync>(
+        &self,
+        input: &ButtonInput<T>,
+        value: T,
+        interactions: &mut BindingInteractions,
+        key: u64,
+        time: &Time,
+        tick: Tick,
+    ) -> bool {
+        match self.apply(input, value) {
+            Some(active) => active,
+            None => interactions.evaluate(key, *self, input.pressed(value), time, tick),
+        }
+    }
+    /// Behaviors that are only active for single frames instead of while the button is held,
+    /// their presses are only seen by one fixed timestep, see [`fixed`].
+    pub fn is_momentary(&self) -> bool {
+        matches!(
+            self,
+            ButtonInputBeheavior::JustPressed
+                | ButtonInputBeheavior::JustReleased
+                | ButtonInputBeheavior::Tap(_)
+                | ButtonInputBeheavior::DoubleTap(_)
+                | ButtonInputBeheavior::Repeat { .. }
+        )
+    }
+    pub fn is_stateful(&self) -> bool {
+        !matches!(
+            self,
+            ButtonInputBeheavior::JustPressed
+                | ButtonInputBeheavior::Pressed
+                | ButtonInputBeheavior::JustReleased
+        )
+    }
 }
 impl Display for ButtonInputBeheavior {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -269,6 +397,11 @@ impl Display for ButtonInputBeheavior {
             ButtonInputBeheavior::JustPressed => "On Press",
             ButtonInputBeheavior::Pressed => "Pressed",
             ButtonInputBeheavior::JustReleased => "On Release",
+            ButtonInputBeheavior::Hold(_) => "Hold",
+            ButtonInputBeheavior::Tap(_) => "Tap",
+            ButtonInputBeheavior::DoubleTap(_) => "Double Tap",
+            ButtonInputBeheavior::Toggle => "Toggle",
+            ButtonInputBeheavior::Repeat { .. } => "Repeat",
         })
     }
 }
@@ -0,0 +1,124 @@
+use std::ops::Deref;
+
+use bevy::{ecs::system::SystemParam, platform::collections::HashMap, prelude::*};
+
+use crate::{
+    Action, ActionSet, ActionsInSet, SchminputSystems,
+    action_state::{ActionState, ActionValueType},
+    subaction_paths::{SubactionPathMap, SubactionPaths},
+    typed::{ActionKind, ActionRef},
+};
+
+pub struct ActionIndexPlugin;
+
+impl Plugin for ActionIndexPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionIndex>();
+        app.add_systems(
+            PreUpdate,
+            update_index.before(SchminputSystems::HandleNewSubactionPaths),
+        );
+    }
+}
+
+/// Maps `"set_name/action_name"` to the [`Action`] entity, updated in [`PreUpdate`]
+/// whenever actions or sets are spawned, despawned or renamed.
+#[derive(Resource, Debug, Default)]
+pub struct ActionIndex {
+    actions: HashMap<String, Entity>,
+}
+
+impl ActionIndex {
+    /// Looks up an action by `"set_name/action_name"`.
+    pub fn get(&self, name: &str) -> Option<Entity> {
+        self.actions.get(name).copied()
+    }
+    pub fn get_in_set(&self, set: &str, action: &str) -> Option<Entity> {
+        self.get(&format!("{set}/{action}"))
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Entity)> {
+        self.actions.iter().map(|(name, e)| (name.as_str(), *e))
+    }
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+}
+
+fn update_index(
+    mut index: ResMut<ActionIndex>,
+    changed_sets: Query<(), Or<(Changed<ActionSet>, Changed<ActionsInSet>)>>,
+    changed_actions: Query<(), Changed<Action>>,
+    mut removed_sets: RemovedComponents<ActionsInSet>,
+    sets: Query<(&ActionSet, &ActionsInSet)>,
+    actions: Query<&Action>,
+) {
+    let removed = removed_sets.read().count() > 0;
+    if !removed && changed_sets.is_empty() && changed_actions.is_empty() {
+        return;
+    }
+    index.actions.clear();
+    for (set, in_set) in &sets {
+        for action in in_set.iter() {
+            let Ok(action_data) = actions.get(action) else {
+                continue;
+            };
+            let name = format!("{}/{}", set.name, action_data.name);
+            if let Some(other) = index.actions.insert(name, action) {
+                warn!(
+                    "actions {other} and {action} are both named {}/{}, only one can be looked up by name",
+                    set.name, action_data.name
+                );
+            }
+        }
+    }
+}
+
+/// Reads actions with a `T` value by [`ActionIndex`] name or entity, e.g.
+/// `ActionReader<bool>`, with subaction paths given as strings.
+#[derive(SystemParam)]
+pub struct ActionReader<'w, 's, T: ActionKind + ActionValueType>
+where
+    <T as ActionKind>::Value: Deref<Target = SubactionPathMap<T>>,
+    ActionState<T>: Component,
+{
+    index: Res<'w, ActionIndex>,
+    paths: Res<'w, SubactionPaths>,
+    actions: Query<'w, 's, (&'static <T as ActionKind>::Value, &'static ActionState<T>)>,
+}
+
+impl<T: ActionKind + ActionValueType> ActionReader<'_, '_, T>
+where
+    <T as ActionKind>::Value: Deref<Target = SubactionPathMap<T>>,
+    ActionState<T>: Component,
+{
+    /// Accepts an [`Entity`] or a [`TypedAction`](crate::typed::TypedAction).
+    pub fn get(&self, action: impl Into<Entity>) -> ActionRef<'_, T> {
+        ActionRef::from_query(&self.actions, action.into())
+    }
+    /// Reads the action for the subaction path, the path has to be requested by the action.
+    pub fn get_with_path(&self, action: impl Into<Entity>, path: &str) -> ActionRef<'_, T> {
+        match self.paths.get(path.to_string()) {
+            Some(path) => self.get(action).with_path(path),
+            None => ActionRef::missing(),
+        }
+    }
+    /// Looks up the action by `"set_name/action_name"`.
+    pub fn by_name(&self, name: &str) -> ActionRef<'_, T> {
+        match self.index.get(name) {
+            Some(action) => self.get(action),
+            None => ActionRef::missing(),
+        }
+    }
+    pub fn by_name_with_path(&self, name: &str, path: &str) -> ActionRef<'_, T> {
+        match self.index.get(name) {
+            Some(action) => self.get_with_path(action, path),
+            None => ActionRef::missing(),
+        }
+    }
+    pub fn index(&self) -> &ActionIndex {
+        &self.index
+    }
+}
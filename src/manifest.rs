@@ -0,0 +1,501 @@
+use std::borrow::Cow;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    ecs::entity::EntityHashMap,
+    platform::collections::HashMap,
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::{
+    Action, ActionSet, BoolActionValue, ButtonInputBeheavior, F32ActionValue, InputAxis,
+    InputAxisDirection, SchminputSystems, Vec2ActionValue,
+    binding_modification::{
+        BindingModifications, PremultiplyDeltaSecsModification, UnboundedModification,
+    },
+    gamepad::{GamepadBinding, GamepadBindingSource, GamepadBindings},
+    keyboard::{KeyboardBinding, KeyboardBindings},
+    mouse::{
+        MouseBindings, MouseButtonBinding, MouseMotionBinding, MouseMotionType, MouseScrollBinding,
+        MouseScrollSource,
+    },
+    subaction_paths::{RequestedSubactionPaths, SubactionPaths},
+};
+
+/// Loads `.actions.ron` files as [`ActionManifest`]s and spawns them for every
+/// [`ActionManifestRoot`]. Enable bevy's `file_watcher` feature to update the
+/// entities when the file changes.
+pub struct ManifestPlugin;
+
+impl Plugin for ManifestPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<ActionManifest>();
+        app.init_asset_loader::<ActionManifestLoader>();
+        app.add_systems(
+            PreUpdate,
+            sync_manifests.before(SchminputSystems::HandleNewSubactionPaths),
+        );
+    }
+}
+
+/// Spawns the action sets of the manifest as children of this entity, despawning it
+/// despawns all of them.
+///
+/// When the manifest changes the existing entities are updated in place, identified by
+/// their names. Their bindings are replaced by the ones in the manifest, the enabled
+/// state of existing sets is kept. Modification entities are spawned as children of
+/// their action and reused in order, so components added to them from code, like
+/// [`InputProcessors`](crate::processors::InputProcessors), survive a reload.
+#[derive(Component, Clone, Debug)]
+#[require(ManifestEntities)]
+pub struct ActionManifestRoot(pub Handle<ActionManifest>);
+
+/// The entities spawned for an [`ActionManifestRoot`].
+#[derive(Component, Clone, Debug, Default)]
+pub struct ManifestEntities {
+    sets: HashMap<String, Entity>,
+    actions: HashMap<(String, String), Entity>,
+    modifications: EntityHashMap<Vec<Entity>>,
+}
+
+impl ManifestEntities {
+    pub fn set(&self, name: &str) -> Option<Entity> {
+        self.sets.get(name).copied()
+    }
+    pub fn action(&self, set: &str, action: &str) -> Option<Entity> {
+        self.actions
+            .get(&(set.to_string(), action.to_string()))
+            .copied()
+    }
+}
+
+#[derive(Asset, TypePath, Clone, Debug, Default, Deserialize)]
+pub struct ActionManifest {
+    pub sets: Vec<ActionSetManifest>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ActionSetManifest {
+    pub name: String,
+    /// Defaults to the name.
+    #[serde(default)]
+    pub localized_name: Option<String>,
+    #[serde(default)]
+    pub priority: u32,
+    #[serde(default)]
+    pub transparent: bool,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub actions: Vec<ActionManifestEntry>,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ActionManifestEntry {
+    pub name: String,
+    /// Defaults to the name.
+    #[serde(default)]
+    pub localized_name: Option<String>,
+    pub value: ManifestValue,
+    #[serde(default)]
+    pub subaction_paths: Vec<String>,
+    #[serde(default)]
+    pub keyboard: Vec<ManifestKeyboardBinding>,
+    #[serde(default)]
+    pub mouse: ManifestMouseBindings,
+    #[serde(default)]
+    pub gamepad: Vec<ManifestGamepadBinding>,
+    /// The OpenXR bindings per interaction profile, only used with the `xr` feature.
+    #[serde(default)]
+    pub openxr: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub modifications: ManifestModifications,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum ManifestValue {
+    Bool,
+    F32,
+    Vec2,
+    /// Only supported with the `xr` feature, without it the action is skipped.
+    Space,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ManifestKeyboardBinding {
+    pub key: KeyCode,
+    #[serde(default)]
+    pub axis: InputAxis,
+    #[serde(default)]
+    pub axis_dir: InputAxisDirection,
+    #[serde(default)]
+    pub behavior: ButtonInputBeheavior,
+    #[serde(default = "one")]
+    pub multiplier: f32,
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ManifestMouseBindings {
+    #[serde(default)]
+    pub buttons: Vec<ManifestMouseButtonBinding>,
+    #[serde(default)]
+    pub motion: Option<ManifestMouseMotionBinding>,
+    #[serde(default)]
+    pub scroll: Vec<ManifestMouseScrollBinding>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ManifestMouseButtonBinding {
+    pub button: MouseButton,
+    #[serde(default)]
+    pub axis: InputAxis,
+    #[serde(default)]
+    pub axis_dir: InputAxisDirection,
+    #[serde(default)]
+    pub behavior: ButtonInputBeheavior,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ManifestMouseMotionBinding {
+    #[serde(default)]
+    pub motion_type: MouseMotionType,
+    #[serde(default = "one")]
+    pub multiplier: f32,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ManifestMouseScrollBinding {
+    #[serde(default)]
+    pub source: MouseScrollSource,
+    #[serde(default)]
+    pub axis: InputAxis,
+    #[serde(default)]
+    pub axis_dir: InputAxisDirection,
+    #[serde(default)]
+    pub behavior: ButtonInputBeheavior,
+    #[serde(default = "one")]
+    pub multiplier: f32,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ManifestGamepadBinding {
+    pub source: GamepadBindingSource,
+    #[serde(default)]
+    pub axis: InputAxis,
+    #[serde(default)]
+    pub axis_dir: InputAxisDirection,
+    #[serde(default)]
+    pub behavior: ButtonInputBeheavior,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ManifestModifications {
+    #[serde(default)]
+    pub all_paths: Option<ManifestModification>,
+    /// Subaction paths and their modifications.
+    #[serde(default)]
+    pub per_path: Vec<(String, ManifestModification)>,
+}
+
+/// Processors can't be expressed in manifests, add
+/// [`InputProcessors`](crate::processors::InputProcessors) to the modification entities
+/// listed in the [`BindingModifications`] of the action instead.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+pub struct ManifestModification {
+    #[serde(default)]
+    pub premultiply_delta_secs: bool,
+    #[serde(default)]
+    pub unbounded: bool,
+}
+
+#[derive(Default, TypePath)]
+pub struct ActionManifestLoader;
+
+impl AssetLoader for ActionManifestLoader {
+    type Asset = ActionManifest;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<ActionManifest, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["actions.ron"]
+    }
+}
+
+fn sync_manifests(
+    mut events: MessageReader<AssetEvent<ActionManifest>>,
+    manifests: Res<Assets<ActionManifest>>,
+    mut roots: Query<(Entity, Ref<ActionManifestRoot>, &mut ManifestEntities)>,
+    set_query: Query<&ActionSet>,
+    mut paths: ResMut<SubactionPaths>,
+    mut cmds: Commands,
+) {
+    let changed = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    for (root, handle, mut entities) in &mut roots {
+        if !handle.is_changed() && !changed.contains(&handle.0.id()) {
+            continue;
+        }
+        let Some(manifest) = manifests.get(&handle.0) else {
+            continue;
+        };
+        spawn_manifest(
+            manifest,
+            root,
+            &mut entities,
+            &set_query,
+            &mut paths,
+            &mut cmds,
+        );
+    }
+}
+
+fn spawn_manifest(
+    manifest: &ActionManifest,
+    root: Entity,
+    entities: &mut ManifestEntities,
+    set_query: &Query<&ActionSet>,
+    paths: &mut SubactionPaths,
+    cmds: &mut Commands,
+) {
+    let mut old_sets = std::mem::take(&mut entities.sets);
+    let mut old_actions = std::mem::take(&mut entities.actions);
+    for set_manifest in manifest.sets.iter() {
+        let set = match old_sets.remove(&set_manifest.name) {
+            Some(set) => set,
+            None => cmds.spawn(ChildOf(root)).id(),
+        };
+        entities.sets.insert(set_manifest.name.clone(), set);
+        let mut action_set = ActionSet::new(
+            set_manifest.name.clone(),
+            localized(&set_manifest.name, &set_manifest.localized_name),
+            set_manifest.priority,
+        );
+        action_set.transparent = set_manifest.transparent;
+        action_set.enabled = set_query
+            .get(set)
+            .map(|set| set.enabled)
+            .unwrap_or(set_manifest.enabled);
+        cmds.entity(set).insert(action_set);
+
+        for action_manifest in set_manifest.actions.iter() {
+            #[cfg(not(feature = "xr"))]
+            if action_manifest.value == ManifestValue::Space {
+                warn!(
+                    "skipping {}/{}, space actions need the xr feature",
+                    set_manifest.name, action_manifest.name
+                );
+                continue;
+            }
+            let key = (set_manifest.name.clone(), action_manifest.name.clone());
+            let action = match old_actions.remove(&key) {
+                Some(action) => action,
+                None => cmds.spawn_empty().id(),
+            };
+            entities.actions.insert(key, action);
+            let old_modifications = entities.modifications.remove(&action).unwrap_or_default();
+            let modifications =
+                spawn_action(action_manifest, action, set, old_modifications, paths, cmds);
+            entities.modifications.insert(action, modifications);
+        }
+    }
+    // the modifications are children of the actions
+    for action in old_actions.into_values() {
+        entities.modifications.remove(&action);
+        cmds.entity(action).despawn();
+    }
+    for set in old_sets.into_values() {
+        cmds.entity(set).despawn();
+    }
+}
+
+/// Inserts the components of the action, returns the modification entities, reusing the
+/// old ones of the action in order.
+fn spawn_action(
+    manifest: &ActionManifestEntry,
+    action: Entity,
+    set: Entity,
+    old_modifications: Vec<Entity>,
+    paths: &mut SubactionPaths,
+    cmds: &mut Commands,
+) -> Vec<Entity> {
+    let sub_paths = manifest
+        .subaction_paths
+        .iter()
+        .map(|path| paths.get_or_create_path(path.clone(), cmds))
+        .collect::<Vec<_>>();
+    let mut modifications = BindingModifications::default();
+    let mut old_modifications = old_modifications.into_iter();
+    let mut spawned = Vec::new();
+    let mut spawn_modification = |manifest: &ManifestModification, cmds: &mut Commands| {
+        let mut entity = match old_modifications.next() {
+            Some(entity) => cmds.entity(entity),
+            None => cmds.spawn(ChildOf(action)),
+        };
+        entity.remove::<(PremultiplyDeltaSecsModification, UnboundedModification)>();
+        if manifest.premultiply_delta_secs {
+            entity.insert(PremultiplyDeltaSecsModification);
+        }
+        if manifest.unbounded {
+            entity.insert(UnboundedModification);
+        }
+        spawned.push(entity.id());
+        entity.id()
+    };
+    if let Some(all_paths) = manifest.modifications.all_paths.as_ref() {
+        modifications.all_paths_modification(spawn_modification(all_paths, cmds));
+    }
+    for (path, modification) in manifest.modifications.per_path.iter() {
+        let path = paths.get_or_create_path(path.clone(), cmds);
+        modifications.path_modification(path, spawn_modification(modification, cmds));
+    }
+    for unused in old_modifications {
+        cmds.entity(unused).despawn();
+    }
+
+    let mut entity = cmds.entity(action);
+    entity.insert((
+        Action::new(
+            manifest.name.clone(),
+            localized(&manifest.name, &manifest.localized_name),
+            set,
+        ),
+        RequestedSubactionPaths(sub_paths),
+        modifications,
+    ));
+    entity.remove::<(BoolActionValue, F32ActionValue, Vec2ActionValue)>();
+    #[cfg(feature = "xr")]
+    entity.remove::<crate::xr::SpaceActionValue>();
+    match manifest.value {
+        ManifestValue::Bool => {
+            entity.insert(BoolActionValue::new());
+        }
+        ManifestValue::F32 => {
+            entity.insert(F32ActionValue::new());
+        }
+        ManifestValue::Vec2 => {
+            entity.insert(Vec2ActionValue::new());
+        }
+        #[cfg(feature = "xr")]
+        ManifestValue::Space => {
+            entity.insert(crate::xr::SpaceActionValue::default());
+        }
+        // skipped by spawn_manifest
+        #[cfg(not(feature = "xr"))]
+        ManifestValue::Space => {}
+    }
+
+    entity.remove::<(KeyboardBindings, MouseBindings, GamepadBindings)>();
+    if !manifest.keyboard.is_empty() {
+        entity.insert(KeyboardBindings(
+            manifest
+                .keyboard
+                .iter()
+                .map(|b| {
+                    let mut binding = KeyboardBinding::new(b.key)
+                        .behavior(b.behavior)
+                        .multiplier(b.multiplier);
+                    binding.axis = b.axis;
+                    binding.axis_dir = b.axis_dir;
+                    binding
+                })
+                .collect(),
+        ));
+    }
+    if !manifest.mouse.buttons.is_empty()
+        || manifest.mouse.motion.is_some()
+        || !manifest.mouse.scroll.is_empty()
+    {
+        entity.insert(MouseBindings {
+            buttons: manifest
+                .mouse
+                .buttons
+                .iter()
+                .map(|b| {
+                    let mut binding = MouseButtonBinding::new(b.button).behavior(b.behavior);
+                    binding.axis = b.axis;
+                    binding.axis_dir = b.axis_dir;
+                    binding
+                })
+                .collect(),
+            movement: manifest.mouse.motion.map(|m| MouseMotionBinding {
+                motion_type: m.motion_type,
+                multiplier: m.multiplier,
+            }),
+            scroll: manifest
+                .mouse
+                .scroll
+                .iter()
+                .map(|s| MouseScrollBinding {
+                    source: s.source,
+                    axis: s.axis,
+                    axis_dir: s.axis_dir,
+                    behavior: s.behavior,
+                    multiplier: s.multiplier,
+                })
+                .collect(),
+        });
+    }
+    if !manifest.gamepad.is_empty() {
+        entity.insert(GamepadBindings {
+            bindings: manifest
+                .gamepad
+                .iter()
+                .map(|b| {
+                    let mut binding = GamepadBinding::new(b.source);
+                    binding.button_behavior = b.behavior;
+                    binding.axis = b.axis;
+                    binding.axis_dir = b.axis_dir;
+                    binding
+                })
+                .collect(),
+        });
+    }
+    #[cfg(feature = "xr")]
+    {
+        entity.remove::<crate::openxr::OxrBindings>();
+        if !manifest.openxr.is_empty() {
+            entity.insert(crate::openxr::OxrBindings {
+                bindings: manifest
+                    .openxr
+                    .iter()
+                    .map(|(profile, paths)| {
+                        (
+                            Cow::Owned(profile.clone()),
+                            paths.iter().cloned().map(Cow::Owned).collect(),
+                        )
+                    })
+                    .collect(),
+            });
+        }
+    }
+    spawned
+}
+
+fn localized(name: &str, localized_name: &Option<String>) -> Cow<'static, str> {
+    Cow::Owned(localized_name.clone().unwrap_or_else(|| name.to_string()))
+}
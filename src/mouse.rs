@@ -1,15 +1,29 @@
-use std::hash::{DefaultHasher, Hash, Hasher};
+use std::{
+    fmt::Display,
+    hash::{DefaultHasher, Hash, Hasher},
+};
 
-use bevy::{input::mouse::MouseMotion, prelude::*};
+use bevy::{
+    ecs::system::SystemChangeTick,
+    input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
 
 use crate::{
-    impl_helpers::{BindingValue, ProviderParam}, priorities::PriorityAppExt as _, subaction_paths::{SubactionPathCreated, SubactionPathStr}, ButtonInputBeheavior, InputAxis, InputAxisDirection, SchminputSystems
+    ButtonInputBeheavior, InputAxis, InputAxisDirection, SchminputSystems,
+    impl_helpers::{BindingValue, ProviderParam},
+    interactions::{BindingInteractions, interaction_key},
+    players::allows_mouse,
+    priorities::PriorityAppExt as _,
+    subaction_paths::{SubactionPathCreated, SubactionPathStr},
 };
 
 pub struct MousePlugin;
 
 impl Plugin for MousePlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<MouseScrollSettings>();
+        app.register_type::<MouseScrollSettings>();
         app.add_systems(
             PreUpdate,
             sync_actions.in_set(SchminputSystems::SyncInputActions),
@@ -18,7 +32,7 @@ impl Plugin for MousePlugin {
             PreUpdate,
             handle_new_subaction_paths.in_set(SchminputSystems::HandleNewSubactionPaths),
         );
-        app.add_binding_id_system(
+        app.add_tracked_binding_id_system::<MouseBindings, _>(
             "schminput:mouse",
             |entity: In<Entity>, query: Query<&MouseBindings>| {
                 let Ok(bindings) = query.get(entity.0) else {
@@ -30,21 +44,58 @@ impl Plugin for MousePlugin {
                     .cloned()
                     .map(AnyMouseBinding::Button)
                     .chain(bindings.movement.map(AnyMouseBinding::Motion))
+                    .chain(bindings.scroll.iter().copied().map(AnyMouseBinding::Scroll))
                     .map(|v| get_binding_id(&v))
                     .collect()
             },
         );
+        app.add_binding_description_system(
+            "schminput:mouse",
+            |entity: In<Entity>, query: Query<&MouseBindings>| {
+                let Ok(bindings) = query.get(entity.0) else {
+                    return Vec::new();
+                };
+                bindings
+                    .buttons
+                    .iter()
+                    .cloned()
+                    .map(AnyMouseBinding::Button)
+                    .chain(bindings.movement.map(AnyMouseBinding::Motion))
+                    .chain(bindings.scroll.iter().copied().map(AnyMouseBinding::Scroll))
+                    .map(|v| {
+                        let description = match &v {
+                            AnyMouseBinding::Button(b) => format!("mouse:{:?}", b.button),
+                            AnyMouseBinding::Motion(m) => format!("mouse:{:?}", m.motion_type),
+                            AnyMouseBinding::Scroll(s) => format!("mouse:scroll/{:?}", s.source),
+                        };
+                        (get_binding_id(&v), description)
+                    })
+                    .collect()
+            },
+        );
     }
 }
 
 fn get_binding_id(binding: &AnyMouseBinding) -> u64 {
-    let mut hasher = DefaultHasher::new();
     match binding {
-        AnyMouseBinding::Button(MouseButtonBinding { button, .. }) => button.hash(&mut hasher),
+        AnyMouseBinding::Button(MouseButtonBinding { button, .. }) => button_binding_id(*button),
         AnyMouseBinding::Motion(MouseMotionBinding { motion_type, .. }) => {
-            motion_type.hash(&mut hasher)
+            let mut hasher = DefaultHasher::new();
+            motion_type.hash(&mut hasher);
+            hasher.finish()
+        }
+        AnyMouseBinding::Scroll(MouseScrollBinding { source, .. }) => {
+            let mut hasher = DefaultHasher::new();
+            "scroll".hash(&mut hasher);
+            source.hash(&mut hasher);
+            hasher.finish()
         }
     }
+}
+
+pub(crate) fn button_binding_id(button: MouseButton) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    button.hash(&mut hasher);
     hasher.finish()
 }
 
@@ -55,7 +106,7 @@ fn handle_new_subaction_paths(
 ) {
     for (entity, path) in event
         .read()
-        .filter_map(|e| Some((e.0 .0, query.get(e.0 .0).ok()?)))
+        .filter_map(|e| Some((e.0.0, query.get(e.0.0).ok()?)))
     {
         if let Some(sub_path) = path.0.strip_prefix("/mouse") {
             if sub_path.is_empty() || sub_path == "/*" {
@@ -70,10 +121,10 @@ fn handle_new_subaction_paths(
                 cmds.entity(entity).insert(MouseSubactionPath::Button);
                 continue;
             }
-            // if sub_path == "/scroll" {
-            //     cmds.entity(entity).insert(MouseSubactionPath::Scroll);
-            //     continue;
-            // }
+            if sub_path == "/scroll" {
+                cmds.entity(entity).insert(MouseSubactionPath::Scroll);
+                continue;
+            }
         }
     }
 }
@@ -81,17 +132,39 @@ fn handle_new_subaction_paths(
 enum AnyMouseBinding {
     Button(MouseButtonBinding),
     Motion(MouseMotionBinding),
+    Scroll(MouseScrollBinding),
 }
 
 #[allow(clippy::type_complexity)]
 pub fn sync_actions(
-    mut query: ProviderParam<&MouseBindings, &MouseSubactionPath>,
+    mut query: ProviderParam<&MouseBindings, &mut BindingInteractions, &MouseSubactionPath>,
     time: Res<Time>,
     input: Res<ButtonInput<MouseButton>>,
     mut delta_motion: MessageReader<MouseMotion>,
+    mut wheel: MessageReader<MouseWheel>,
+    scroll_settings: Res<MouseScrollSettings>,
+    ticks: SystemChangeTick,
 ) {
-    query.run(
+    let scroll = wheel
+        .read()
+        .map(|e| {
+            let delta = Vec2::new(e.x, e.y);
+            match e.unit {
+                MouseScrollUnit::Line => delta,
+                MouseScrollUnit::Pixel => delta / scroll_settings.pixels_per_line,
+            }
+        })
+        .sum::<Vec2>();
+    query.run_with_deltas(
         "schminput:mouse",
+        |binding| match binding {
+            AnyMouseBinding::Button(button) => button.behavior.is_momentary(),
+            // discrete directions act like buttons
+            AnyMouseBinding::Scroll(scroll) if scroll.source.is_discrete() => {
+                scroll.behavior.is_momentary()
+            }
+            AnyMouseBinding::Motion(_) | AnyMouseBinding::Scroll(_) => true,
+        },
         get_binding_id,
         |binding, path| {
             matches!(
@@ -103,40 +176,47 @@ pub fn sync_actions(
                     }),
                     MouseSubactionPath::DeltaMotion
                 ) | (AnyMouseBinding::Button(_), MouseSubactionPath::Button)
+                    | (AnyMouseBinding::Scroll(_), MouseSubactionPath::Scroll)
                     | (_, MouseSubactionPath::All)
             )
         },
-        |bindings| {
+        |_| false,
+        |bindings, visit| {
             bindings
                 .buttons
                 .iter()
-                .cloned()
+                .copied()
                 .map(AnyMouseBinding::Button)
                 .chain(bindings.movement.map(AnyMouseBinding::Motion))
-                .collect()
+                .chain(bindings.scroll.iter().copied().map(AnyMouseBinding::Scroll))
+                .for_each(|binding| visit(&binding))
         },
-        |binding, _, _, data| {
-            let time_mutiplier = match data.modifications.premul_delta_time {
-                true => time.delta_secs(),
-                false => 1.0,
-            };
+        |binding, interactions, _, data, out| {
+            if !allows_mouse(data.devices) {
+                return;
+            }
+            let time_mutiplier = data.modifications.delta_multiplier();
             match binding {
                 AnyMouseBinding::Button(button) => {
-                    let bool = data
-                        .is_bool
-                        .then(|| button.behavior.apply(&input, button.button));
+                    let active = button.behavior.apply_with_state(
+                        &input,
+                        button.button,
+                        interactions,
+                        interaction_key(get_binding_id(binding), button.behavior, None),
+                        &time,
+                        ticks.this_run(),
+                    );
+                    let bool = data.is_bool.then_some(active);
                     let f32 = data.is_f32.then(|| {
-                        button.behavior.apply(&input, button.button) as u8 as f32
-                            * button.axis_dir.as_multipier()
-                            * time_mutiplier
+                        active as u8 as f32 * button.axis_dir.as_multipier() * time_mutiplier
                     });
                     let vec2 = data.is_vec2.then(|| {
-                        let val = button.behavior.apply(&input, button.button) as u8 as f32;
+                        let val = active as u8 as f32;
                         button
                             .axis
                             .new_vec(val * button.axis_dir.as_multipier() * time_mutiplier)
                     });
-                    vec![BindingValue { vec2, bool, f32 }]
+                    out.push(BindingValue { vec2, bool, f32 });
                 }
                 AnyMouseBinding::Motion(MouseMotionBinding {
                     motion_type,
@@ -153,9 +233,60 @@ pub fn sync_actions(
                         let f32 = data.is_f32.then_some(delta.x);
                         let vec2 = data.is_vec2.then_some(delta);
 
-                        vec![BindingValue { vec2, bool, f32 }]
+                        out.push(BindingValue { vec2, bool, f32 });
                     }
                 },
+                AnyMouseBinding::Scroll(scroll_binding) => {
+                    let scaled = scroll * scroll_binding.multiplier * time_mutiplier;
+                    let dir = scroll_binding.axis_dir.as_multipier();
+                    let value = match scroll_binding.source {
+                        MouseScrollSource::Both => BindingValue {
+                            bool: data.is_bool.then_some(scaled != Vec2::ZERO),
+                            f32: data.is_f32.then_some(scaled.y * dir),
+                            vec2: data.is_vec2.then_some(scaled * dir),
+                        },
+                        MouseScrollSource::Vertical | MouseScrollSource::Horizontal => {
+                            let val = match scroll_binding.source {
+                                MouseScrollSource::Vertical => scaled.y,
+                                _ => scaled.x,
+                            };
+                            BindingValue {
+                                bool: data.is_bool.then_some(val != 0.0),
+                                f32: data.is_f32.then_some(val * dir),
+                                vec2: data.is_vec2.then(|| scroll_binding.axis.new_vec(val * dir)),
+                            }
+                        }
+                        source => {
+                            let scrolled = match source {
+                                MouseScrollSource::Up => scroll.y > 0.0,
+                                MouseScrollSource::Down => scroll.y < 0.0,
+                                MouseScrollSource::Left => scroll.x < 0.0,
+                                _ => scroll.x > 0.0,
+                            };
+                            let active = interactions.evaluate(
+                                interaction_key(
+                                    get_binding_id(binding),
+                                    scroll_binding.behavior,
+                                    None,
+                                ),
+                                scroll_binding.behavior,
+                                scrolled,
+                                &time,
+                                ticks.this_run(),
+                            );
+                            let val = active as u8 as f32
+                                * dir
+                                * scroll_binding.multiplier
+                                * time_mutiplier;
+                            BindingValue {
+                                bool: data.is_bool.then_some(active),
+                                f32: data.is_f32.then_some(val),
+                                vec2: data.is_vec2.then(|| scroll_binding.axis.new_vec(val)),
+                            }
+                        }
+                    };
+                    out.push(value);
+                }
             }
         },
     );
@@ -165,14 +296,16 @@ pub fn sync_actions(
 pub enum MouseSubactionPath {
     DeltaMotion,
     Button,
-    // Scroll,
+    Scroll,
     All,
 }
 
 #[derive(Clone, Default, Debug, Reflect, Component)]
+#[require(BindingInteractions)]
 pub struct MouseBindings {
     pub buttons: Vec<MouseButtonBinding>,
     pub movement: Option<MouseMotionBinding>,
+    pub scroll: Vec<MouseScrollBinding>,
 }
 
 impl MouseBindings {
@@ -180,6 +313,10 @@ impl MouseBindings {
         self.buttons.push(binding);
         self
     }
+    pub fn scroll(mut self, binding: MouseScrollBinding) -> Self {
+        self.scroll.push(binding);
+        self
+    }
     pub fn delta_motion(mut self) -> Self {
         let mut mmb = match self.movement {
             Some(v) => v,
@@ -256,6 +393,11 @@ impl MouseButtonBinding {
         self.behavior = ButtonInputBeheavior::JustReleased;
         self
     }
+
+    pub fn behavior(mut self, behavior: ButtonInputBeheavior) -> Self {
+        self.behavior = behavior;
+        self
+    }
 }
 
 #[derive(Clone, Copy, Debug, Reflect)]
@@ -278,7 +420,125 @@ impl MouseMotionBinding {
 }
 
 #[derive(Clone, Copy, Default, Debug, Reflect, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "manifest", derive(serde::Deserialize))]
 pub enum MouseMotionType {
     #[default]
     DeltaMotion,
 }
+
+/// Converts scroll in pixels to lines, all scroll values are in lines.
+#[derive(Clone, Copy, Debug, Resource, Reflect)]
+pub struct MouseScrollSettings {
+    pub pixels_per_line: f32,
+}
+
+impl Default for MouseScrollSettings {
+    fn default() -> Self {
+        Self {
+            pixels_per_line: 16.0,
+        }
+    }
+}
+
+/// Scroll in lines, +Y is scrolling up and +X scrolling right.
+#[derive(Clone, Copy, Debug, Reflect, PartialEq)]
+pub struct MouseScrollBinding {
+    pub source: MouseScrollSource,
+    /// Used by all sources except [`MouseScrollSource::Both`], which uses both axes.
+    pub axis: InputAxis,
+    pub axis_dir: InputAxisDirection,
+    /// Only used by the discrete directions, a frame with scroll in the direction counts as
+    /// a press.
+    pub behavior: ButtonInputBeheavior,
+    /// Scales the scrolled lines, or the value of 1 of an active discrete direction.
+    pub multiplier: f32,
+}
+
+impl MouseScrollBinding {
+    pub fn new(source: MouseScrollSource) -> MouseScrollBinding {
+        MouseScrollBinding {
+            source,
+            axis: default(),
+            axis_dir: default(),
+            behavior: default(),
+            multiplier: 1.0,
+        }
+    }
+    pub fn x_axis(mut self) -> Self {
+        self.axis = InputAxis::X;
+        self
+    }
+
+    pub fn y_axis(mut self) -> Self {
+        self.axis = InputAxis::Y;
+        self
+    }
+
+    pub fn positive_axis_dir(mut self) -> Self {
+        self.axis_dir = InputAxisDirection::Positive;
+        self
+    }
+
+    pub fn negative_axis_dir(mut self) -> Self {
+        self.axis_dir = InputAxisDirection::Negative;
+        self
+    }
+
+    pub fn just_pressed(mut self) -> Self {
+        self.behavior = ButtonInputBeheavior::JustPressed;
+        self
+    }
+
+    pub fn just_released(mut self) -> Self {
+        self.behavior = ButtonInputBeheavior::JustReleased;
+        self
+    }
+
+    pub fn behavior(mut self, behavior: ButtonInputBeheavior) -> Self {
+        self.behavior = behavior;
+        self
+    }
+
+    pub fn multiplier(mut self, multiplier: f32) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug, Reflect, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "manifest", derive(serde::Deserialize))]
+pub enum MouseScrollSource {
+    /// Both axes as a vec2, f32 actions get the vertical scroll.
+    #[default]
+    Both,
+    Vertical,
+    Horizontal,
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl MouseScrollSource {
+    /// The source is a direction that acts like a button.
+    pub fn is_discrete(&self) -> bool {
+        !matches!(
+            self,
+            MouseScrollSource::Both | MouseScrollSource::Vertical | MouseScrollSource::Horizontal
+        )
+    }
+}
+
+impl Display for MouseScrollSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            MouseScrollSource::Both => "Scroll",
+            MouseScrollSource::Vertical => "Vertical Scroll",
+            MouseScrollSource::Horizontal => "Horizontal Scroll",
+            MouseScrollSource::Up => "Scroll Up",
+            MouseScrollSource::Down => "Scroll Down",
+            MouseScrollSource::Left => "Scroll Left",
+            MouseScrollSource::Right => "Scroll Right",
+        })
+    }
+}
@@ -16,8 +16,13 @@ use bevy_mod_xr::session::{XrPreSessionEnd, XrSessionCreated};
 use crate::ActionsInSet;
 #[cfg(not(target_family = "wasm"))]
 use crate::{
+    aggregation::{aggregate_value, AggregationState, ValueAggregation},
     binding_modification::{BindingModifications, PremultiplyDeltaSecsModification},
-    subaction_paths::{RequestedSubactionPaths, SubactionPathStr},
+    contexts::ContextLayer,
+    fixed::FixedAccumulation,
+    impl_helpers::{label_id, BindingValue, DisabledProviders},
+    processors::ProcessorChain,
+    subaction_paths::{RequestedSubactionPaths, SubactionPath, SubactionPathStr},
     xr::SpaceActionValue,
     Action, ActionSet, BoolActionValue, F32ActionValue, SchminputSystems, Vec2ActionValue,
 };
@@ -146,13 +151,21 @@ fn sync_non_blocking_action_sets(world: &mut World) {
 
 #[cfg(not(target_family = "wasm"))]
 fn sync_action_sets(
-    query: Query<(&OxrActionSet, &ActionSet)>,
+    query: Query<(&OxrActionSet, &ActionSet, Option<&ContextLayer>)>,
     mut sync_set: MessageWriter<OxrSyncActionSet>,
 ) {
+    let active = |set: &ActionSet| set.enabled && !set.transparent;
+    // the runtime can't block single inputs for lower context layers,
+    // so sets shadowed by a context aren't synced at all
+    let top_layer = query
+        .iter()
+        .filter(|(_, v, _)| active(v))
+        .filter_map(|(_, _, layer)| layer.copied())
+        .max();
     let sets = query
         .iter()
-        .filter(|(_, v)| v.enabled && !v.transparent)
-        .map(|(set, _)| OxrSyncActionSet(set.0.clone()));
+        .filter(|(_, v, layer)| active(v) && layer.copied() >= top_layer)
+        .map(|(set, _, _)| OxrSyncActionSet(set.0.clone()));
     sync_set.write_batch(sets);
 }
 
@@ -300,12 +313,21 @@ fn sync_input_action(
         Option<&mut SpaceActionValue>,
         &RequestedSubactionPaths,
         &BindingModifications,
+        &ValueAggregation,
+        &mut AggregationState,
+        Has<FixedAccumulation>,
     )>,
     path_query: Query<&OxrSubactionPath>,
     simple_path_query: Query<Has<IsOxrSubactionPath>>,
     modification_query: Query<Has<PremultiplyDeltaSecsModification>>,
+    processors_query: Query<&ProcessorChain>,
     time: Res<Time>,
+    fixed_time: Res<Time<Fixed>>,
+    disabled_providers: Res<DisabledProviders>,
 ) {
+    if disabled_providers.is_disabled("schminput:openxr") {
+        return;
+    }
     let Ok((
         mut action,
         mut bool_val,
@@ -314,6 +336,9 @@ fn sync_input_action(
         mut space_val,
         requested_subaction_paths,
         modifications,
+        aggregation,
+        mut aggregation_state,
+        is_fixed,
     )) = query.get_mut(action.0)
     else {
         return;
@@ -336,83 +361,121 @@ fn sync_input_action(
     {
         pre_mul_delta_time |= modification_query.get(modification.0).unwrap_or(false);
     }
-    let delta_multiplier = match pre_mul_delta_time {
-        true => time.delta_secs(),
-        false => 1.0,
+    let delta_multiplier = match (pre_mul_delta_time, is_fixed) {
+        (true, true) => fixed_time.timestep().as_secs_f32(),
+        (true, false) => time.delta_secs(),
+        (false, _) => 1.0,
     };
+    // the runtime only gives us the combined value when not using a subaction path,
+    // so only the all paths processors apply to it, they see the value before the delta
+    let process = |sub_path: Option<SubactionPath>, mut value: BindingValue| {
+        let per_path = modifications
+            .per_path
+            .iter()
+            .filter(|(path, _)| Some(*path) == sub_path)
+            .map(|(_, modification)| modification.0);
+        for processors in modifications
+            .all_paths
+            .iter()
+            .map(|modification| modification.0)
+            .chain(per_path)
+            .filter_map(|e| processors_query.get(e).ok())
+        {
+            value = processors.process(value);
+        }
+        value.f32 = value.f32.map(|v| v * delta_multiplier);
+        value.vec2 = value.vec2.map(|v| v * delta_multiplier);
+        value
+    };
+    // the runtime already combined the bindings of the action using the longest vector and
+    // largest float, as the OpenXR spec requires, so this is a single source like the others
+    let aggregation = *aggregation;
+    let label_id = label_id("schminput:openxr");
     match action.as_mut() {
         OxrAction::Bool(action) => {
-            match action.state(&session, openxr::Path::NULL) {
-                Ok(v) => {
-                    if let Some(val) = bool_val.as_mut() {
-                        val.any |= v.current_state;
-                    } else {
-                        warn!("Bool action but no bool Value!");
-                    }
-                }
-                Err(e) => warn!("unable to get data from action: {}", e.to_string()),
-            };
-            for (sub_action_path, path) in paths.into_iter() {
+            if bool_val.is_none() {
+                warn!("Bool action but no bool Value!");
+                return;
+            }
+            let paths = [(None, openxr::Path::NULL)]
+                .into_iter()
+                .chain(paths.into_iter().map(|(p, path)| (Some(p), path)));
+            for (sub_action_path, path) in paths {
                 match action.state(&session, path) {
-                    Ok(v) => {
-                        if let Some(val) = bool_val.as_mut() {
-                            *val.entry_with_path(sub_action_path).or_default() |= v.current_state;
-                        } else {
-                            warn!("Bool action but no bool Value!");
-                        }
-                    }
+                    Ok(v) => aggregate_value(
+                        label_id,
+                        sub_action_path,
+                        BindingValue {
+                            bool: Some(v.current_state),
+                            ..default()
+                        },
+                        aggregation,
+                        &mut aggregation_state,
+                        None,
+                        None,
+                        bool_val.as_mut(),
+                    ),
                     Err(e) => warn!("unable to get data from action: {}", e.to_string()),
                 };
             }
         }
         OxrAction::F32(action) => {
-            match action.state(&session, openxr::Path::NULL) {
-                Ok(v) => {
-                    if let Some(val) = f32_val.as_mut() {
-                        val.any += v.current_state * delta_multiplier;
-                    } else {
-                        warn!("F32 action but no f32 Value!");
-                    }
-                }
-                Err(e) => warn!("unable to get data from action: {}", e.to_string()),
-            };
-            for (sub_action_path, path) in paths.into_iter() {
+            if f32_val.is_none() {
+                warn!("F32 action but no f32 Value!");
+                return;
+            }
+            let paths = [(None, openxr::Path::NULL)]
+                .into_iter()
+                .chain(paths.into_iter().map(|(p, path)| (Some(p), path)));
+            for (sub_action_path, path) in paths {
                 match action.state(&session, path) {
-                    Ok(v) => {
-                        if let Some(val) = f32_val.as_mut() {
-                            *val.entry_with_path(sub_action_path).or_default() +=
-                                v.current_state * delta_multiplier;
-                        } else {
-                            warn!("F32 action but no f32 Value!");
-                        }
-                    }
+                    Ok(v) => aggregate_value(
+                        label_id,
+                        sub_action_path,
+                        process(
+                            sub_action_path,
+                            BindingValue {
+                                f32: Some(v.current_state),
+                                ..default()
+                            },
+                        ),
+                        aggregation,
+                        &mut aggregation_state,
+                        None,
+                        f32_val.as_mut(),
+                        None,
+                    ),
                     Err(e) => warn!("unable to get data from action: {}", e.to_string()),
                 };
             }
         }
         OxrAction::Vec2(action) => {
-            match action.state(&session, openxr::Path::NULL) {
-                Ok(v) => {
-                    if let Some(val) = vec2_val.as_mut() {
-                        // This might be broken!
-                        val.any += v.current_state.to_vec2() * delta_multiplier;
-                    } else {
-                        warn!("Vec2 action but no Vec2 Value!");
-                    }
-                }
-                Err(e) => warn!("unable to get data from action: {}", e.to_string()),
-            };
-            for (sub_action_path, path) in paths.into_iter() {
+            if vec2_val.is_none() {
+                warn!("Vec2 action but no Vec2 Value!");
+                return;
+            }
+            let paths = [(None, openxr::Path::NULL)]
+                .into_iter()
+                .chain(paths.into_iter().map(|(p, path)| (Some(p), path)));
+            for (sub_action_path, path) in paths {
                 match action.state(&session, path) {
-                    Ok(v) => {
-                        if let Some(val) = vec2_val.as_mut() {
-                            // This might be broken!
-                            *val.entry_with_path(sub_action_path).or_default() +=
-                                v.current_state.to_vec2() * delta_multiplier;
-                        } else {
-                            warn!("Vec2 action but no Vec2 Value!");
-                        }
-                    }
+                    Ok(v) => aggregate_value(
+                        label_id,
+                        sub_action_path,
+                        process(
+                            sub_action_path,
+                            BindingValue {
+                                // This might be broken!
+                                vec2: Some(v.current_state.to_vec2()),
+                                ..default()
+                            },
+                        ),
+                        aggregation,
+                        &mut aggregation_state,
+                        vec2_val.as_mut(),
+                        None,
+                        None,
+                    ),
                     Err(e) => warn!("unable to get data from action: {}", e.to_string()),
                 };
             }
@@ -0,0 +1,139 @@
+use bevy::{ecs::entity::EntityHashSet, prelude::*};
+
+use crate::ActionSet;
+
+/// Gives new gamepads to players that have no connected gamepad.
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Player>();
+        app.register_type::<PlayerDevices>();
+        app.register_type::<OwnedByPlayer>();
+        app.register_type::<PlayerActionSets>();
+        app.add_systems(
+            PreUpdate,
+            assign_gamepads.before(crate::SchminputSystems::SyncInputActions),
+        );
+    }
+}
+
+/// A local player, despawning it removes the player and all of its action sets.
+#[derive(Clone, Copy, Debug, Component, Reflect, PartialEq, Eq, Hash)]
+#[require(PlayerDevices, PlayerActionSets)]
+pub struct Player {
+    /// Players with lower slots get new gamepads first.
+    pub slot: u32,
+}
+
+impl Player {
+    pub fn new(slot: u32) -> Self {
+        Self { slot }
+    }
+}
+
+/// The devices a [`Player`] owns, the action sets of the player only see input from these.
+#[derive(Clone, Debug, Default, Component, Reflect)]
+pub struct PlayerDevices {
+    pub keyboard: bool,
+    pub mouse: bool,
+    pub gamepads: EntityHashSet,
+    /// Newly connected gamepads are given to the player while it has no connected gamepad.
+    pub auto_assign_gamepad: bool,
+}
+
+impl PlayerDevices {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_keyboard(mut self) -> Self {
+        self.keyboard = true;
+        self
+    }
+    pub fn with_mouse(mut self) -> Self {
+        self.mouse = true;
+        self
+    }
+    pub fn with_gamepad(mut self, gamepad: Entity) -> Self {
+        self.gamepads.insert(gamepad);
+        self
+    }
+    pub fn auto_assign_gamepad(mut self) -> Self {
+        self.auto_assign_gamepad = true;
+        self
+    }
+}
+
+/// Makes an [`ActionSet`] only see input from the devices of the [`Player`],
+/// it only blocks and consumes inputs for the other sets of the same player.
+#[derive(Clone, Copy, Debug, Component, Reflect, PartialEq, Eq)]
+#[relationship(relationship_target = PlayerActionSets)]
+pub struct OwnedByPlayer(pub Entity);
+
+#[derive(Clone, Debug, Default, Component, Reflect, Deref)]
+#[relationship_target(relationship = OwnedByPlayer, linked_spawn)]
+pub struct PlayerActionSets(EntityHashSet);
+
+pub(crate) fn allows_keyboard(devices: Option<&PlayerDevices>) -> bool {
+    devices.is_none_or(|d| d.keyboard)
+}
+pub(crate) fn allows_mouse(devices: Option<&PlayerDevices>) -> bool {
+    devices.is_none_or(|d| d.mouse)
+}
+pub(crate) fn allows_gamepad(devices: Option<&PlayerDevices>, gamepad: Entity) -> bool {
+    devices.is_none_or(|d| d.gamepads.contains(&gamepad))
+}
+
+pub trait PlayerCommandsExt {
+    /// Spawns an enabled copy of the action set and its actions that is owned by the player,
+    /// keep the original set disabled so it doesn't see the input of every player.
+    ///
+    /// The copy is named `"{set_name}_player_{slot}"` so recordings, snapshots and the
+    /// [`ActionIndex`](crate::lookup::ActionIndex) can tell the copies of different players apart.
+    fn copy_action_set_for_player(&mut self, set: Entity, player: Entity) -> Entity;
+}
+
+impl PlayerCommandsExt for Commands<'_, '_> {
+    fn copy_action_set_for_player(&mut self, set: Entity, player: Entity) -> Entity {
+        self.entity(set)
+            .clone_and_spawn_with_opt_out(|builder| {
+                builder.linked_cloning(true);
+            })
+            .insert(OwnedByPlayer(player))
+            .queue(move |mut entity: EntityWorldMut| {
+                let slot = entity.world_scope(|world| world.get::<Player>(player).map(|p| p.slot));
+                if let Some(mut set) = entity.get_mut::<ActionSet>() {
+                    set.enabled = true;
+                    if let Some(slot) = slot {
+                        set.name = format!("{}_player_{slot}", set.name).into();
+                    }
+                }
+            })
+            .id()
+    }
+}
+
+fn assign_gamepads(
+    new_gamepads: Query<Entity, Added<Gamepad>>,
+    connected: Query<(), With<Gamepad>>,
+    mut players: Query<(&Player, &mut PlayerDevices)>,
+) {
+    for gamepad in &new_gamepads {
+        // reconnected gamepads go back to their player
+        if players.iter().any(|(_, d)| d.gamepads.contains(&gamepad)) {
+            continue;
+        }
+        let Some((_, mut devices)) = players
+            .iter_mut()
+            .filter(|(_, d)| {
+                d.auto_assign_gamepad && !d.gamepads.iter().any(|g| connected.contains(*g))
+            })
+            .min_by_key(|(player, _)| player.slot)
+        else {
+            continue;
+        };
+        // forget gamepads that were disconnected, a new one replaces them
+        devices.gamepads.retain(|g| connected.contains(*g));
+        devices.gamepads.insert(gamepad);
+    }
+}
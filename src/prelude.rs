@@ -1,17 +1,33 @@
+pub use crate::chords::{ChordBinding, ChordBindings, ChordInput};
+pub use crate::contexts::{ContextCommandsExt, ContextPolicy, ContextStack, InputContext};
 pub use crate::gamepad::{
     GamepadBinding, GamepadBindingSource, GamepadBindings, GamepadHapticOutput,
     GamepadHapticOutputBindings, GamepadPathSelector,
 };
+pub use crate::joining::{JoinFlow, LeaveAction};
 pub use crate::keyboard::{KeyboardBinding, KeyboardBindings};
-pub use crate::mouse::{MouseBindings, MouseButtonBinding, MouseMotionBinding, MouseMotionType};
+pub use crate::lookup::{ActionIndex, ActionReader};
+pub use crate::mouse::{
+    MouseBindings, MouseButtonBinding, MouseMotionBinding, MouseMotionType, MouseScrollBinding,
+    MouseScrollSource,
+};
 // these all work with only "xr" by chance, nice
 #[cfg(feature = "xr")]
 pub use crate::openxr::{
     OxrBindings, META_TOUCH_PLUS_PROFILE, META_TOUCH_PRO_PROFILE, OCULUS_TOUCH_PROFILE,
 };
+pub use crate::players::{OwnedByPlayer, Player, PlayerCommandsExt, PlayerDevices};
+pub use crate::recording::{InputRecorder, InputRecording};
+pub use crate::sequences::{SequenceBinding, SequenceBindings};
 pub use crate::subaction_paths::{RequestedSubactionPaths, SubactionPaths};
+pub use crate::typed::{
+    ActionSetDeclaration, BoolAction, F32Action, TypedActions, TypedActionsAppExt, Vec2Action,
+};
+#[cfg(feature = "xr")]
+pub use crate::typed::SpaceAction;
 #[cfg(feature = "xr")]
 pub use crate::xr::{AttachSpaceToEntity, SpaceActionValue};
+pub use crate::action_state::{BoolActionState, F32ActionState, Vec2ActionState};
 pub use crate::DefaultSchminputPlugins;
 pub use crate::{Action, ActionSet};
 pub use crate::{BoolActionValue, F32ActionValue, Vec2ActionValue};
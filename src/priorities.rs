@@ -1,107 +1,539 @@
-use std::hash::{DefaultHasher, Hash, Hasher};
+use std::{
+    any::TypeId,
+    hash::{DefaultHasher, Hash, Hasher},
+    mem,
+};
 
 use bevy::{
-    ecs::{entity::EntityHashMap, system::SystemId}, platform::collections::{HashMap, HashSet}, prelude::*
+    ecs::{
+        entity::{EntityHashMap, EntityHashSet},
+        system::SystemId,
+    },
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
 };
 
-use crate::{ActionSet, ActionsInSet, SchminputSystems};
+use crate::{
+    Action, ActionSet, ActionsInSet, SchminputSystems, contexts::ContextLayer,
+    debug::BlockingAction, players::OwnedByPlayer,
+};
 
 pub struct PrioritiesPlugin;
 impl Plugin for PrioritiesPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<BindingIdSystems>();
+        app.init_resource::<ConsumedInputs>();
+        app.init_resource::<ActionOrder>();
+        app.init_resource::<RecomputeBlockedInputs>();
+        app.register_type::<BlockingMode>();
+        app.add_systems(
+            PreUpdate,
+            (detect_changes, system)
+                .chain()
+                .in_set(SchminputSystems::CalculateBindingCollisions),
+        );
         app.add_systems(
             PreUpdate,
-            system.in_set(SchminputSystems::CalculateBindingCollisions),
+            clear_consumed_inputs.in_set(SchminputSystems::ClearValues),
         );
+        #[cfg(feature = "xr")]
+        app.track_binding_component::<crate::openxr::OxrBindings>();
+    }
+}
+
+fn clear_consumed_inputs(mut consumed: ResMut<ConsumedInputs>) {
+    consumed.all.clear();
+    consumed.below.clear();
+    consumed.consumptions.clear();
+}
+
+/// Set when something that the blocked inputs depend on changed.
+#[derive(Resource, Debug)]
+pub(crate) struct RecomputeBlockedInputs(bool);
+
+impl Default for RecomputeBlockedInputs {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+fn detect_changes(
+    sets: Query<
+        (),
+        (
+            With<ActionSet>,
+            Or<(
+                Changed<ActionSet>,
+                Changed<ActionsInSet>,
+                Changed<BlockingMode>,
+                Changed<ContextLayer>,
+            )>,
+        ),
+    >,
+    actions: Query<(), (With<Action>, Or<(Changed<Action>, Changed<BlockingMode>)>)>,
+    mut removed_sets: RemovedComponents<ActionSet>,
+    mut removed_actions_in_set: RemovedComponents<ActionsInSet>,
+    mut removed_actions: RemovedComponents<Action>,
+    mut removed_modes: RemovedComponents<BlockingMode>,
+    mut removed_layers: RemovedComponents<ContextLayer>,
+    mut recompute: ResMut<RecomputeBlockedInputs>,
+) {
+    // read all of them so old removals don't show up again
+    let removed = removed_sets.read().count()
+        + removed_actions_in_set.read().count()
+        + removed_actions.read().count()
+        + removed_modes.read().count()
+        + removed_layers.read().count();
+    if removed > 0 || !sets.is_empty() || !actions.is_empty() {
+        recompute.0 = true;
     }
 }
 
-fn system(world: &mut World) {
+fn detect_binding_changes<C: Component>(
+    query: Query<(), Changed<C>>,
+    mut removed: RemovedComponents<C>,
+    mut recompute: ResMut<RecomputeBlockedInputs>,
+) {
+    if removed.read().count() > 0 || !query.is_empty() {
+        recompute.0 = true;
+    }
+}
+
+/// Updates the [`BlockedInputs`] at the end of the system set when anything they depend on
+/// changed, instead of every frame.
+pub(crate) fn system(
+    mut recompute: ResMut<RecomputeBlockedInputs>,
+    binding_id_systems: Res<BindingIdSystems>,
+    mut cmds: Commands,
+) {
+    // untracked binding id systems could return different ids in any frame
+    if mem::take(&mut recompute.0) || binding_id_systems.has_untracked {
+        cmds.queue(update_blocked_inputs);
+    }
+}
+
+fn update_blocked_inputs(world: &mut World) {
     let mut bindings = EntityHashMap::<HashMap<u64, Vec<u64>>>::default();
     let binding_id_systems = world.remove_resource::<BindingIdSystems>().unwrap();
     let query = world
-        .query::<(Entity, &ActionSet, &ActionsInSet)>()
+        .query::<(Entity, &ActionSet, &ActionsInSet, Option<&BlockingMode>)>()
         .iter(world)
-        .filter(|(_, set, _)| set.enabled && !set.transparent)
-        .map(|(e, _, a)| (e, a.0.iter().cloned().collect::<Vec<_>>()))
+        .filter(|(_, set, _, _)| set.enabled && !set.transparent)
+        .map(|(e, _, a, mode)| (e, a.0.iter().cloned().collect::<Vec<_>>(), mode.copied()))
         .collect::<Vec<_>>();
-    for (entity, actions) in query.into_iter() {
+    let mut uses_consumption = false;
+    for (entity, actions, set_mode) in query.into_iter() {
         for action in actions.into_iter() {
-            for (binding_type_id, system) in binding_id_systems.0.iter() {
-                let id = match world.run_system_with(*system, action) {
-                    Ok(id) => id,
-                    Err(err) => {
-                        error!("error while running binding id system: {err}");
-                        continue;
-                    }
+            let mode = world.get::<BlockingMode>(action).copied().or(set_mode);
+            // these only block the inputs they consume while syncing
+            if mode == Some(BlockingMode::WhenTriggered) {
+                uses_consumption = true;
+                continue;
+            }
+            let set_bindings = bindings.entry(entity).or_default();
+            for (binding_type_id, ids) in binding_id_systems.binding_ids(world, action) {
+                set_bindings.entry(binding_type_id).or_default().extend(ids);
+            }
+        }
+    }
+    let groups = priority_groups(world);
+    let owners = groups
+        .iter()
+        .flatten()
+        .map(|set| (*set, world.get::<OwnedByPlayer>(*set).map(|owner| owner.0)))
+        .collect::<EntityHashMap<_>>();
+    let mut scopes = owners.values().copied().collect::<Vec<_>>();
+    scopes.sort_unstable();
+    scopes.dedup();
+    // the sets of each player are only blocked by their own sets and the sets without an owner
+    for scope in scopes {
+        let mut last: HashMap<u64, HashSet<u64>> = default();
+        for sets in groups.iter() {
+            let blocked = BlockedInputs(last.clone());
+            for set in sets.iter().copied() {
+                if owners[&set] == scope && world.get::<BlockedInputs>(set) != Some(&blocked) {
+                    world.entity_mut(set).insert(blocked.clone());
+                }
+            }
+            for set in sets.iter().filter(|set| blocks(owners[*set], scope)) {
+                let Some(data) = bindings.get(set) else {
+                    continue;
                 };
-                bindings
-                    .entry(entity)
-                    .or_default()
-                    .entry(*binding_type_id)
-                    .or_default()
-                    .extend(id);
+                for (binding_type_id, ids) in data {
+                    last.entry(*binding_type_id).or_default().extend(ids);
+                }
             }
         }
     }
+
+    // disabled and transparent sets are never blocked
+    let grouped = groups.iter().flatten().copied().collect::<EntityHashSet>();
+    let stale = world
+        .query_filtered::<Entity, With<BlockedInputs>>()
+        .iter(world)
+        .filter(|set| !grouped.contains(set))
+        .collect::<Vec<_>>();
+    for set in stale {
+        world.entity_mut(set).remove::<BlockedInputs>();
+    }
+
+    let mut order = mem::take(&mut world.resource_mut::<ActionOrder>().0);
+    order.clear();
+    if uses_consumption {
+        // actions of sets without a priority group go first, nothing consumes inputs for them
+        order.extend(
+            world
+                .query::<(Entity, &Action)>()
+                .iter(world)
+                .filter(|(_, action)| !grouped.contains(&action.set))
+                .map(|(e, _)| (e, 0)),
+        );
+        for (group, sets) in groups.iter().enumerate() {
+            for set in sets {
+                let Some(actions) = world.get::<ActionsInSet>(*set) else {
+                    continue;
+                };
+                order.extend(actions.iter().map(|action| (action, group + 1)));
+            }
+        }
+    }
+    world.resource_mut::<ActionOrder>().0 = order;
+
+    world.insert_resource(binding_id_systems);
+}
+
+/// Can a set owned by `blocker` block or consume inputs for a set owned by `owner`,
+/// sets without an owner block the sets of every player.
+pub(crate) fn blocks(blocker: Option<Entity>, owner: Option<Entity>) -> bool {
+    blocker.is_none() || blocker == owner
+}
+
+/// The enabled, non transparent sets grouped by priority, highest first.
+pub(crate) fn priority_groups(world: &mut World) -> Vec<Vec<Entity>> {
     let priorities = world
-        .query::<(Entity, &ActionSet)>()
+        .query::<(Entity, &ActionSet, Option<&ContextLayer>)>()
         .iter(world)
-        .filter(|(_, s)| s.enabled && !s.transparent)
-        .map(|(e, s)| (e, s.priority))
+        .filter(|(_, s, _)| s.enabled)
+        .filter_map(|(e, s, layer)| Some((e, EffectivePriority::of(s, layer)?)))
         .collect::<Vec<_>>();
-    let mut priority_sets = HashMap::<u32, Vec<Entity>>::new();
+    let mut priority_sets = HashMap::<EffectivePriority, Vec<Entity>>::new();
     for (e, p) in priorities.iter().cloned() {
         priority_sets.entry(p).or_default().push(e);
     }
     let mut sets_set = priority_sets.into_iter().collect::<Vec<_>>();
     sets_set.sort_by_key(|&(priority, _)| priority);
     sets_set.reverse();
-    let mut last: HashMap<u64, HashSet<u64>> = default();
-    for (_, sets) in sets_set {
-        let blocked = BlockedInputs(last.clone());
-        for set in sets {
-            world.entity_mut(set).insert(blocked.clone());
-            let Some(data) = bindings.get(&set) else {
-                continue;
-            };
-            for (binding_type_id, ids) in data {
-                last.entry(*binding_type_id).or_default().extend(ids);
+    sets_set.into_iter().map(|(_, sets)| sets).collect()
+}
+
+/// When the bindings of an action block the same inputs in [`ActionSet`]s with a lower
+/// priority, put it on an action or a set, the one on the action is used if both have one.
+#[derive(Clone, Copy, Component, Debug, Default, Reflect, PartialEq, Eq, Hash)]
+pub enum BlockingMode {
+    /// The inputs are blocked while the set is enabled.
+    #[default]
+    Always,
+    /// The inputs are only blocked on frames where the binding of the action has a non zero
+    /// value, it consumes them. OpenXR bindings are always blocked by the runtime.
+    ///
+    /// Inputs are only consumed for bindings of the same provider, e.g. a triggered keyboard
+    /// binding consumes its key for the keyboard bindings of lower sets, not for their chord
+    /// bindings. Held chords consume their inputs for the bindings of every provider in lower
+    /// sets in both modes, triggered sequences don't consume the inputs of their steps.
+    WhenTriggered,
+}
+
+/// The actions in the order their inputs have to be synced in, highest priority first,
+/// with their priority group. Empty if no action uses [`BlockingMode::WhenTriggered`].
+#[derive(Clone, Resource, Debug, Default)]
+pub(crate) struct ActionOrder(pub(crate) Vec<(Entity, usize)>);
+
+/// The priority [`ActionSet`]s block each other with, sets of shadowing contexts
+/// block everything below them, regardless of priority.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EffectivePriority {
+    pub layer: u32,
+    pub priority: u32,
+}
+
+impl EffectivePriority {
+    /// [`None`] for transparent sets, they don't block and aren't blocked.
+    pub fn of(set: &ActionSet, layer: Option<&ContextLayer>) -> Option<Self> {
+        (!set.transparent).then(|| Self {
+            layer: layer.map_or(0, |layer| layer.0),
+            priority: set.priority,
+        })
+    }
+}
+
+#[derive(Clone, Component, Debug, PartialEq, Eq)]
+pub struct BlockedInputs(pub HashMap<u64, HashSet<u64>>);
+
+impl BlockedInputs {
+    pub fn is_blocked(&self, label_id: u64, binding_id: u64) -> bool {
+        self.0
+            .get(&label_id)
+            .is_some_and(|ids| ids.contains(&binding_id))
+    }
+}
+
+/// Inputs that are consumed for this frame, bindings using them are ignored in every
+/// [`ActionSet`], e.g. the plain binding of the main input of a chord,
+/// or only in the sets below a priority, e.g. the members of a held chord.
+///
+/// Inputs consumed by a set owned by a player are only consumed for the sets of that player.
+#[derive(Clone, Resource, Debug, Default)]
+pub struct ConsumedInputs {
+    all: HashMap<u64, HashSet<u64>>,
+    below: HashMap<(u64, u64, Option<Entity>), EffectivePriority>,
+    /// which actions consumed the inputs, for debugging
+    consumptions: Vec<Consumption>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Consumption {
+    label_id: u64,
+    binding_id: u64,
+    owner: Option<Entity>,
+    /// [`None`] when consumed for every set
+    below: Option<EffectivePriority>,
+    by: BlockingAction,
+}
+
+impl ConsumedInputs {
+    /// Consumes the input for every set, `by` is the action consuming it.
+    pub fn consume(&mut self, label_id: u64, binding_id: u64, by: BlockingAction) {
+        self.all.entry(label_id).or_default().insert(binding_id);
+        self.consumptions.push(Consumption {
+            label_id,
+            binding_id,
+            owner: None,
+            below: None,
+            by,
+        });
+    }
+    /// Consumes the input for the sets with a lower priority, nothing for transparent sets,
+    /// `owner` is the player owning the consuming set and `by` the action consuming it.
+    pub fn consume_below(
+        &mut self,
+        label_id: u64,
+        binding_id: u64,
+        priority: Option<EffectivePriority>,
+        owner: Option<Entity>,
+        by: BlockingAction,
+    ) {
+        let Some(priority) = priority else {
+            return;
+        };
+        let below = self.below.entry((label_id, binding_id, owner)).or_default();
+        *below = priority.max(*below);
+        self.consumptions.push(Consumption {
+            label_id,
+            binding_id,
+            owner,
+            below: Some(priority),
+            by,
+        });
+    }
+    /// The actions that consumed the input for a set with the priority, owned by `owner`.
+    pub fn consumed_by(
+        &self,
+        label_id: u64,
+        binding_id: u64,
+        priority: Option<EffectivePriority>,
+        owner: Option<Entity>,
+    ) -> Vec<BlockingAction> {
+        let mut out = Vec::new();
+        for consumption in self.consumptions.iter() {
+            let applies = consumption.label_id == label_id
+                && consumption.binding_id == binding_id
+                && match consumption.below {
+                    None => true,
+                    Some(below) => {
+                        priority.is_some_and(|priority| below > priority)
+                            && blocks(consumption.owner, owner)
+                    }
+                };
+            if applies && !out.contains(&consumption.by) {
+                out.push(consumption.by);
             }
         }
+        out
     }
+    /// Is the input consumed for a set with the priority, owned by `owner`.
+    pub fn is_consumed(
+        &self,
+        label_id: u64,
+        binding_id: u64,
+        priority: Option<EffectivePriority>,
+        owner: Option<Entity>,
+    ) -> bool {
+        let consumed_by = |blocker: Option<Entity>, priority: EffectivePriority| {
+            self.below
+                .get(&(label_id, binding_id, blocker))
+                .is_some_and(|below| *below > priority)
+        };
+        self.all
+            .get(&label_id)
+            .is_some_and(|ids| ids.contains(&binding_id))
+            || priority.is_some_and(|priority| {
+                consumed_by(None, priority) || (owner.is_some() && consumed_by(owner, priority))
+            })
+    }
+}
 
-    world.insert_resource(binding_id_systems);
+/// multiple systems can provide ids for the same label
+#[derive(Resource, Default)]
+pub(crate) struct BindingIdSystems {
+    systems: HashMap<u64, Vec<SystemId<In<Entity>, Vec<u64>>>>,
+    description_systems: HashMap<u64, Vec<SystemId<In<Entity>, Vec<(u64, String)>>>>,
+    labels: HashMap<u64, String>,
+    tracked_components: HashSet<TypeId>,
+    has_untracked: bool,
 }
 
-#[derive(Clone, Component, Debug)]
-pub struct BlockedInputs(pub HashMap<u64, HashSet<u64>>);
+impl BindingIdSystems {
+    /// The ids of the bindings of the action, by label id.
+    pub(crate) fn binding_ids(&self, world: &mut World, action: Entity) -> HashMap<u64, Vec<u64>> {
+        let mut out = HashMap::<u64, Vec<u64>>::new();
+        for (binding_type_id, systems) in self.systems.iter() {
+            for system in systems {
+                let id = match world.run_system_with(*system, action) {
+                    Ok(id) => id,
+                    Err(err) => {
+                        error!("error while running binding id system: {err}");
+                        continue;
+                    }
+                };
+                out.entry(*binding_type_id).or_default().extend(id);
+            }
+        }
+        out
+    }
+    /// The readable descriptions of the bindings of the action by label id and binding id,
+    /// e.g. `keyboard:KeyW`.
+    pub(crate) fn descriptions(
+        &self,
+        world: &mut World,
+        action: Entity,
+    ) -> HashMap<(u64, u64), String> {
+        let mut out = HashMap::new();
+        for (binding_type_id, systems) in self.description_systems.iter() {
+            for system in systems {
+                match world.run_system_with(*system, action) {
+                    Ok(descriptions) => out.extend(
+                        descriptions
+                            .into_iter()
+                            .map(|(id, description)| ((*binding_type_id, id), description)),
+                    ),
+                    Err(err) => error!("error while running binding description system: {err}"),
+                }
+            }
+        }
+        out
+    }
+    /// For bindings without a description, e.g. `schminput:keyboard:0x1f`.
+    pub(crate) fn fallback_description(&self, label_id: u64, binding_id: u64) -> String {
+        match self.labels.get(&label_id) {
+            Some(label) => format!("{label}:{binding_id:#x}"),
+            None => format!("{label_id:#x}:{binding_id:#x}"),
+        }
+    }
+}
 
-#[derive(Resource, Default)]
-struct BindingIdSystems(HashMap<u64, SystemId<In<Entity>, Vec<u64>>>);
+fn register_binding_id_system<M>(
+    app: &mut App,
+    label: &str,
+    system: impl IntoSystem<In<Entity>, Vec<u64>, M> + 'static,
+) {
+    app.init_resource::<BindingIdSystems>();
+    let mut hasher = DefaultHasher::new();
+    label.hash(&mut hasher);
+    let label_id = hasher.finish();
+    let system = app.register_system(system);
+    let mut systems = app.world_mut().resource_mut::<BindingIdSystems>();
+    systems.labels.insert(label_id, label.to_string());
+    systems.systems.entry(label_id).or_default().push(system);
+}
 
 pub trait PriorityAppExt {
+    /// The blocked inputs are recomputed every frame while untracked binding id systems
+    /// exist, use [`PriorityAppExt::add_tracked_binding_id_system`] when possible.
     fn add_binding_id_system<M>(
         &mut self,
         label: &str,
         system: impl IntoSystem<In<Entity>, Vec<u64>, M> + 'static,
     ) -> &mut Self;
+    /// The ids are only requested again after changes tracked through
+    /// [`PriorityAppExt::track_binding_component`], `C` is the component with the bindings
+    /// the system reads, it gets tracked by this.
+    fn add_tracked_binding_id_system<C: Component, M>(
+        &mut self,
+        label: &str,
+        system: impl IntoSystem<In<Entity>, Vec<u64>, M> + 'static,
+    ) -> &mut Self;
+    /// Recomputes the [`BlockedInputs`] when the component changes, call it for every
+    /// other component that tracked binding id systems read.
+    fn track_binding_component<C: Component>(&mut self) -> &mut Self;
+    /// Describes the bindings of an action for debugging, e.g. `keyboard:KeyW`,
+    /// the ids have to match the ones from the binding id systems of the label.
+    fn add_binding_description_system<M>(
+        &mut self,
+        label: &str,
+        system: impl IntoSystem<In<Entity>, Vec<(u64, String)>, M> + 'static,
+    ) -> &mut Self;
 }
 impl PriorityAppExt for App {
     fn add_binding_id_system<M>(
         &mut self,
         label: &str,
         system: impl IntoSystem<In<Entity>, Vec<u64>, M> + 'static,
+    ) -> &mut Self {
+        register_binding_id_system(self, label, system);
+        self.world_mut()
+            .resource_mut::<BindingIdSystems>()
+            .has_untracked = true;
+        self
+    }
+    fn add_tracked_binding_id_system<C: Component, M>(
+        &mut self,
+        label: &str,
+        system: impl IntoSystem<In<Entity>, Vec<u64>, M> + 'static,
+    ) -> &mut Self {
+        self.track_binding_component::<C>();
+        register_binding_id_system(self, label, system);
+        self
+    }
+    fn track_binding_component<C: Component>(&mut self) -> &mut Self {
+        self.init_resource::<BindingIdSystems>();
+        let newly_tracked = self
+            .world_mut()
+            .resource_mut::<BindingIdSystems>()
+            .tracked_components
+            .insert(TypeId::of::<C>());
+        if newly_tracked {
+            self.add_systems(
+                PreUpdate,
+                detect_binding_changes::<C>
+                    .in_set(SchminputSystems::CalculateBindingCollisions)
+                    .before(system),
+            );
+        }
+        self
+    }
+    fn add_binding_description_system<M>(
+        &mut self,
+        label: &str,
+        system: impl IntoSystem<In<Entity>, Vec<(u64, String)>, M> + 'static,
     ) -> &mut Self {
         self.init_resource::<BindingIdSystems>();
-        let mut hasher = DefaultHasher::new();
-        label.hash(&mut hasher);
         let system = self.register_system(system);
         self.world_mut()
             .resource_mut::<BindingIdSystems>()
-            .0
-            .insert(hasher.finish(), system);
+            .description_systems
+            .entry(crate::impl_helpers::label_id(label))
+            .or_default()
+            .push(system);
         self
     }
 }
@@ -0,0 +1,359 @@
+use std::fmt::Debug;
+
+use bevy::{
+    ecs::entity::EntityHashSet,
+    math::cubic_splines::{CubicCardinalSpline, CubicCurve, CubicGenerator},
+    prelude::*,
+};
+
+use crate::{SchminputSystems, impl_helpers::BindingValue};
+
+pub struct ProcessorPlugin;
+
+impl Plugin for ProcessorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ProcessorOf>();
+        app.register_type::<InputProcessors>();
+        app.register_type::<AxialDeadzone>();
+        app.register_type::<RadialDeadzone>();
+        app.register_type::<Scale>();
+        app.register_type::<Invert>();
+        app.register_type::<Swizzle>();
+        app.register_type::<ResponseCurve>();
+        app.register_type::<Clamp>();
+        app.register_type::<Normalize>();
+        app.init_resource::<ProcessorRegistry>();
+        app.init_resource::<ChangedProcessorChains>();
+        app.add_systems(
+            PreUpdate,
+            (detect_chain_changes, build_processor_chains)
+                .chain()
+                .in_set(SchminputSystems::BuildProcessorChains),
+        );
+        app.add_input_processor::<AxialDeadzone>();
+        app.add_input_processor::<RadialDeadzone>();
+        app.add_input_processor::<Scale>();
+        app.add_input_processor::<Invert>();
+        app.add_input_processor::<Swizzle>();
+        app.add_input_processor::<ResponseCurve>();
+        app.add_input_processor::<Clamp>();
+        app.add_input_processor::<Normalize>();
+    }
+}
+
+/// Implemented by processor components, register them with
+/// [`InputProcessorAppExt::add_input_processor`] to add your own processors.
+///
+/// Processors get the value of all bindings of one input source that match the same
+/// modifications combined, so radial processors see the whole stick and not single axes.
+/// Bool values are not processed.
+pub trait InputProcessor: Debug + Send + Sync + 'static {
+    fn process_f32(&self, value: f32) -> f32 {
+        value
+    }
+    fn process_vec2(&self, value: Vec2) -> Vec2 {
+        value
+    }
+}
+
+pub trait InputProcessorAppExt {
+    /// Registers a processor component, entities with it that are [`ProcessorOf`] a
+    /// modification entity are part of its processor chain. Changing the component updates
+    /// the chain through a system added by this.
+    fn add_input_processor<P: InputProcessor + Component + Clone>(&mut self) -> &mut Self;
+}
+
+impl InputProcessorAppExt for App {
+    fn add_input_processor<P: InputProcessor + Component + Clone>(&mut self) -> &mut Self {
+        self.init_resource::<ProcessorRegistry>();
+        self.init_resource::<ChangedProcessorChains>();
+        self.world_mut()
+            .resource_mut::<ProcessorRegistry>()
+            .0
+            .push(|entity| {
+                entity
+                    .get::<P>()
+                    .map(|p| Box::new(p.clone()) as Box<dyn InputProcessor>)
+            });
+        self.add_systems(
+            PreUpdate,
+            detect_processor_changes::<P>
+                .in_set(SchminputSystems::BuildProcessorChains)
+                .before(build_processor_chains),
+        )
+    }
+}
+
+/// Makes the processor on this entity a step in the [`InputProcessors`] of a modification
+/// entity referenced in [`BindingModifications`](crate::binding_modification::BindingModifications).
+#[derive(Clone, Copy, Debug, Component, Reflect, PartialEq, Eq)]
+#[relationship(relationship_target = InputProcessors)]
+pub struct ProcessorOf(pub Entity);
+
+/// The ordered processor chain of a modification entity, spawn it with
+/// `related!(InputProcessors[RadialDeadzone::new(0.2), Scale::splat(2.0)])`.
+///
+/// The processors of the all paths modification run first, followed by the ones of
+/// every matching per path modification in order.
+/// Processors run on the raw values, before the delta of a
+/// [`PremultiplyDeltaSecsModification`](crate::binding_modification::PremultiplyDeltaSecsModification)
+/// is applied.
+///
+/// The processor components can be changed at runtime, e.g. from a system that syncs them
+/// with your own settings component.
+#[derive(Clone, Debug, Default, Component, Reflect, Deref)]
+#[relationship_target(relationship = ProcessorOf, linked_spawn)]
+pub struct InputProcessors(Vec<Entity>);
+
+/// The processors of the [`InputProcessors`] of a modification entity, kept up to date
+/// with the processor components so the providers don't need to look them up.
+#[derive(Debug, Default, Component)]
+pub struct ProcessorChain(Vec<Box<dyn InputProcessor>>);
+
+impl ProcessorChain {
+    pub fn iter(&self) -> impl Iterator<Item = &dyn InputProcessor> {
+        self.0.iter().map(|p| p.as_ref())
+    }
+    pub fn process(&self, mut value: BindingValue) -> BindingValue {
+        for processor in self.0.iter() {
+            value.f32 = value.f32.map(|v| processor.process_f32(v));
+            value.vec2 = value.vec2.map(|v| processor.process_vec2(v));
+        }
+        value
+    }
+}
+
+/// Reads the registered processor component of an entity.
+#[derive(Resource, Default)]
+struct ProcessorRegistry(Vec<fn(EntityRef<'_>) -> Option<Box<dyn InputProcessor>>>);
+
+/// Modification entities whose [`ProcessorChain`] has to be rebuilt.
+#[derive(Resource, Default)]
+struct ChangedProcessorChains(EntityHashSet);
+
+fn detect_processor_changes<P: Component>(
+    query: Query<&ProcessorOf, Changed<P>>,
+    of_query: Query<&ProcessorOf>,
+    mut removed: RemovedComponents<P>,
+    mut changed: ResMut<ChangedProcessorChains>,
+) {
+    changed.0.extend(query.iter().map(|of| of.0));
+    // despawned processors are removed from the chain through the relationship
+    changed
+        .0
+        .extend(removed.read().filter_map(|e| Some(of_query.get(e).ok()?.0)));
+}
+
+fn detect_chain_changes(
+    query: Query<Entity, Changed<InputProcessors>>,
+    mut removed: RemovedComponents<InputProcessors>,
+    mut changed: ResMut<ChangedProcessorChains>,
+) {
+    changed.0.extend(query.iter());
+    changed.0.extend(removed.read());
+}
+
+fn build_processor_chains(mut changed: ResMut<ChangedProcessorChains>, mut cmds: Commands) {
+    if changed.0.is_empty() {
+        return;
+    }
+    let changed = std::mem::take(&mut changed.0);
+    cmds.queue(move |world: &mut World| {
+        world.resource_scope(|world, registry: Mut<ProcessorRegistry>| {
+            for modification in changed {
+                let Ok(entity) = world.get_entity(modification) else {
+                    continue;
+                };
+                let chain = entity.get::<InputProcessors>().map(|processors| {
+                    processors
+                        .iter()
+                        .filter_map(|p| {
+                            let p = world.get_entity(p).ok()?;
+                            registry.0.iter().find_map(|get| get(p))
+                        })
+                        .collect()
+                });
+                let mut entity = world.entity_mut(modification);
+                match chain {
+                    Some(chain) => entity.insert(ProcessorChain(chain)),
+                    None => entity.remove::<ProcessorChain>(),
+                };
+            }
+        });
+    });
+}
+
+/// Maps the magnitude of `value` from `lower..upper` to `0..1`.
+fn deadzone(value: f32, lower: f32, upper: f32) -> f32 {
+    if upper <= lower {
+        return (value.abs() > lower) as u8 as f32 * value.signum();
+    }
+    ((value.abs() - lower) / (upper - lower)).clamp(0.0, 1.0) * value.signum()
+}
+
+/// Applies a deadzone to each axis separately, values below `lower` become 0,
+/// values above `upper` become 1.
+#[derive(Clone, Copy, Debug, Component, Reflect, PartialEq)]
+pub struct AxialDeadzone {
+    pub lower: f32,
+    pub upper: f32,
+}
+impl AxialDeadzone {
+    pub fn new(lower: f32) -> Self {
+        Self { lower, upper: 1.0 }
+    }
+}
+impl InputProcessor for AxialDeadzone {
+    fn process_f32(&self, value: f32) -> f32 {
+        deadzone(value, self.lower, self.upper)
+    }
+    fn process_vec2(&self, value: Vec2) -> Vec2 {
+        Vec2::new(
+            deadzone(value.x, self.lower, self.upper),
+            deadzone(value.y, self.lower, self.upper),
+        )
+    }
+}
+
+/// Applies a deadzone to the length of the vector, keeping its direction.
+#[derive(Clone, Copy, Debug, Component, Reflect, PartialEq)]
+pub struct RadialDeadzone {
+    pub lower: f32,
+    pub upper: f32,
+}
+impl RadialDeadzone {
+    pub fn new(lower: f32) -> Self {
+        Self { lower, upper: 1.0 }
+    }
+}
+impl InputProcessor for RadialDeadzone {
+    fn process_f32(&self, value: f32) -> f32 {
+        deadzone(value, self.lower, self.upper)
+    }
+    fn process_vec2(&self, value: Vec2) -> Vec2 {
+        let length = value.length();
+        if length == 0.0 {
+            return Vec2::ZERO;
+        }
+        value / length * deadzone(length, self.lower, self.upper)
+    }
+}
+
+/// Multiplies the value, floats use the x component.
+#[derive(Clone, Copy, Debug, Component, Reflect, PartialEq)]
+pub struct Scale(pub Vec2);
+impl Scale {
+    pub fn splat(scale: f32) -> Self {
+        Self(Vec2::splat(scale))
+    }
+}
+impl InputProcessor for Scale {
+    fn process_f32(&self, value: f32) -> f32 {
+        value * self.0.x
+    }
+    fn process_vec2(&self, value: Vec2) -> Vec2 {
+        value * self.0
+    }
+}
+
+/// Negates the selected axes, floats use the x axis.
+#[derive(Clone, Copy, Debug, Component, Reflect, PartialEq)]
+pub struct Invert(pub BVec2);
+impl Invert {
+    pub const X: Self = Self(BVec2::new(true, false));
+    pub const Y: Self = Self(BVec2::new(false, true));
+    pub const BOTH: Self = Self(BVec2::TRUE);
+}
+impl InputProcessor for Invert {
+    fn process_f32(&self, value: f32) -> f32 {
+        match self.0.x {
+            true => -value,
+            false => value,
+        }
+    }
+    fn process_vec2(&self, value: Vec2) -> Vec2 {
+        Vec2::select(self.0, -value, value)
+    }
+}
+
+/// Swaps the x and y axes.
+#[derive(Clone, Copy, Debug, Component, Reflect, PartialEq)]
+pub struct Swizzle;
+impl InputProcessor for Swizzle {
+    fn process_vec2(&self, value: Vec2) -> Vec2 {
+        value.yx()
+    }
+}
+
+/// Remaps the magnitude of each axis in the range 0..=1, keeping the sign.
+#[derive(Clone, Debug, Component, Reflect, PartialEq)]
+pub enum ResponseCurve {
+    /// Raises the magnitude to the power.
+    Power(f32),
+    /// A spline through evenly spaced output values for the inputs 0..=1,
+    /// created with [`ResponseCurve::spline`].
+    Spline(CubicCurve<f32>),
+}
+impl ResponseCurve {
+    /// Creates a Catmull-Rom spline through the `points`, the first point is the output
+    /// for an input of 0 and the last one for 1, returns [`None`] for less than two points.
+    pub fn spline(points: impl IntoIterator<Item = f32>) -> Option<Self> {
+        CubicCardinalSpline::new_catmull_rom(points)
+            .to_curve()
+            .ok()
+            .map(ResponseCurve::Spline)
+    }
+    fn apply(&self, value: f32) -> f32 {
+        let magnitude = value.abs().min(1.0);
+        let out = match self {
+            ResponseCurve::Power(power) => magnitude.powf(*power),
+            ResponseCurve::Spline(curve) => {
+                curve.position(magnitude * curve.segments().len() as f32)
+            }
+        };
+        out * value.signum()
+    }
+}
+impl InputProcessor for ResponseCurve {
+    fn process_f32(&self, value: f32) -> f32 {
+        self.apply(value)
+    }
+    fn process_vec2(&self, value: Vec2) -> Vec2 {
+        Vec2::new(self.apply(value.x), self.apply(value.y))
+    }
+}
+
+/// Clamps each axis.
+#[derive(Clone, Copy, Debug, Component, Reflect, PartialEq)]
+pub struct Clamp {
+    pub min: f32,
+    pub max: f32,
+}
+impl Clamp {
+    pub fn new(min: f32, max: f32) -> Self {
+        Self { min, max }
+    }
+}
+impl InputProcessor for Clamp {
+    fn process_f32(&self, value: f32) -> f32 {
+        value.clamp(self.min, self.max)
+    }
+    fn process_vec2(&self, value: Vec2) -> Vec2 {
+        value.clamp(Vec2::splat(self.min), Vec2::splat(self.max))
+    }
+}
+
+/// Normalizes vectors and turns floats into -1, 0 or 1, zero stays zero.
+#[derive(Clone, Copy, Debug, Component, Reflect, PartialEq)]
+pub struct Normalize;
+impl InputProcessor for Normalize {
+    fn process_f32(&self, value: f32) -> f32 {
+        match value == 0.0 {
+            true => 0.0,
+            false => value.signum(),
+        }
+    }
+    fn process_vec2(&self, value: Vec2) -> Vec2 {
+        value.normalize_or_zero()
+    }
+}
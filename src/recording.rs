@@ -0,0 +1,412 @@
+use std::{io, mem, path::Path, time::Duration};
+
+use bevy::{ecs::entity::EntityHashSet, platform::collections::HashMap, prelude::*};
+
+use crate::{
+    Action, ActionSet, BoolActionValue, F32ActionValue, SchminputSystems, Vec2ActionValue,
+    action_events::ActionEventValue,
+    impl_helpers::DisabledProviders,
+    subaction_paths::{SubactionPathMap, SubactionPathStr, SubactionPaths},
+};
+
+const HEADER: &str = "schminput-recording 1";
+
+/// Records the final action values every frame and plays them back, controlled
+/// through the [`InputRecorder`] resource.
+pub struct RecordingPlugin;
+
+impl Plugin for RecordingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputRecorder>();
+        app.add_message::<PlaybackFinished>();
+        app.add_systems(
+            PreUpdate,
+            update_disabled_providers.in_set(SchminputSystems::ClearValues),
+        );
+        app.add_systems(
+            PreUpdate,
+            (play_back, record)
+                .chain()
+                .after(SchminputSystems::SyncInputActions)
+                .before(SchminputSystems::AccumulateFixedValues),
+        );
+    }
+}
+
+/// Written when an [`InputRecording`] played back to the end.
+#[derive(Clone, Copy, Debug, Message)]
+pub struct PlaybackFinished;
+
+/// The action values of every frame, keyed by the names of the [`ActionSet`] and [`Action`],
+/// so a recording can be played back in another run of the app.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputRecording {
+    pub frames: Vec<RecordedFrame>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RecordedFrame {
+    /// The frame delta while recording, playback advances one frame per app frame,
+    /// use this with [`TimeUpdateStrategy::ManualDuration`] for deterministic timing.
+    pub delta: Duration,
+    pub values: Vec<RecordedValue>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedValue {
+    pub set: String,
+    pub action: String,
+    /// [`None`] for the value of all paths combined
+    pub path: Option<String>,
+    pub value: ActionEventValue,
+}
+
+impl InputRecording {
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        std::fs::read_to_string(path)?.parse()
+    }
+}
+
+impl std::fmt::Display for InputRecording {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{HEADER}")?;
+        for frame in self.frames.iter() {
+            writeln!(f, "frame\t{}", frame.delta.as_nanos())?;
+            for value in frame.values.iter() {
+                let (ty, value_str) = match value.value {
+                    ActionEventValue::Bool(v) => ("bool", v.to_string()),
+                    ActionEventValue::F32(v) => ("f32", v.to_string()),
+                    ActionEventValue::Vec2(v) => ("vec2", format!("{} {}", v.x, v.y)),
+                };
+                writeln!(
+                    f,
+                    "{ty}\t{}\t{}\t{}\t{value_str}",
+                    escape(&value.set),
+                    escape(&value.action),
+                    value.path.as_deref().map_or("*".into(), escape),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for InputRecording {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |line: usize, msg: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid recording at line {}: {msg}", line + 1),
+            )
+        };
+        let mut lines = s.lines().enumerate();
+        if lines.next().map(|(_, l)| l) != Some(HEADER) {
+            return Err(error(0, "unknown header"));
+        }
+        let mut recording = InputRecording::default();
+        for (i, line) in lines.filter(|(_, l)| !l.is_empty()) {
+            let fields = line.split('\t').collect::<Vec<_>>();
+            match fields.as_slice() {
+                ["frame", nanos] => {
+                    let nanos = nanos.parse().map_err(|_| error(i, "invalid delta"))?;
+                    recording.frames.push(RecordedFrame {
+                        delta: Duration::from_nanos(nanos),
+                        values: Vec::new(),
+                    });
+                }
+                [ty, set, action, path, value] => {
+                    let value = match *ty {
+                        "bool" => value.parse().ok().map(ActionEventValue::Bool),
+                        "f32" => value.parse().ok().map(ActionEventValue::F32),
+                        "vec2" => value.split_once(' ').and_then(|(x, y)| {
+                            Some(ActionEventValue::Vec2(Vec2::new(
+                                x.parse().ok()?,
+                                y.parse().ok()?,
+                            )))
+                        }),
+                        _ => return Err(error(i, "unknown value type")),
+                    }
+                    .ok_or_else(|| error(i, "invalid value"))?;
+                    let Some(frame) = recording.frames.last_mut() else {
+                        return Err(error(i, "value before the first frame"));
+                    };
+                    frame.values.push(RecordedValue {
+                        set: unescape(set),
+                        action: unescape(action),
+                        path: (*path != "*").then(|| unescape(path)),
+                        value,
+                    });
+                }
+                _ => return Err(error(i, "wrong number of fields")),
+            }
+        }
+        Ok(recording)
+    }
+}
+
+fn escape(str: &str) -> String {
+    str.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+fn unescape(str: &str) -> String {
+    let mut out = String::with_capacity(str.len());
+    let mut chars = str.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('t') => out.push('\t'),
+                Some('n') => out.push('\n'),
+                Some(c) => out.push(c),
+                None => out.push('\\'),
+            },
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// What happens while playing back a recording.
+#[derive(Clone, Debug)]
+pub struct InputPlaybackSettings {
+    /// The labels of the providers that are disabled during playback,
+    /// values from providers that stay enabled are replaced by recorded values.
+    pub disabled_providers: Vec<&'static str>,
+}
+
+impl Default for InputPlaybackSettings {
+    /// disables all built in providers
+    fn default() -> Self {
+        Self {
+            disabled_providers: vec![
+                "schminput:keyboard",
+                "schminput:mouse",
+                "schminput:gamepad",
+                "schminput:chord",
+                "schminput:sequence",
+                "schminput:openxr",
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+enum RecorderState {
+    #[default]
+    Idle,
+    Recording(InputRecording),
+    Playing {
+        recording: InputRecording,
+        frame: usize,
+        settings: InputPlaybackSettings,
+    },
+}
+
+/// Starts and stops recording and playback.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct InputRecorder {
+    state: RecorderState,
+    /// the providers this resource disabled in [`DisabledProviders`]
+    disabled_providers: Vec<&'static str>,
+}
+
+impl InputRecorder {
+    /// Starts a new recording, stopping playback.
+    pub fn start_recording(&mut self) {
+        self.state = RecorderState::Recording(InputRecording::default());
+    }
+    /// Returns the recording, or [`None`] if nothing was being recorded.
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        match mem::take(&mut self.state) {
+            RecorderState::Recording(recording) => Some(recording),
+            state => {
+                self.state = state;
+                None
+            }
+        }
+    }
+    /// Plays the recording from the start on the next frame, stopping any recording.
+    pub fn start_playback(&mut self, recording: InputRecording, settings: InputPlaybackSettings) {
+        self.state = RecorderState::Playing {
+            recording,
+            frame: 0,
+            settings,
+        };
+    }
+    pub fn stop_playback(&mut self) {
+        if self.is_playing() {
+            self.state = RecorderState::Idle;
+        }
+    }
+    pub fn is_recording(&self) -> bool {
+        matches!(self.state, RecorderState::Recording(_))
+    }
+    pub fn is_playing(&self) -> bool {
+        matches!(self.state, RecorderState::Playing { .. })
+    }
+}
+
+fn update_disabled_providers(
+    mut recorder: ResMut<InputRecorder>,
+    mut disabled: ResMut<DisabledProviders>,
+) {
+    let recorder = recorder.as_mut();
+    let wanted = match &recorder.state {
+        RecorderState::Playing { settings, .. } => settings.disabled_providers.as_slice(),
+        _ => &[],
+    };
+    if recorder.disabled_providers == wanted {
+        return;
+    }
+    for label in recorder.disabled_providers.iter() {
+        disabled.enable(label);
+    }
+    for label in wanted.iter() {
+        disabled.disable(label);
+    }
+    recorder.disabled_providers = wanted.to_vec();
+}
+
+#[allow(clippy::type_complexity)]
+fn play_back(
+    mut recorder: ResMut<InputRecorder>,
+    mut query: Query<(
+        Entity,
+        &Action,
+        Option<&mut BoolActionValue>,
+        Option<&mut F32ActionValue>,
+        Option<&mut Vec2ActionValue>,
+    )>,
+    set_query: Query<&ActionSet>,
+    mut paths: ResMut<SubactionPaths>,
+    mut finished: MessageWriter<PlaybackFinished>,
+    mut cmds: Commands,
+) {
+    let RecorderState::Playing {
+        recording, frame, ..
+    } = &mut recorder.state
+    else {
+        return;
+    };
+    let Some(recorded) = recording.frames.get_mut(*frame).map(mem::take) else {
+        recorder.state = RecorderState::Idle;
+        finished.write(PlaybackFinished);
+        return;
+    };
+    *frame += 1;
+    // stopping now lets the providers run again on the next frame
+    if *frame == recording.frames.len() {
+        recorder.state = RecorderState::Idle;
+        finished.write(PlaybackFinished);
+    }
+
+    let actions = query
+        .iter()
+        .filter_map(|(e, action, ..)| {
+            let set = set_query.get(action.set).ok()?;
+            Some(((set.name.to_string(), action.name.to_string()), e))
+        })
+        .collect::<HashMap<_, _>>();
+    // recorded actions only have the recorded values, even if their providers are enabled
+    let mut reset = EntityHashSet::default();
+    for value in recorded.values.iter() {
+        let Some(entity) = actions.get(&(value.set.clone(), value.action.clone())) else {
+            continue;
+        };
+        let Ok((_, _, mut bool, mut f32, mut vec2)) = query.get_mut(*entity) else {
+            continue;
+        };
+        if reset.insert(*entity) {
+            if let Some(v) = bool.as_deref_mut() {
+                *v = default();
+            }
+            if let Some(v) = f32.as_deref_mut() {
+                *v = default();
+            }
+            if let Some(v) = vec2.as_deref_mut() {
+                *v = default();
+            }
+        }
+        let path = value
+            .path
+            .clone()
+            .map(|path| paths.get_or_create_path(path, &mut cmds));
+        match value.value {
+            ActionEventValue::Bool(v) => {
+                if let Some(bool) = bool.as_deref_mut() {
+                    bool.0.set_value(path, v);
+                }
+            }
+            ActionEventValue::F32(v) => {
+                if let Some(f32) = f32.as_deref_mut() {
+                    f32.0.set_value(path, v);
+                }
+            }
+            ActionEventValue::Vec2(v) => {
+                if let Some(vec2) = vec2.as_deref_mut() {
+                    vec2.0.set_value(path, v);
+                }
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn record(
+    mut recorder: ResMut<InputRecorder>,
+    query: Query<(
+        &Action,
+        Option<&BoolActionValue>,
+        Option<&F32ActionValue>,
+        Option<&Vec2ActionValue>,
+    )>,
+    set_query: Query<&ActionSet>,
+    path_query: Query<&SubactionPathStr>,
+    time: Res<Time>,
+) {
+    let RecorderState::Recording(recording) = &mut recorder.state else {
+        return;
+    };
+    let mut frame = RecordedFrame {
+        delta: time.delta(),
+        values: Vec::new(),
+    };
+    for (action, bool, f32, vec2) in &query {
+        let Ok(set) = set_query.get(action.set) else {
+            continue;
+        };
+        let mut push = |path: Option<String>, value: ActionEventValue| {
+            frame.values.push(RecordedValue {
+                set: set.name.to_string(),
+                action: action.name.to_string(),
+                path,
+                value,
+            });
+        };
+        fn entries<T: Copy + Default + Into<ActionEventValue>>(
+            map: &SubactionPathMap<T>,
+            path_query: &Query<&SubactionPathStr>,
+        ) -> impl Iterator<Item = (Option<String>, ActionEventValue)> {
+            map.entries().filter_map(|(path, value)| {
+                let path = match path {
+                    Some(path) => Some(path_query.get(path.0).ok()?.0.to_string()),
+                    None => None,
+                };
+                Some((path, (*value).into()))
+            })
+        }
+        bool.into_iter()
+            .flat_map(|v| entries(&v.0, &path_query))
+            .chain(f32.into_iter().flat_map(|v| entries(&v.0, &path_query)))
+            .chain(vec2.into_iter().flat_map(|v| entries(&v.0, &path_query)))
+            .for_each(|(path, value)| push(path, value));
+    }
+    recording.frames.push(frame);
+}
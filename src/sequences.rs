@@ -0,0 +1,386 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    time::Duration,
+};
+
+use bevy::{
+    ecs::{change_detection::Tick, system::SystemChangeTick},
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+use crate::{
+    Action, ActionSet, SchminputSystems,
+    action_state::{BoolActionState, F32ActionState, Vec2ActionState},
+    chords::{ChordInput, ChordInputs},
+    impl_helpers::{BindingValue, ProviderParam},
+    priorities::PriorityAppExt as _,
+};
+
+pub struct SequencePlugin;
+
+impl Plugin for SequencePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            sync_actions.in_set(SchminputSystems::SyncInputActions),
+        );
+        app.add_systems(
+            PreUpdate,
+            reset_disabled_sequences.in_set(SchminputSystems::ClearValues),
+        );
+        app.add_tracked_binding_id_system::<SequenceBindings, _>(
+            "schminput:sequence",
+            |entity: In<Entity>, query: Query<&SequenceBindings>| {
+                let Ok(bindings) = query.get(entity.0) else {
+                    return Vec::new();
+                };
+                bindings.0.iter().map(get_binding_id).collect()
+            },
+        );
+        app.add_binding_description_system(
+            "schminput:sequence",
+            |entity: In<Entity>, query: Query<&SequenceBindings>| {
+                let Ok(bindings) = query.get(entity.0) else {
+                    return Vec::new();
+                };
+                bindings
+                    .0
+                    .iter()
+                    .map(|binding| (get_binding_id(binding), describe(binding)))
+                    .collect()
+            },
+        );
+    }
+}
+
+/// e.g. `sequence:keyboard:KeyA>keyboard:KeyB`
+fn describe(binding: &SequenceBinding) -> String {
+    let steps = binding
+        .steps
+        .iter()
+        .map(|step| {
+            step.0
+                .iter()
+                .map(|input| match input {
+                    SequenceInput::Button(button) => button.describe(),
+                    SequenceInput::Action(action) => format!("action:{action}"),
+                })
+                .collect::<Vec<_>>()
+                .join("+")
+        })
+        .collect::<Vec<_>>();
+    format!("sequence:{}", steps.join(">"))
+}
+
+fn get_binding_id(binding: &SequenceBinding) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    binding.steps.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// sequences in disabled action sets start over once the set gets enabled again
+fn reset_disabled_sequences(
+    mut query: Query<(&Action, &mut SequenceProgress)>,
+    set_query: Query<&ActionSet>,
+) {
+    for (action, mut progress) in &mut query {
+        if !set_query.get(action.set).is_ok_and(|set| set.enabled) && !progress.0.is_empty() {
+            progress.0.clear();
+        }
+    }
+}
+
+fn sync_actions(
+    mut query: ProviderParam<&SequenceBindings, &mut SequenceProgress, ()>,
+    action_states: Query<(
+        Option<&BoolActionState>,
+        Option<&F32ActionState>,
+        Option<&Vec2ActionState>,
+    )>,
+    inputs: ChordInputs,
+    time: Res<Time>,
+    ticks: SystemChangeTick,
+) {
+    query.run_with_deltas(
+        "schminput:sequence",
+        // sequences without a hold time are only active for one frame
+        |binding| binding.hold_output.is_zero(),
+        get_binding_id,
+        // sequences don't have subaction paths
+        |_, _| false,
+        |_| false,
+        |bindings, visit| bindings.0.iter().for_each(visit),
+        |binding, progress, _, data, out| {
+            let blocked = |input: ChordInput| {
+                data.blocked_inputs
+                    .is_some_and(|b| b.is_blocked(input.label_id(), input.binding_id()))
+                    || data.consumed_inputs.is_consumed(
+                        input.label_id(),
+                        input.binding_id(),
+                        data.priority,
+                        data.owner,
+                    )
+            };
+            let pressed = |input: &SequenceInput| match *input {
+                SequenceInput::Button(button) => {
+                    !blocked(button) && inputs.any_pressed(button, data.devices)
+                }
+                SequenceInput::Action(action) => action_states
+                    .get(action)
+                    .is_ok_and(|(b, f, v)| any_state(b, f, v, |active, _| active)),
+            };
+            let just_pressed = |input: &SequenceInput| match *input {
+                SequenceInput::Button(button) => {
+                    !blocked(button) && inputs.any_just_pressed(button, data.devices)
+                }
+                SequenceInput::Action(action) => action_states
+                    .get(action)
+                    .is_ok_and(|(b, f, v)| any_state(b, f, v, |_, started| started)),
+            };
+            // any key or gamepad button, or an input of the sequence, that isn't in the step,
+            // steps waiting for an action can't tell which buttons trigger it
+            let wrong_input = |step: &SequenceStep| {
+                let waits_for_action = step
+                    .0
+                    .iter()
+                    .any(|input| matches!(input, SequenceInput::Action(_)));
+                let unrelated_button = !waits_for_action
+                    && inputs
+                        .just_pressed_keys_and_buttons(data.devices)
+                        .filter(|input| !blocked(*input))
+                        .map(SequenceInput::Button)
+                        .any(|input| !step.0.contains(&input));
+                let other_step_input = binding
+                    .steps
+                    .iter()
+                    .flat_map(|step| step.0.iter())
+                    .filter(|input| !step.0.contains(input))
+                    .any(&just_pressed);
+                unrelated_button || other_step_input
+            };
+            let active = progress
+                .0
+                .entry(get_binding_id(binding))
+                .or_default()
+                .update(
+                    binding,
+                    pressed,
+                    just_pressed,
+                    wrong_input,
+                    time.elapsed(),
+                    ticks.this_run(),
+                );
+            out.push(BindingValue {
+                bool: data.is_bool.then_some(active),
+                f32: data.is_f32.then_some(active as u8 as f32),
+                vec2: None,
+            });
+        },
+    );
+}
+
+/// calls `f` with the active and started state of the action, for whichever value type it has
+fn any_state(
+    bool: Option<&BoolActionState>,
+    f32: Option<&F32ActionState>,
+    vec2: Option<&Vec2ActionState>,
+    f: impl Fn(bool, bool) -> bool,
+) -> bool {
+    bool.is_some_and(|s| f(s.any.active(), s.any.started()))
+        || f32.is_some_and(|s| f(s.any.active(), s.any.started()))
+        || vec2.is_some_and(|s| f(s.any.active(), s.any.started()))
+}
+
+/// The progress of every [`SequenceBinding`] of an action.
+#[derive(Clone, Debug, Default, Component)]
+pub struct SequenceProgress(HashMap<u64, SequenceState>);
+
+#[derive(Clone, Copy, Debug, Default)]
+struct SequenceState {
+    last_update: Option<Tick>,
+    output: bool,
+    step: usize,
+    started_at: Duration,
+    last_step_at: Duration,
+    completed_at: Option<Duration>,
+}
+
+impl SequenceState {
+    fn update(
+        &mut self,
+        binding: &SequenceBinding,
+        pressed: impl Fn(&SequenceInput) -> bool,
+        just_pressed: impl Fn(&SequenceInput) -> bool,
+        wrong_input: impl Fn(&SequenceStep) -> bool,
+        now: Duration,
+        tick: Tick,
+    ) -> bool {
+        if self.last_update == Some(tick) {
+            return self.output;
+        }
+        self.last_update = Some(tick);
+        if binding.steps.is_empty() {
+            return false;
+        }
+
+        let timed_out = now - self.last_step_at > binding.step_timeout
+            || binding
+                .timeout
+                .is_some_and(|timeout| now - self.started_at > timeout);
+        if self.step > 0 && timed_out {
+            self.step = 0;
+        }
+        // all inputs of the step are held and at least one of them got pressed this frame
+        let step_done =
+            |step: &SequenceStep| step.0.iter().all(&pressed) && step.0.iter().any(&just_pressed);
+        if step_done(&binding.steps[self.step]) {
+            self.advance(binding, now);
+        } else if binding.reset_on_wrong_input && wrong_input(&binding.steps[self.step]) {
+            self.step = 0;
+            // the wrong input might be the start of a new attempt
+            if step_done(&binding.steps[0]) {
+                self.advance(binding, now);
+            }
+        }
+
+        self.output = self
+            .completed_at
+            .is_some_and(|completed_at| now - completed_at <= binding.hold_output);
+        self.output
+    }
+
+    fn advance(&mut self, binding: &SequenceBinding, now: Duration) {
+        if self.step == 0 {
+            self.started_at = now;
+        }
+        self.step += 1;
+        self.last_step_at = now;
+        if self.step == binding.steps.len() {
+            self.step = 0;
+            self.completed_at = Some(now);
+        }
+    }
+}
+
+/// An input that a [`SequenceStep`] listens to.
+#[derive(Clone, Copy, Debug, Reflect, PartialEq, Eq, Hash)]
+pub enum SequenceInput {
+    Button(ChordInput),
+    /// Another action, it counts as pressed while it is active and as just pressed when it
+    /// started. Actions are seen one frame late since their state updates after sequences.
+    Action(Entity),
+}
+
+impl From<ChordInput> for SequenceInput {
+    fn from(value: ChordInput) -> Self {
+        SequenceInput::Button(value)
+    }
+}
+impl From<KeyCode> for SequenceInput {
+    fn from(value: KeyCode) -> Self {
+        SequenceInput::Button(value.into())
+    }
+}
+impl From<MouseButton> for SequenceInput {
+    fn from(value: MouseButton) -> Self {
+        SequenceInput::Button(value.into())
+    }
+}
+impl From<GamepadButton> for SequenceInput {
+    fn from(value: GamepadButton) -> Self {
+        SequenceInput::Button(value.into())
+    }
+}
+
+/// All inputs of a step have to be held, with at least one of them pressed on that frame.
+#[derive(Clone, Debug, Reflect, PartialEq, Eq, Hash)]
+pub struct SequenceStep(pub Vec<SequenceInput>);
+
+#[derive(Clone, Debug, Default, Component, Reflect)]
+#[require(SequenceProgress)]
+pub struct SequenceBindings(pub Vec<SequenceBinding>);
+
+impl SequenceBindings {
+    pub fn bind(mut self, binding: SequenceBinding) -> Self {
+        self.0.push(binding);
+        self
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Fires once all steps happened in order, only supports bool and f32 actions.
+#[derive(Clone, Debug, Reflect, PartialEq)]
+pub struct SequenceBinding {
+    pub steps: Vec<SequenceStep>,
+    /// The maximum time between two steps.
+    pub step_timeout: Duration,
+    /// The maximum time between the first and the last step.
+    pub timeout: Option<Duration>,
+    /// Start over when a key or gamepad button that isn't part of the current step is pressed,
+    /// or an input of another step, otherwise only the timeouts reset the sequence.
+    /// Steps with an action only start over on the inputs of other steps.
+    pub reset_on_wrong_input: bool,
+    /// How long the action stays active after the sequence completed,
+    /// zero means only on the frame it completed.
+    pub hold_output: Duration,
+}
+
+impl Default for SequenceBinding {
+    fn default() -> Self {
+        Self {
+            steps: Vec::new(),
+            step_timeout: Duration::from_millis(500),
+            timeout: None,
+            reset_on_wrong_input: false,
+            hold_output: Duration::ZERO,
+        }
+    }
+}
+
+impl SequenceBinding {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn then(mut self, input: impl Into<SequenceInput>) -> Self {
+        self.steps.push(SequenceStep(vec![input.into()]));
+        self
+    }
+
+    /// Adds a step where all inputs have to be held at the same time.
+    pub fn then_all(mut self, inputs: impl IntoIterator<Item = impl Into<SequenceInput>>) -> Self {
+        self.steps
+            .push(SequenceStep(inputs.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    pub fn then_action(mut self, action: Entity) -> Self {
+        self.steps
+            .push(SequenceStep(vec![SequenceInput::Action(action)]));
+        self
+    }
+
+    pub fn step_timeout(mut self, timeout: Duration) -> Self {
+        self.step_timeout = timeout;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn reset_on_wrong_input(mut self) -> Self {
+        self.reset_on_wrong_input = true;
+        self
+    }
+
+    pub fn hold_output(mut self, duration: Duration) -> Self {
+        self.hold_output = duration;
+        self
+    }
+}
@@ -0,0 +1,373 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    Action, ActionSet, BoolActionValue, F32ActionValue, SchminputSystems, Vec2ActionValue,
+    subaction_paths::{RequestedSubactionPaths, SubactionPathStr},
+};
+
+/// Applies [`ApplyActionSnapshot`] messages.
+pub struct SnapshotPlugin;
+
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<ApplyActionSnapshot>();
+        app.add_systems(
+            PreUpdate,
+            apply_snapshots
+                .after(SchminputSystems::SyncInputActions)
+                .before(SchminputSystems::AccumulateFixedValues),
+        );
+    }
+}
+
+/// Replaces the values of the action sets in the snapshot for this frame,
+/// applied after the providers ran.
+#[derive(Clone, Debug, Message)]
+pub struct ApplyActionSnapshot(pub ActionSnapshot);
+
+fn apply_snapshots(mut reader: MessageReader<ApplyActionSnapshot>, mut snapshots: ActionSnapshots) {
+    for ApplyActionSnapshot(snapshot) in reader.read() {
+        snapshots.apply(snapshot);
+    }
+}
+
+/// Float values are rounded to multiples of `1 / QUANTIZATION_STEPS`.
+pub const QUANTIZATION_STEPS: f32 = 1024.0;
+
+fn quantize(value: f32) -> i32 {
+    (value * QUANTIZATION_STEPS).round() as i32
+}
+
+fn dequantize(value: i32) -> f32 {
+    value as f32 / QUANTIZATION_STEPS
+}
+
+/// 64 bit FNV-1a, unlike the std hasher this is the same on every machine and build.
+fn stable_hash(parts: &[&str]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for part in parts {
+        // the separator keeps ("ab", "c") and ("a", "bc") apart
+        for byte in part.bytes().chain([0xff]) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// The stable id of an [`ActionSet`], the hash of its name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ActionSetId(pub u64);
+
+impl ActionSetId {
+    pub fn new(set_name: &str) -> Self {
+        Self(stable_hash(&[set_name]))
+    }
+}
+
+/// The stable id of an [`Action`], the hash of its name and the name of its set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ActionId(pub u64);
+
+impl ActionId {
+    pub fn new(set_name: &str, action_name: &str) -> Self {
+        Self(stable_hash(&[set_name, action_name]))
+    }
+}
+
+/// The stable id of a [`SubactionPath`], the hash of its path string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SubactionPathId(pub u64);
+
+impl SubactionPathId {
+    pub fn new(path: &str) -> Self {
+        Self(stable_hash(&[path]))
+    }
+}
+
+/// A quantized action value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SnapshotValue {
+    Bool(bool),
+    F32(i32),
+    Vec2(i32, i32),
+}
+
+impl SnapshotValue {
+    pub fn f32(value: f32) -> Self {
+        SnapshotValue::F32(quantize(value))
+    }
+    pub fn vec2(value: Vec2) -> Self {
+        SnapshotValue::Vec2(quantize(value.x), quantize(value.y))
+    }
+    pub fn as_f32(&self) -> Option<f32> {
+        match *self {
+            SnapshotValue::F32(v) => Some(dequantize(v)),
+            _ => None,
+        }
+    }
+    pub fn as_vec2(&self) -> Option<Vec2> {
+        match *self {
+            SnapshotValue::Vec2(x, y) => Some(Vec2::new(dequantize(x), dequantize(y))),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SnapshotEntry {
+    pub action: ActionId,
+    /// [`None`] for the value of all paths combined
+    pub path: Option<SubactionPathId>,
+    pub value: SnapshotValue,
+}
+
+/// The values of some action sets on one frame, captured and applied through [`ActionSnapshots`].
+///
+/// Only values that aren't the default are stored, sorted by their ids so the same input
+/// gives the same bytes on every machine.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ActionSnapshot {
+    /// Actions in these sets that have no entry are reset to the default value.
+    pub sets: Vec<ActionSetId>,
+    pub entries: Vec<SnapshotEntry>,
+}
+
+impl ActionSnapshot {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_varint(&mut out, self.sets.len() as u64);
+        for set in self.sets.iter() {
+            out.extend_from_slice(&set.0.to_le_bytes());
+        }
+        write_varint(&mut out, self.entries.len() as u64);
+        for entry in self.entries.iter() {
+            let ty = match entry.value {
+                SnapshotValue::Bool(false) => 0,
+                SnapshotValue::Bool(true) => 1,
+                SnapshotValue::F32(_) => 2,
+                SnapshotValue::Vec2(..) => 3,
+            };
+            out.push(ty | (entry.path.is_some() as u8) << 2);
+            out.extend_from_slice(&entry.action.0.to_le_bytes());
+            if let Some(path) = entry.path {
+                out.extend_from_slice(&path.0.to_le_bytes());
+            }
+            match entry.value {
+                SnapshotValue::Bool(_) => {}
+                SnapshotValue::F32(v) => write_signed_varint(&mut out, v),
+                SnapshotValue::Vec2(x, y) => {
+                    write_signed_varint(&mut out, x);
+                    write_signed_varint(&mut out, y);
+                }
+            }
+        }
+        out
+    }
+
+    /// Returns [`None`] if the bytes are not a valid snapshot.
+    pub fn from_bytes(mut bytes: &[u8]) -> Option<Self> {
+        let bytes = &mut bytes;
+        let mut snapshot = ActionSnapshot::default();
+        for _ in 0..read_varint(bytes)? {
+            snapshot.sets.push(ActionSetId(read_u64(bytes)?));
+        }
+        for _ in 0..read_varint(bytes)? {
+            let (&tag, rest) = bytes.split_first()?;
+            *bytes = rest;
+            let action = ActionId(read_u64(bytes)?);
+            let path = match tag >> 2 {
+                0 => None,
+                1 => Some(SubactionPathId(read_u64(bytes)?)),
+                _ => return None,
+            };
+            let value = match tag & 0b11 {
+                0 => SnapshotValue::Bool(false),
+                1 => SnapshotValue::Bool(true),
+                2 => SnapshotValue::F32(read_signed_varint(bytes)?),
+                _ => SnapshotValue::Vec2(read_signed_varint(bytes)?, read_signed_varint(bytes)?),
+            };
+            snapshot.entries.push(SnapshotEntry {
+                action,
+                path,
+                value,
+            });
+        }
+        bytes.is_empty().then_some(snapshot)
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_signed_varint(out: &mut Vec<u8>, value: i32) {
+    write_varint(out, ((value << 1) ^ (value >> 31)) as u32 as u64);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first()?;
+        *bytes = rest;
+        // the tenth byte only has room for the highest bit
+        if shift == 63 && byte & 0x7f > 1 {
+            return None;
+        }
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn read_signed_varint(bytes: &mut &[u8]) -> Option<i32> {
+    let value = u32::try_from(read_varint(bytes)?).ok()?;
+    Some((value >> 1) as i32 ^ -((value & 1) as i32))
+}
+
+fn read_u64(bytes: &mut &[u8]) -> Option<u64> {
+    let (value, rest) = bytes.split_first_chunk()?;
+    *bytes = rest;
+    Some(u64::from_le_bytes(*value))
+}
+
+/// Captures and applies [`ActionSnapshot`]s.
+#[derive(SystemParam)]
+pub struct ActionSnapshots<'w, 's> {
+    actions: Query<
+        'w,
+        's,
+        (
+            &'static Action,
+            &'static RequestedSubactionPaths,
+            Option<&'static mut BoolActionValue>,
+            Option<&'static mut F32ActionValue>,
+            Option<&'static mut Vec2ActionValue>,
+        ),
+    >,
+    sets: Query<'w, 's, &'static ActionSet>,
+    paths: Query<'w, 's, &'static SubactionPathStr>,
+}
+
+impl ActionSnapshots<'_, '_> {
+    /// Captures the current values of the actions in `sets`,
+    /// run this after [`SchminputSystems::SyncInputActions`].
+    ///
+    /// Float values are only quantized in the snapshot, the local actions keep their exact
+    /// values. To see the same values as the peers receiving the snapshot, apply it locally
+    /// with [`apply`](Self::apply) or an [`ApplyActionSnapshot`] message as well.
+    pub fn capture(&self, sets: &[Entity]) -> ActionSnapshot {
+        let mut snapshot = ActionSnapshot {
+            sets: sets
+                .iter()
+                .filter_map(|set| Some(ActionSetId::new(&self.sets.get(*set).ok()?.name)))
+                .collect(),
+            entries: Vec::new(),
+        };
+        for (action, _, bool, f32, vec2) in self.actions.iter() {
+            if !sets.contains(&action.set) {
+                continue;
+            }
+            let Ok(set) = self.sets.get(action.set) else {
+                continue;
+            };
+            let id = ActionId::new(&set.name, &action.name);
+            let values = bool
+                .iter()
+                .flat_map(|v| v.entries().map(|(p, v)| (p, SnapshotValue::Bool(*v))))
+                .chain(
+                    f32.iter()
+                        .flat_map(|v| v.entries().map(|(p, v)| (p, SnapshotValue::f32(*v)))),
+                )
+                .chain(
+                    vec2.iter()
+                        .flat_map(|v| v.entries().map(|(p, v)| (p, SnapshotValue::vec2(*v)))),
+                );
+            for (path, value) in values.filter(|(_, value)| !is_default(*value)) {
+                let path = match path {
+                    Some(path) => match self.paths.get(path.0) {
+                        Ok(str) => Some(SubactionPathId::new(&str.0)),
+                        Err(_) => continue,
+                    },
+                    None => None,
+                };
+                snapshot.entries.push(SnapshotEntry {
+                    action: id,
+                    path,
+                    value,
+                });
+            }
+        }
+        snapshot.sets.sort();
+        snapshot.entries.sort();
+        snapshot
+    }
+
+    /// Replaces the values of the actions in the sets of the snapshot, entries of actions
+    /// that don't exist are ignored. Run this after [`SchminputSystems::SyncInputActions`].
+    pub fn apply(&mut self, snapshot: &ActionSnapshot) {
+        for (action, requested_paths, mut bool, mut f32, mut vec2) in self.actions.iter_mut() {
+            let Ok(set) = self.sets.get(action.set) else {
+                continue;
+            };
+            if !snapshot.sets.contains(&ActionSetId::new(&set.name)) {
+                continue;
+            }
+            let id = ActionId::new(&set.name, &action.name);
+            if let Some(v) = bool.as_deref_mut() {
+                *v = default();
+            }
+            if let Some(v) = f32.as_deref_mut() {
+                *v = default();
+            }
+            if let Some(v) = vec2.as_deref_mut() {
+                *v = default();
+            }
+            for entry in snapshot.entries.iter().filter(|e| e.action == id) {
+                let path = match entry.path {
+                    Some(path_id) => {
+                        let Some(path) = requested_paths.iter().find(|path| {
+                            self.paths
+                                .get(path.0)
+                                .is_ok_and(|str| SubactionPathId::new(&str.0) == path_id)
+                        }) else {
+                            continue;
+                        };
+                        Some(*path)
+                    }
+                    None => None,
+                };
+                match entry.value {
+                    SnapshotValue::Bool(v) => {
+                        if let Some(bool) = bool.as_deref_mut() {
+                            bool.0.set_value(path, v);
+                        }
+                    }
+                    SnapshotValue::F32(_) => {
+                        if let Some(f32) = f32.as_deref_mut() {
+                            f32.0.set_value(path, entry.value.as_f32().unwrap());
+                        }
+                    }
+                    SnapshotValue::Vec2(..) => {
+                        if let Some(vec2) = vec2.as_deref_mut() {
+                            vec2.0.set_value(path, entry.value.as_vec2().unwrap());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn is_default(value: SnapshotValue) -> bool {
+    matches!(
+        value,
+        SnapshotValue::Bool(false) | SnapshotValue::F32(0) | SnapshotValue::Vec2(0, 0)
+    )
+}
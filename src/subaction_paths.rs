@@ -149,6 +149,19 @@ impl<T: Default> SubactionPathMap<T> {
     pub fn set_value_for_path(&mut self, path: SubactionPath, value: T) {
         self.paths.entry(path).insert(value);
     }
+    /// The value for all paths with [`None`] as its path, followed by the values of the paths.
+    pub(crate) fn entries(&self) -> impl Iterator<Item = (Option<SubactionPath>, &T)> {
+        [(None, &self.any)]
+            .into_iter()
+            .chain(self.paths.iter().map(|(path, value)| (Some(*path), value)))
+    }
+    /// Sets the value of the path, or the value for all paths for [`None`].
+    pub(crate) fn set_value(&mut self, path: Option<SubactionPath>, value: T) {
+        match path {
+            Some(path) => self.set_value_for_path(path, value),
+            None => self.any = value,
+        }
+    }
     pub fn new() -> SubactionPathMap<T> {
         default()
     }
@@ -0,0 +1,183 @@
+//! Virtual input devices for driving an app without a window, e.g. in integration tests.
+//! Requires the `testing` feature.
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use schminput::{prelude::*, testing::*};
+//!
+//! let mut app = headless_app();
+//! let set = app.world_mut().spawn(ActionSet::new("core", "Core", 0)).id();
+//! let jump = app
+//!     .world_mut()
+//!     .spawn((
+//!         Action::new("jump", "Jump", set),
+//!         KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::Space)),
+//!         BoolActionValue::new(),
+//!     ))
+//!     .id();
+//! app.press_key(KeyCode::Space).step();
+//! assert!(app.action_value::<BoolActionValue>(jump).any);
+//! ```
+
+use std::time::Duration;
+
+use atomicow::CowArc;
+use bevy::{
+    input::{
+        ButtonState, InputPlugin,
+        gamepad::{RawGamepadAxisChangedEvent, RawGamepadButtonChangedEvent, RawGamepadEvent},
+        keyboard::{Key, KeyboardInput, NativeKey},
+        mouse::{MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel},
+    },
+    prelude::*,
+    time::TimeUpdateStrategy,
+};
+
+use crate::{DefaultSchminputPlugins, gamepad::GamepadIdentifier};
+
+/// The time every frame of a [`headless_app`] takes.
+pub const HEADLESS_FRAME_TIME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// An app with [`MinimalPlugins`], the [`InputPlugin`] and the [`DefaultSchminputPlugins`],
+/// where every frame takes [`HEADLESS_FRAME_TIME`].
+pub fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, InputPlugin, DefaultSchminputPlugins));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(HEADLESS_FRAME_TIME));
+    app
+}
+
+/// Injects input into an [`App`], the input is seen by the actions on the next
+/// [`VirtualInputAppExt::step`].
+pub trait VirtualInputAppExt {
+    fn press_key(&mut self, key: KeyCode) -> &mut Self;
+    fn release_key(&mut self, key: KeyCode) -> &mut Self;
+    fn press_mouse_button(&mut self, button: MouseButton) -> &mut Self;
+    fn release_mouse_button(&mut self, button: MouseButton) -> &mut Self;
+    fn move_mouse(&mut self, delta: Vec2) -> &mut Self;
+    /// Scrolls by `lines`, +Y is scrolling up.
+    fn scroll_mouse(&mut self, lines: Vec2) -> &mut Self;
+    /// Spawns a connected gamepad, the identifier is used by gamepad subaction paths,
+    /// e.g. `"1"` for `"/gamepad/1"`.
+    fn spawn_virtual_gamepad(&mut self, identifier: impl Into<CowArc<'static, str>>) -> Entity;
+    /// Sets the analog value of a button, the digital state follows the [`GamepadSettings`].
+    fn set_gamepad_button(
+        &mut self,
+        gamepad: Entity,
+        button: GamepadButton,
+        value: f32,
+    ) -> &mut Self;
+    fn press_gamepad_button(&mut self, gamepad: Entity, button: GamepadButton) -> &mut Self {
+        self.set_gamepad_button(gamepad, button, 1.0)
+    }
+    fn release_gamepad_button(&mut self, gamepad: Entity, button: GamepadButton) -> &mut Self {
+        self.set_gamepad_button(gamepad, button, 0.0)
+    }
+    fn set_gamepad_axis(&mut self, gamepad: Entity, axis: GamepadAxis, value: f32) -> &mut Self;
+    /// Runs one frame.
+    fn step(&mut self) -> &mut Self;
+    fn step_frames(&mut self, frames: usize) -> &mut Self {
+        for _ in 0..frames {
+            self.step();
+        }
+        self
+    }
+    /// The value component of an action, panics if the action doesn't have one.
+    fn action_value<V: Component>(&self, action: Entity) -> &V;
+}
+
+impl VirtualInputAppExt for App {
+    fn press_key(&mut self, key: KeyCode) -> &mut Self {
+        write_key(self, key, ButtonState::Pressed)
+    }
+
+    fn release_key(&mut self, key: KeyCode) -> &mut Self {
+        write_key(self, key, ButtonState::Released)
+    }
+
+    fn press_mouse_button(&mut self, button: MouseButton) -> &mut Self {
+        write_mouse_button(self, button, ButtonState::Pressed)
+    }
+
+    fn release_mouse_button(&mut self, button: MouseButton) -> &mut Self {
+        write_mouse_button(self, button, ButtonState::Released)
+    }
+
+    fn move_mouse(&mut self, delta: Vec2) -> &mut Self {
+        self.world_mut().write_message(MouseMotion { delta });
+        self
+    }
+
+    fn scroll_mouse(&mut self, lines: Vec2) -> &mut Self {
+        self.world_mut().write_message(MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x: lines.x,
+            y: lines.y,
+            window: Entity::PLACEHOLDER,
+        });
+        self
+    }
+
+    fn spawn_virtual_gamepad(&mut self, identifier: impl Into<CowArc<'static, str>>) -> Entity {
+        self.world_mut()
+            .spawn((
+                Name::new("Virtual Gamepad"),
+                Gamepad::default(),
+                GamepadIdentifier(identifier.into()),
+            ))
+            .id()
+    }
+
+    fn set_gamepad_button(
+        &mut self,
+        gamepad: Entity,
+        button: GamepadButton,
+        value: f32,
+    ) -> &mut Self {
+        self.world_mut()
+            .write_message(RawGamepadEvent::Button(RawGamepadButtonChangedEvent::new(
+                gamepad, button, value,
+            )));
+        self
+    }
+
+    fn set_gamepad_axis(&mut self, gamepad: Entity, axis: GamepadAxis, value: f32) -> &mut Self {
+        self.world_mut()
+            .write_message(RawGamepadEvent::Axis(RawGamepadAxisChangedEvent::new(
+                gamepad, axis, value,
+            )));
+        self
+    }
+
+    fn step(&mut self) -> &mut Self {
+        self.update();
+        self
+    }
+
+    fn action_value<V: Component>(&self, action: Entity) -> &V {
+        self.world()
+            .get::<V>(action)
+            .unwrap_or_else(|| panic!("action {action} has no {}", std::any::type_name::<V>()))
+    }
+}
+
+fn write_key(app: &mut App, key: KeyCode, state: ButtonState) -> &mut App {
+    app.world_mut().write_message(KeyboardInput {
+        key_code: key,
+        logical_key: Key::Unidentified(NativeKey::Unidentified),
+        state,
+        text: None,
+        repeat: false,
+        window: Entity::PLACEHOLDER,
+    });
+    app
+}
+
+fn write_mouse_button(app: &mut App, button: MouseButton, state: ButtonState) -> &mut App {
+    app.world_mut().write_message(MouseButtonInput {
+        button,
+        state,
+        window: Entity::PLACEHOLDER,
+    });
+    app
+}
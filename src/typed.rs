@@ -0,0 +1,230 @@
+use std::marker::PhantomData;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    BoolActionValue, F32ActionValue, Vec2ActionValue,
+    action_state::{ActionState, ActionStateData, ActionValueType},
+    subaction_paths::{SubactionPath, SubactionPathMap},
+};
+
+/// An action entity that is known to have the value component of `T`,
+/// e.g. a field of a struct deriving [`ActionSet`](crate::ActionSet).
+pub struct TypedAction<T: ActionKind> {
+    entity: Entity,
+    _marker: PhantomData<fn() -> T>,
+}
+
+pub type BoolAction = TypedAction<bool>;
+pub type F32Action = TypedAction<f32>;
+pub type Vec2Action = TypedAction<Vec2>;
+#[cfg(feature = "xr")]
+pub type SpaceAction = TypedAction<Space>;
+
+impl<T: ActionKind> TypedAction<T> {
+    pub fn new(entity: Entity) -> Self {
+        Self {
+            entity,
+            _marker: PhantomData,
+        }
+    }
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+    /// The value component to spawn the action with.
+    #[doc(hidden)]
+    pub fn value() -> T::Value {
+        T::Value::default()
+    }
+}
+
+impl<T: ActionKind> From<TypedAction<T>> for Entity {
+    fn from(action: TypedAction<T>) -> Self {
+        action.entity
+    }
+}
+
+impl<T: ActionKind> Clone for TypedAction<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T: ActionKind> Copy for TypedAction<T> {}
+impl<T: ActionKind> PartialEq for TypedAction<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entity == other.entity
+    }
+}
+impl<T: ActionKind> Eq for TypedAction<T> {}
+impl<T: ActionKind> std::fmt::Debug for TypedAction<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TypedAction").field(&self.entity).finish()
+    }
+}
+
+/// Names what reading a [`TypedAction`] returns, used by `#[derive(ActionSet)]`.
+pub trait ActionHandle {
+    type Ref<'a>;
+}
+
+impl<T: ActionKind> ActionHandle for TypedAction<T> {
+    type Ref<'a> = T::Ref<'a>;
+}
+
+/// The value types an action can be declared with.
+pub trait ActionKind: Send + Sync + 'static {
+    /// The value component of actions of this kind.
+    type Value: Component + Default;
+    /// What [`TypedActions::get`] returns for this kind.
+    type Ref<'a>;
+    fn get<'a>(actions: &'a TypedActions, action: Entity) -> Self::Ref<'a>;
+}
+
+impl ActionKind for bool {
+    type Value = BoolActionValue;
+    type Ref<'a> = ActionRef<'a, bool>;
+    fn get<'a>(actions: &'a TypedActions, action: Entity) -> Self::Ref<'a> {
+        ActionRef::from_query(&actions.bool, action)
+    }
+}
+impl ActionKind for f32 {
+    type Value = F32ActionValue;
+    type Ref<'a> = ActionRef<'a, f32>;
+    fn get<'a>(actions: &'a TypedActions, action: Entity) -> Self::Ref<'a> {
+        ActionRef::from_query(&actions.f32, action)
+    }
+}
+impl ActionKind for Vec2 {
+    type Value = Vec2ActionValue;
+    type Ref<'a> = ActionRef<'a, Vec2>;
+    fn get<'a>(actions: &'a TypedActions, action: Entity) -> Self::Ref<'a> {
+        ActionRef::from_query(&actions.vec2, action)
+    }
+}
+
+/// The kind of actions with a [`SpaceActionValue`](crate::xr::SpaceActionValue).
+#[cfg(feature = "xr")]
+pub struct Space;
+
+#[cfg(feature = "xr")]
+impl ActionKind for Space {
+    type Value = crate::xr::SpaceActionValue;
+    type Ref<'a> = Option<&'a crate::xr::SpaceActionValue>;
+    fn get<'a>(actions: &'a TypedActions, action: Entity) -> Self::Ref<'a> {
+        actions.space.get(action).ok()
+    }
+}
+
+/// Read access to the values and states of [`TypedAction`]s.
+#[derive(SystemParam)]
+pub struct TypedActions<'w, 's> {
+    bool: Query<'w, 's, (&'static BoolActionValue, &'static ActionState<bool>)>,
+    f32: Query<'w, 's, (&'static F32ActionValue, &'static ActionState<f32>)>,
+    vec2: Query<'w, 's, (&'static Vec2ActionValue, &'static ActionState<Vec2>)>,
+    #[cfg(feature = "xr")]
+    space: Query<'w, 's, &'static crate::xr::SpaceActionValue>,
+}
+
+impl TypedActions<'_, '_> {
+    pub fn get<T: ActionKind>(&self, action: TypedAction<T>) -> T::Ref<'_> {
+        T::get(self, action.entity)
+    }
+}
+
+/// The value and state of an action, despawned actions read as the default value.
+pub struct ActionRef<'a, T: ActionValueType> {
+    value: Option<&'a SubactionPathMap<T>>,
+    state: Option<&'a ActionState<T>>,
+    path: Option<SubactionPath>,
+}
+
+impl<'a, T: ActionValueType> ActionRef<'a, T> {
+    pub(crate) fn new(value: &'a SubactionPathMap<T>, state: &'a ActionState<T>) -> Self {
+        Self {
+            value: Some(value),
+            state: Some(state),
+            path: None,
+        }
+    }
+    pub(crate) fn missing() -> Self {
+        Self {
+            value: None,
+            state: None,
+            path: None,
+        }
+    }
+    pub(crate) fn from_query<V: Component + std::ops::Deref<Target = SubactionPathMap<T>>>(
+        query: &'a Query<(&V, &ActionState<T>)>,
+        action: Entity,
+    ) -> Self {
+        match query.get(action) {
+            Ok((value, state)) => Self::new(value, state),
+            Err(_) => Self::missing(),
+        }
+    }
+    /// Makes [`value`](Self::value) and [`state`](Self::state) read the value of a
+    /// requested subaction path instead of the one for all paths.
+    pub fn with_path(mut self, path: SubactionPath) -> Self {
+        self.path = Some(path);
+        self
+    }
+    /// If the action exists and has a value of this type.
+    pub fn exists(&self) -> bool {
+        self.value.is_some()
+    }
+    /// The value for all subaction paths, or the path set with [`with_path`](Self::with_path).
+    pub fn value(&self) -> T {
+        match self.path {
+            Some(path) => self.path_value(path),
+            None => self.value.map(|v| v.any).unwrap_or_default(),
+        }
+    }
+    /// The value for a requested subaction path.
+    pub fn path_value(&self, path: SubactionPath) -> T {
+        self.value
+            .and_then(|v| v.get_with_path(&path).copied())
+            .unwrap_or_default()
+    }
+    pub fn state(&self) -> ActionStateData<T> {
+        match self.path {
+            Some(path) => self.path_state(path),
+            None => self.state.map(|s| s.any).unwrap_or_default(),
+        }
+    }
+    pub fn path_state(&self, path: SubactionPath) -> ActionStateData<T> {
+        self.state
+            .and_then(|s| s.get_with_path(&path).copied())
+            .unwrap_or_default()
+    }
+    /// The action is active, true or non zero.
+    pub fn pressed(&self) -> bool {
+        self.state().active()
+    }
+    /// The action became active this frame.
+    pub fn just_pressed(&self) -> bool {
+        self.state().started()
+    }
+    /// The action stopped being active this frame.
+    pub fn just_released(&self) -> bool {
+        self.state().completed()
+    }
+}
+
+/// Implemented by `#[derive(ActionSet)]`, spawns the set and its actions.
+pub trait ActionSetDeclaration: Sized {
+    fn spawn(cmds: &mut Commands) -> Self;
+}
+
+pub trait TypedActionsAppExt {
+    /// Spawns the actions in [`Startup`] and inserts the declaration as a resource.
+    fn add_action_set<S: ActionSetDeclaration + Resource>(&mut self) -> &mut Self;
+}
+
+impl TypedActionsAppExt for App {
+    fn add_action_set<S: ActionSetDeclaration + Resource>(&mut self) -> &mut Self {
+        self.add_systems(Startup, |mut cmds: Commands| {
+            let actions = S::spawn(&mut cmds);
+            cmds.insert_resource(actions);
+        })
+    }
+}
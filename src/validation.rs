@@ -0,0 +1,355 @@
+use std::fmt::Display;
+
+use bevy::{ecs::entity::EntityHashMap, platform::collections::HashMap, prelude::*};
+
+use crate::{
+    Action, ActionSet, ActionsInSet,
+    players::OwnedByPlayer,
+    priorities::{BindingIdSystems, BlockingMode, priority_groups},
+};
+
+/// Validates the bindings after [`Startup`], the result is logged and stored in the
+/// [`BindingReport`].
+pub struct ValidationPlugin;
+
+impl Plugin for ValidationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BindingReport>();
+        app.add_systems(PostStartup, validate_bindings_on_startup);
+    }
+}
+
+#[derive(Resource, Clone, Debug, Default)]
+pub struct BindingReport {
+    pub issues: Vec<BindingIssue>,
+}
+
+impl BindingReport {
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BindingIssue {
+    pub kind: BindingIssueKind,
+    /// Names the actions and the input, used when logging the issue.
+    pub description: String,
+}
+
+impl Display for BindingIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.description)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BindingIssueKind {
+    /// Multiple actions in the same set use the same input.
+    SameSet {
+        set: Entity,
+        label_id: u64,
+        binding_id: u64,
+        actions: Vec<Entity>,
+    },
+    /// Actions in different sets with the same priority use the same input,
+    /// none of them blocks the others.
+    EqualPriority {
+        label_id: u64,
+        binding_id: u64,
+        actions: Vec<Entity>,
+    },
+    /// The binding never gets input while the actions of the higher priority sets use it.
+    Shadowed {
+        action: Entity,
+        label_id: u64,
+        binding_id: u64,
+        by: Vec<Entity>,
+    },
+    /// The OpenXR binding doesn't fit the value component of the action, or the action
+    /// doesn't have exactly one value component.
+    OxrValueMismatch {
+        action: Entity,
+        profile: String,
+        binding: String,
+    },
+}
+
+fn validate_bindings_on_startup(world: &mut World) {
+    let report = validate_bindings(world);
+    for issue in report.issues.iter() {
+        warn!("{issue}");
+    }
+    world.insert_resource(report);
+}
+
+/// Checks the bindings of all actions using the ids from the systems registered through
+/// [`PriorityAppExt::add_binding_id_system`](crate::priorities::PriorityAppExt::add_binding_id_system).
+///
+/// Conflicts within a set are checked for every set, conflicts between sets only for enabled
+/// sets, the same ones that block each other.
+pub fn validate_bindings(world: &mut World) -> BindingReport {
+    let Some(binding_id_systems) = world.remove_resource::<BindingIdSystems>() else {
+        return BindingReport::default();
+    };
+    let sets = world
+        .query::<(Entity, &ActionsInSet)>()
+        .iter(world)
+        .map(|(e, a)| (e, a.iter().collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+    let mut ids = EntityHashMap::<HashMap<u64, Vec<u64>>>::default();
+    let mut descriptions = HashMap::<(u64, u64), String>::new();
+    for action in sets.iter().flat_map(|(_, actions)| actions.iter()) {
+        descriptions.extend(binding_id_systems.descriptions(world, *action));
+        let mut action_ids = binding_id_systems.binding_ids(world, *action);
+        for ids in action_ids.values_mut() {
+            ids.sort_unstable();
+            ids.dedup();
+        }
+        ids.insert(*action, action_ids);
+    }
+    let input = |label_id: u64, binding_id: u64| {
+        descriptions
+            .get(&(label_id, binding_id))
+            .cloned()
+            .unwrap_or_else(|| binding_id_systems.fallback_description(label_id, binding_id))
+    };
+    let mut issues = Vec::new();
+
+    for (set, actions) in sets.iter() {
+        let mut users = HashMap::<(u64, u64), Vec<Entity>>::new();
+        for action in actions {
+            for (label_id, binding_id) in flat_ids(&ids, *action) {
+                users
+                    .entry((label_id, binding_id))
+                    .or_default()
+                    .push(*action);
+            }
+        }
+        for ((label_id, binding_id), actions) in sorted(users) {
+            let description = format!(
+                "{} use the same input {} in set {}",
+                action_names(world, &actions),
+                input(label_id, binding_id),
+                set_name(world, *set),
+            );
+            issues.push(BindingIssue {
+                kind: BindingIssueKind::SameSet {
+                    set: *set,
+                    label_id,
+                    binding_id,
+                    actions,
+                },
+                description,
+            });
+        }
+    }
+
+    let mut higher = HashMap::<(u64, u64, Option<Entity>), Vec<Entity>>::new();
+    for group in priority_groups(world) {
+        // sets of different players see different devices, so they can't conflict
+        let mut users = HashMap::<(u64, u64, Option<Entity>), Vec<(Entity, Entity)>>::new();
+        for set in group.iter() {
+            let owner = world.get::<OwnedByPlayer>(*set).map(|owner| owner.0);
+            let Some((_, actions)) = sets.iter().find(|(e, _)| e == set) else {
+                continue;
+            };
+            for action in actions {
+                for (label_id, binding_id) in flat_ids(&ids, *action) {
+                    users
+                        .entry((label_id, binding_id, owner))
+                        .or_default()
+                        .push((*set, *action));
+                    // sets of players are only shadowed by their own sets and sets without an owner
+                    let by = higher
+                        .get(&(label_id, binding_id, None))
+                        .into_iter()
+                        .chain(owner.and(higher.get(&(label_id, binding_id, owner))))
+                        .flatten()
+                        .copied()
+                        .collect::<Vec<_>>();
+                    if by.is_empty() {
+                        continue;
+                    }
+                    let description = format!(
+                        "the {} binding of {} is shadowed by {}",
+                        input(label_id, binding_id),
+                        action_names(world, &[*action]),
+                        action_names(world, &by),
+                    );
+                    issues.push(BindingIssue {
+                        kind: BindingIssueKind::Shadowed {
+                            action: *action,
+                            label_id,
+                            binding_id,
+                            by,
+                        },
+                        description,
+                    });
+                }
+            }
+        }
+        let mut users = users.into_iter().collect::<Vec<_>>();
+        users.sort_by_key(|(key, _)| *key);
+        for ((label_id, binding_id, owner), users) in users {
+            // consuming actions only block while they trigger
+            for (_, action) in users.iter().filter(|(set, action)| {
+                world
+                    .get::<BlockingMode>(*action)
+                    .or_else(|| world.get::<BlockingMode>(*set))
+                    != Some(&BlockingMode::WhenTriggered)
+            }) {
+                higher
+                    .entry((label_id, binding_id, owner))
+                    .or_default()
+                    .push(*action);
+            }
+            if users.iter().all(|(set, _)| *set == users[0].0) {
+                continue;
+            }
+            let actions = users
+                .into_iter()
+                .map(|(_, action)| action)
+                .collect::<Vec<_>>();
+            let description = format!(
+                "{} use the same input {} in sets with the same priority",
+                action_names(world, &actions),
+                input(label_id, binding_id),
+            );
+            issues.push(BindingIssue {
+                kind: BindingIssueKind::EqualPriority {
+                    label_id,
+                    binding_id,
+                    actions,
+                },
+                description,
+            });
+        }
+    }
+
+    #[cfg(feature = "xr")]
+    oxr_issues(world, &mut issues);
+
+    world.insert_resource(binding_id_systems);
+    BindingReport { issues }
+}
+
+fn flat_ids(
+    ids: &EntityHashMap<HashMap<u64, Vec<u64>>>,
+    action: Entity,
+) -> impl Iterator<Item = (u64, u64)> + '_ {
+    ids.get(&action)
+        .into_iter()
+        .flat_map(|ids| ids.iter())
+        .flat_map(|(label_id, ids)| ids.iter().map(|id| (*label_id, *id)))
+}
+
+/// The inputs used by more than one action, in a stable order.
+fn sorted(users: HashMap<(u64, u64), Vec<Entity>>) -> Vec<((u64, u64), Vec<Entity>)> {
+    let mut users = users
+        .into_iter()
+        .filter(|(_, actions)| actions.len() > 1)
+        .collect::<Vec<_>>();
+    users.sort_by_key(|(key, _)| *key);
+    users
+}
+
+pub(crate) fn set_name(world: &World, set: Entity) -> String {
+    world
+        .get::<ActionSet>(set)
+        .map(|set| set.name.to_string())
+        .unwrap_or_else(|| set.to_string())
+}
+
+pub(crate) fn action_names(world: &World, actions: &[Entity]) -> String {
+    actions
+        .iter()
+        .map(|action| match world.get::<Action>(*action) {
+            Some(a) => format!("{}/{}", set_name(world, a.set), a.name),
+            None => action.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(feature = "xr")]
+fn oxr_issues(world: &mut World, issues: &mut Vec<BindingIssue>) {
+    use crate::{
+        BoolActionValue, F32ActionValue, Vec2ActionValue, openxr::OxrBindings, xr::SpaceActionValue,
+    };
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum OxrValue {
+        Bool,
+        F32,
+        Vec2,
+        Pose,
+    }
+
+    let mut query = world.query::<(
+        Entity,
+        &OxrBindings,
+        Has<BoolActionValue>,
+        Has<F32ActionValue>,
+        Has<Vec2ActionValue>,
+        Has<SpaceActionValue>,
+    )>();
+    let actions = query
+        .iter(world)
+        .map(|(e, bindings, b, f, v, s)| {
+            let value = match (b, f, v, s) {
+                (true, false, false, false) => Some(OxrValue::Bool),
+                (false, true, false, false) => Some(OxrValue::F32),
+                (false, false, true, false) => Some(OxrValue::Vec2),
+                (false, false, false, true) => Some(OxrValue::Pose),
+                _ => None,
+            };
+            (e, bindings.bindings.clone(), value)
+        })
+        .collect::<Vec<_>>();
+    for (action, bindings, value) in actions {
+        for (profile, binding) in bindings
+            .iter()
+            .flat_map(|(profile, paths)| paths.iter().map(move |path| (profile, path)))
+        {
+            let component = binding.rsplit('/').next().unwrap_or_default();
+            let input = match component {
+                "pose" => Some(OxrValue::Pose),
+                "x" | "y" | "value" | "force" => Some(OxrValue::F32),
+                "click" | "touch" => Some(OxrValue::Bool),
+                "thumbstick" | "trackpad" | "joystick" => Some(OxrValue::Vec2),
+                _ => None,
+            };
+            // the runtime converts between bool and float inputs
+            let fits = match (value, input) {
+                (None, _) => false,
+                (_, None) => true,
+                (Some(OxrValue::Bool | OxrValue::F32), Some(OxrValue::Bool | OxrValue::F32)) => {
+                    true
+                }
+                (Some(value), Some(input)) => value == input,
+            };
+            if fits {
+                continue;
+            }
+            let description = match value {
+                Some(_) => format!(
+                    "the OpenXR binding {binding} for {profile} doesn't fit the value of {}",
+                    action_names(world, &[action]),
+                ),
+                None => format!(
+                    "{} has OpenXR bindings but not exactly one value component",
+                    action_names(world, &[action]),
+                ),
+            };
+            issues.push(BindingIssue {
+                kind: BindingIssueKind::OxrValueMismatch {
+                    action,
+                    profile: profile.to_string(),
+                    binding: binding.to_string(),
+                },
+                description,
+            });
+        }
+    }
+}
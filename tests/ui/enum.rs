@@ -0,0 +1,8 @@
+use schminput::prelude::*;
+
+#[derive(ActionSet)]
+enum EnumActions {
+    Jump(BoolAction),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use std::marker::PhantomData;
+
+use schminput::prelude::*;
+
+#[derive(ActionSet)]
+struct GenericActions<T: Send + Sync + 'static> {
+    jump: BoolAction,
+    _marker: PhantomData<T>,
+}
+
+fn main() {}
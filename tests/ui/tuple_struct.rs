@@ -0,0 +1,6 @@
+use schminput::prelude::*;
+
+#[derive(ActionSet)]
+struct TupleActions(BoolAction);
+
+fn main() {}
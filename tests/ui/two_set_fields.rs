@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+use schminput::prelude::*;
+
+#[derive(Resource, ActionSet)]
+struct CoreActions {
+    #[action_set]
+    set: Entity,
+    #[action_set]
+    other_set: Entity,
+    jump: BoolAction,
+}
+
+fn main() {}
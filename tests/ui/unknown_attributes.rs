@@ -0,0 +1,16 @@
+use bevy::prelude::*;
+use schminput::prelude::*;
+
+#[derive(Resource, ActionSet)]
+#[action_set(nam = "core")]
+struct SetAttribute {
+    jump: BoolAction,
+}
+
+#[derive(Resource, ActionSet)]
+struct ActionAttribute {
+    #[action(keys = KeyCode::Space)]
+    jump: BoolAction,
+}
+
+fn main() {}
@@ -0,0 +1,2205 @@
+use std::time::Duration;
+
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use schminput::{
+    ButtonInputBeheavior,
+    action_events::{
+        ActionCanceled, ActionCompleted, ActionEventValue, ActionOngoing, ActionStarted,
+    },
+    aggregation::ValueAggregation,
+    binding_modification::{BindingModifications, PremultiplyDeltaSecsModification},
+    contexts::{ContextCommandsExt, ContextLayer, ContextPolicy, InputContext},
+    debug::{
+        BlockingAction, ContributingBindings, DebugValue, LogActionValues, PathDebugValue,
+        action_debug_info, dump_actions, explain_blocked,
+    },
+    fixed::{FixedBoolActionValue, FixedVec2ActionValue},
+    gamepad::GamepadIdentifier,
+    interactions::BindingInteractions,
+    joining::{JoinDevice, PlayerJoined, PlayerLeft},
+    prelude::*,
+    priorities::BlockingMode,
+    processors::{InputProcessor, InputProcessorAppExt, InputProcessors, RadialDeadzone, Scale},
+    recording::InputPlaybackSettings,
+    snapshot::{
+        ActionId, ActionSetId, ActionSnapshot, ActionSnapshots, ApplyActionSnapshot,
+        QUANTIZATION_STEPS, SnapshotEntry, SnapshotValue, SubactionPathId,
+    },
+    subaction_paths::SubactionPath,
+    testing::*,
+    validation::{BindingIssueKind, BindingReport, validate_bindings},
+};
+
+fn spawn_set(app: &mut App, name: &'static str, priority: u32) -> Entity {
+    app.world_mut()
+        .spawn(ActionSet::new(name, name, priority))
+        .id()
+}
+
+#[test]
+fn keyboard_and_mouse() {
+    let mut app = headless_app();
+    let set = spawn_set(&mut app, "core", 0);
+    let fire = app
+        .world_mut()
+        .spawn((
+            Action::new("fire", "Fire", set),
+            KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::KeyF)),
+            MouseBindings::new().bind(MouseButtonBinding::new(MouseButton::Left)),
+            BoolActionValue::new(),
+        ))
+        .id();
+    let look = app
+        .world_mut()
+        .spawn((
+            Action::new("look", "Look", set),
+            MouseBindings::new().delta_motion(),
+            Vec2ActionValue::new(),
+        ))
+        .id();
+    app.step();
+
+    app.press_key(KeyCode::KeyF).step();
+    assert!(app.action_value::<BoolActionValue>(fire).any);
+    app.release_key(KeyCode::KeyF).step();
+    assert!(!app.action_value::<BoolActionValue>(fire).any);
+    app.press_mouse_button(MouseButton::Left).step();
+    assert!(app.action_value::<BoolActionValue>(fire).any);
+
+    // mouse motion is y down, actions are y up
+    app.move_mouse(Vec2::new(3.0, -2.0)).step();
+    assert_eq!(
+        app.action_value::<Vec2ActionValue>(look).any,
+        Vec2::new(3.0, 2.0)
+    );
+    app.step();
+    assert_eq!(app.action_value::<Vec2ActionValue>(look).any, Vec2::ZERO);
+}
+
+#[test]
+fn gamepad_path_selector() {
+    let mut app = headless_app();
+    let set = spawn_set(&mut app, "core", 0);
+    let gamepad_1 = app.spawn_virtual_gamepad("1");
+    let gamepad_2 = app.spawn_virtual_gamepad("2");
+    let (all, first) = app
+        .world_mut()
+        .resource_scope(|world, mut paths: Mut<SubactionPaths>| {
+            let mut cmds = world.commands();
+            (
+                paths.get_or_create_path("/gamepad/*", &mut cmds),
+                paths.get_or_create_path("/gamepad/1", &mut cmds),
+            )
+        });
+    let jump = app
+        .world_mut()
+        .spawn((
+            Action::new("jump", "Jump", set),
+            GamepadBindings::new().bind(GamepadBinding::new(GamepadBindingSource::South)),
+            RequestedSubactionPaths(vec![all, first]),
+            F32ActionValue::new(),
+        ))
+        .id();
+    app.step();
+
+    app.press_gamepad_button(gamepad_2, GamepadButton::South)
+        .step();
+    let value = app.action_value::<F32ActionValue>(jump);
+    assert_eq!(value.any, 1.0);
+    assert_eq!(value.get_with_path_or_default(&all), 1.0);
+    assert_eq!(value.get_with_path_or_default(&first), 0.0);
+
+    app.release_gamepad_button(gamepad_2, GamepadButton::South)
+        .set_gamepad_button(gamepad_1, GamepadButton::South, 0.5)
+        .step();
+    let value = app.action_value::<F32ActionValue>(jump);
+    assert_eq!(value.get_with_path_or_default(&first), 0.5);
+}
+
+#[test]
+fn gamepad_axes() {
+    let mut app = headless_app();
+    let set = spawn_set(&mut app, "core", 0);
+    let gamepad = app.spawn_virtual_gamepad("1");
+    let movement = app
+        .world_mut()
+        .spawn((
+            Action::new("move", "Move", set),
+            GamepadBindings::new().add_stick(
+                GamepadBindingSource::LeftStickX,
+                GamepadBindingSource::LeftStickY,
+            ),
+            Vec2ActionValue::new(),
+        ))
+        .id();
+    app.step();
+
+    app.set_gamepad_axis(gamepad, GamepadAxis::LeftStickX, 0.5)
+        .set_gamepad_axis(gamepad, GamepadAxis::LeftStickY, -0.25)
+        .step();
+    assert_eq!(
+        app.action_value::<Vec2ActionValue>(movement).any,
+        Vec2::new(0.5, -0.25)
+    );
+}
+
+#[test]
+fn value_aggregation() {
+    let mut app = headless_app();
+    let set = spawn_set(&mut app, "core", 0);
+    let gamepad = app.spawn_virtual_gamepad("1");
+    let mut spawn_vec2 = |aggregation| {
+        app.world_mut()
+            .spawn((
+                Action::new("move", "Move", set),
+                KeyboardBindings::new().add_dpad(
+                    KeyCode::KeyW,
+                    KeyCode::KeyS,
+                    KeyCode::KeyA,
+                    KeyCode::KeyD,
+                ),
+                GamepadBindings::new().add_stick(
+                    GamepadBindingSource::LeftStickX,
+                    GamepadBindingSource::LeftStickY,
+                ),
+                aggregation,
+                Vec2ActionValue::new(),
+            ))
+            .id()
+    };
+    let sum = spawn_vec2(ValueAggregation::default());
+    let max_magnitude = spawn_vec2(ValueAggregation::MaxMagnitude);
+    let longest = spawn_vec2(ValueAggregation::LongestVector);
+    let average = spawn_vec2(ValueAggregation::Average);
+    let latest = spawn_vec2(ValueAggregation::Latest);
+    let clamped_sum = app
+        .world_mut()
+        .spawn((
+            Action::new("throttle", "Throttle", set),
+            KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::KeyW)),
+            MouseBindings::new().bind(MouseButtonBinding::new(MouseButton::Left)),
+            GamepadBindings::new().bind(GamepadBinding::new(GamepadBindingSource::LeftStickX)),
+            ValueAggregation::ClampedSum,
+            F32ActionValue::new(),
+        ))
+        .id();
+    app.step();
+
+    app.press_key(KeyCode::KeyD).step();
+    assert_eq!(app.action_value::<Vec2ActionValue>(latest).any, Vec2::X);
+    app.set_gamepad_axis(gamepad, GamepadAxis::LeftStickX, 0.6)
+        .set_gamepad_axis(gamepad, GamepadAxis::LeftStickY, 0.9)
+        .step();
+    let value = |app: &App, action| app.action_value::<Vec2ActionValue>(action).any;
+    assert!(value(&app, sum).abs_diff_eq(Vec2::new(1.6, 0.9), 1e-6));
+    assert!(value(&app, max_magnitude).abs_diff_eq(Vec2::new(1.0, 0.9), 1e-6));
+    assert!(value(&app, longest).abs_diff_eq(Vec2::new(0.6, 0.9), 1e-6));
+    assert!(value(&app, average).abs_diff_eq(Vec2::new(0.8, 0.45), 1e-6));
+    assert!(value(&app, latest).abs_diff_eq(Vec2::new(0.6, 0.9), 1e-6));
+
+    // the gamepad changed last, but doesn't give a value anymore
+    app.world_mut().entity_mut(gamepad).despawn();
+    app.step();
+    assert_eq!(value(&app, latest), Vec2::X);
+
+    // 1 + 1 - 1 is 1 in any order
+    let gamepad = app.spawn_virtual_gamepad("2");
+    app.set_gamepad_axis(gamepad, GamepadAxis::LeftStickX, -1.0)
+        .press_key(KeyCode::KeyW)
+        .press_mouse_button(MouseButton::Left)
+        .step();
+    assert_eq!(app.action_value::<F32ActionValue>(clamped_sum).any, 1.0);
+    app.release_key(KeyCode::KeyW)
+        .release_mouse_button(MouseButton::Left)
+        .step();
+    assert_eq!(app.action_value::<F32ActionValue>(clamped_sum).any, -1.0);
+}
+
+#[test]
+fn priorities_block_lower_sets() {
+    let mut app = headless_app();
+    let menu = spawn_set(&mut app, "menu", 1);
+    let core = spawn_set(&mut app, "core", 0);
+    let mut spawn_action = |set| {
+        app.world_mut()
+            .spawn((
+                Action::new("confirm", "Confirm", set),
+                KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::Enter)),
+                BoolActionValue::new(),
+            ))
+            .id()
+    };
+    let confirm = spawn_action(menu);
+    let jump = spawn_action(core);
+    app.step();
+
+    app.press_key(KeyCode::Enter).step();
+    assert!(app.action_value::<BoolActionValue>(confirm).any);
+    assert!(!app.action_value::<BoolActionValue>(jump).any);
+
+    app.world_mut().get_mut::<ActionSet>(menu).unwrap().enabled = false;
+    app.step();
+    assert!(!app.action_value::<BoolActionValue>(confirm).any);
+    assert!(app.action_value::<BoolActionValue>(jump).any);
+}
+
+#[test]
+fn player_sets_only_block_their_own_sets() {
+    let mut app = headless_app();
+    let spawn_player = |app: &mut App, slot, gamepad| {
+        let player = app
+            .world_mut()
+            .spawn((
+                Player::new(slot),
+                PlayerDevices::new().with_gamepad(gamepad),
+            ))
+            .id();
+        let mut spawn_action = |name: &'static str, priority| {
+            let set = spawn_set(app, name, priority);
+            app.world_mut()
+                .entity_mut(set)
+                .insert(OwnedByPlayer(player));
+            app.world_mut()
+                .spawn((
+                    Action::new(name, name, set),
+                    GamepadBindings::new().bind(GamepadBinding::new(GamepadBindingSource::South)),
+                    BoolActionValue::new(),
+                ))
+                .id()
+        };
+        (spawn_action("pause", 1), spawn_action("jump", 0))
+    };
+    let gamepad_1 = app.spawn_virtual_gamepad("1");
+    let gamepad_2 = app.spawn_virtual_gamepad("2");
+    let (pause_1, jump_1) = spawn_player(&mut app, 0, gamepad_1);
+    let (pause_2, jump_2) = spawn_player(&mut app, 1, gamepad_2);
+    let pause_set_1 = app.world().get::<Action>(pause_1).unwrap().set;
+    app.world_mut()
+        .get_mut::<ActionSet>(pause_set_1)
+        .unwrap()
+        .enabled = false;
+    app.step();
+
+    // the pause set of player 2 doesn't block the jump of player 1
+    app.press_gamepad_button(gamepad_1, GamepadButton::South)
+        .press_gamepad_button(gamepad_2, GamepadButton::South)
+        .step();
+    assert!(app.action_value::<BoolActionValue>(jump_1).any);
+    assert!(app.action_value::<BoolActionValue>(pause_2).any);
+    assert!(!app.action_value::<BoolActionValue>(jump_2).any);
+    let report = validate_bindings(app.world_mut());
+    assert!(
+        !report.issues.iter().any(
+            |i| matches!(i.kind, BindingIssueKind::Shadowed { action, .. } if action == jump_1)
+        ),
+        "{:?}",
+        report.issues
+    );
+
+    // and it only consumes the input for the sets of player 2
+    let pause_set_2 = app.world().get::<Action>(pause_2).unwrap().set;
+    app.world_mut()
+        .entity_mut(pause_set_2)
+        .insert(BlockingMode::WhenTriggered);
+    app.step();
+    assert!(app.action_value::<BoolActionValue>(jump_1).any);
+    assert!(app.action_value::<BoolActionValue>(pause_2).any);
+    assert!(!app.action_value::<BoolActionValue>(jump_2).any);
+}
+
+#[test]
+fn blocked_inputs_follow_changes() {
+    let mut app = headless_app();
+    let menu = spawn_set(&mut app, "menu", 1);
+    let core = spawn_set(&mut app, "core", 0);
+    let hidden = spawn_set(&mut app, "hidden", 2);
+    app.world_mut()
+        .get_mut::<ActionSet>(hidden)
+        .unwrap()
+        .enabled = false;
+    let enter = || KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::Enter));
+    let confirm = app
+        .world_mut()
+        .spawn((
+            Action::new("confirm", "Confirm", menu),
+            enter(),
+            BoolActionValue::new(),
+        ))
+        .id();
+    let jump = app
+        .world_mut()
+        .spawn((
+            Action::new("jump", "Jump", core),
+            enter(),
+            BoolActionValue::new(),
+        ))
+        .id();
+    app.press_key(KeyCode::Enter).step();
+    let pressed = |app: &App| {
+        (
+            app.action_value::<BoolActionValue>(confirm).any,
+            app.action_value::<BoolActionValue>(jump).any,
+        )
+    };
+    assert_eq!(pressed(&app), (true, false));
+
+    // binding changes
+    app.world_mut()
+        .entity_mut(confirm)
+        .insert(KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::Space)));
+    app.step();
+    assert_eq!(pressed(&app), (false, true));
+    app.world_mut().entity_mut(confirm).insert(enter());
+    app.step();
+    assert_eq!(pressed(&app), (true, false));
+
+    // set changes
+    app.world_mut().get_mut::<ActionSet>(menu).unwrap().enabled = false;
+    app.step();
+    assert_eq!(pressed(&app), (false, true));
+    app.world_mut().get_mut::<ActionSet>(menu).unwrap().enabled = true;
+    app.step();
+    assert_eq!(pressed(&app), (true, false));
+    app.world_mut().get_mut::<ActionSet>(core).unwrap().priority = 2;
+    app.step();
+    assert_eq!(pressed(&app), (false, true));
+    app.world_mut().get_mut::<ActionSet>(core).unwrap().priority = 0;
+    app.step();
+    assert_eq!(pressed(&app), (true, false));
+
+    // membership changes
+    app.world_mut()
+        .entity_mut(confirm)
+        .insert(Action::new("confirm", "Confirm", hidden));
+    app.step();
+    assert_eq!(pressed(&app), (false, true));
+    app.world_mut()
+        .entity_mut(confirm)
+        .insert(Action::new("confirm", "Confirm", menu));
+    app.step();
+    assert_eq!(pressed(&app), (true, false));
+}
+
+#[test]
+fn triggered_actions_consume_inputs() {
+    let mut app = headless_app();
+    let dialog = spawn_set(&mut app, "dialog", 1);
+    let core = spawn_set(&mut app, "core", 0);
+    app.world_mut()
+        .entity_mut(dialog)
+        .insert(BlockingMode::WhenTriggered);
+    let confirm = spawn_key_action(
+        &mut app,
+        dialog,
+        KeyCode::Enter,
+        ButtonInputBeheavior::JustPressed,
+    );
+    let save = app
+        .world_mut()
+        .spawn((
+            Action::new("save", "Save", dialog),
+            ChordBindings::new()
+                .bind(ChordBinding::new(KeyCode::KeyS).modifier(KeyCode::ControlLeft)),
+            BoolActionValue::new(),
+        ))
+        .id();
+    let jump = spawn_key_action(
+        &mut app,
+        core,
+        KeyCode::Enter,
+        ButtonInputBeheavior::Pressed,
+    );
+    let write = spawn_key_action(&mut app, core, KeyCode::KeyS, ButtonInputBeheavior::Pressed);
+    app.step();
+    let value = |app: &App, action| app.action_value::<BoolActionValue>(action).any;
+
+    // idle actions don't block anything
+    app.press_key(KeyCode::KeyS).step();
+    assert!(value(&app, write));
+    app.release_key(KeyCode::KeyS).step();
+
+    app.press_key(KeyCode::Enter).step();
+    assert!(value(&app, confirm));
+    assert!(!value(&app, jump));
+    app.step();
+    assert!(!value(&app, confirm));
+    assert!(value(&app, jump));
+    app.release_key(KeyCode::Enter).step();
+
+    // chords consume the keys for the keyboard bindings below them
+    app.press_key(KeyCode::ControlLeft).step();
+    app.press_key(KeyCode::KeyS).step();
+    assert!(value(&app, save));
+    assert!(!value(&app, write));
+    app.release_key(KeyCode::ControlLeft).step();
+    assert!(!value(&app, save));
+    assert!(value(&app, write));
+}
+
+#[test]
+fn chords() {
+    let mut app = headless_app();
+    let editor = spawn_set(&mut app, "editor", 1);
+    let core = spawn_set(&mut app, "core", 0);
+    let save = app
+        .world_mut()
+        .spawn((
+            Action::new("save", "Save", editor),
+            ChordBindings::new()
+                .bind(ChordBinding::new(KeyCode::KeyS).modifier(KeyCode::ControlLeft)),
+            BoolActionValue::new(),
+        ))
+        .id();
+    let mut spawn_plain = |name, key| {
+        app.world_mut()
+            .spawn((
+                Action::new(name, name, core),
+                KeyboardBindings::new().bind(KeyboardBinding::new(key)),
+                BoolActionValue::new(),
+            ))
+            .id()
+    };
+    let back = spawn_plain("back", KeyCode::KeyS);
+    let crouch = spawn_plain("crouch", KeyCode::ControlLeft);
+    app.step();
+    let pressed = |app: &App, action| app.action_value::<BoolActionValue>(action).any;
+
+    // the modifier has to be held before the input
+    app.press_key(KeyCode::KeyS).step();
+    app.press_key(KeyCode::ControlLeft).step();
+    assert!(!pressed(&app, save));
+    assert!(pressed(&app, back));
+    assert!(pressed(&app, crouch));
+    app.release_key(KeyCode::KeyS)
+        .release_key(KeyCode::ControlLeft)
+        .step();
+    app.press_key(KeyCode::KeyS)
+        .press_key(KeyCode::ControlLeft)
+        .step();
+    assert!(!pressed(&app, save));
+    app.release_key(KeyCode::KeyS)
+        .release_key(KeyCode::ControlLeft)
+        .step();
+
+    // the members are only blocked in lower sets while the chord is held
+    app.press_key(KeyCode::ControlLeft).step();
+    assert!(pressed(&app, crouch));
+    app.press_key(KeyCode::KeyS).step();
+    assert!(pressed(&app, save));
+    assert!(!pressed(&app, back));
+    assert!(!pressed(&app, crouch));
+    app.step();
+    assert!(pressed(&app, save));
+    app.release_key(KeyCode::KeyS).step();
+    assert!(!pressed(&app, save));
+    assert!(pressed(&app, crouch));
+}
+
+fn spawn_key_action(
+    app: &mut App,
+    set: Entity,
+    key: KeyCode,
+    behavior: ButtonInputBeheavior,
+) -> Entity {
+    app.world_mut()
+        .spawn((
+            Action::new(format!("{key:?}"), format!("{key:?}"), set),
+            KeyboardBindings::new().bind(KeyboardBinding::new(key).behavior(behavior)),
+            BoolActionValue::new(),
+        ))
+        .id()
+}
+
+#[test]
+fn button_interactions() {
+    let ms = Duration::from_millis;
+    let mut app = headless_app();
+    let set = spawn_set(&mut app, "core", 0);
+    let tap = spawn_key_action(
+        &mut app,
+        set,
+        KeyCode::KeyT,
+        ButtonInputBeheavior::Tap(ms(100)),
+    );
+    let double_tap = spawn_key_action(
+        &mut app,
+        set,
+        KeyCode::KeyD,
+        ButtonInputBeheavior::DoubleTap(ms(200)),
+    );
+    let toggle = spawn_key_action(&mut app, set, KeyCode::KeyG, ButtonInputBeheavior::Toggle);
+    let repeat = spawn_key_action(
+        &mut app,
+        set,
+        KeyCode::KeyR,
+        ButtonInputBeheavior::Repeat {
+            delay: ms(110),
+            rate: ms(60),
+        },
+    );
+    app.step();
+    let value = |app: &App, action| app.action_value::<BoolActionValue>(action).any;
+
+    app.press_key(KeyCode::KeyT).step();
+    assert!(!value(&app, tap));
+    app.release_key(KeyCode::KeyT).step();
+    assert!(value(&app, tap));
+    app.step();
+    assert!(!value(&app, tap));
+    app.press_key(KeyCode::KeyT).step_frames(10);
+    app.release_key(KeyCode::KeyT).step();
+    assert!(!value(&app, tap), "released after the tap duration");
+
+    app.press_key(KeyCode::KeyD).step();
+    assert!(!value(&app, double_tap));
+    app.release_key(KeyCode::KeyD).step();
+    app.press_key(KeyCode::KeyD).step();
+    assert!(value(&app, double_tap));
+    app.release_key(KeyCode::KeyD).step_frames(20);
+    app.press_key(KeyCode::KeyD).step();
+    assert!(!value(&app, double_tap), "second press after the duration");
+    app.release_key(KeyCode::KeyD).step();
+
+    app.press_key(KeyCode::KeyG).step();
+    assert!(value(&app, toggle));
+    app.release_key(KeyCode::KeyG).step();
+    assert!(value(&app, toggle));
+    app.press_key(KeyCode::KeyG).step();
+    assert!(!value(&app, toggle));
+    app.release_key(KeyCode::KeyG).step();
+
+    app.press_key(KeyCode::KeyR);
+    let active_frames = (0..20)
+        .filter(|_| value(app.step(), repeat))
+        .collect::<Vec<_>>();
+    assert_eq!(active_frames, [0, 7, 11, 15, 19]);
+}
+
+#[test]
+fn hold_restarts_after_being_blocked() {
+    let mut app = headless_app();
+    let core = spawn_set(&mut app, "core", 0);
+    let menu = spawn_set(&mut app, "menu", 1);
+    app.world_mut().get_mut::<ActionSet>(menu).unwrap().enabled = false;
+    let hold = spawn_key_action(
+        &mut app,
+        core,
+        KeyCode::KeyH,
+        ButtonInputBeheavior::Hold(Duration::from_millis(100)),
+    );
+    spawn_key_action(&mut app, menu, KeyCode::KeyH, ButtonInputBeheavior::Pressed);
+    app.step();
+    let value = |app: &App| app.action_value::<BoolActionValue>(hold).any;
+    let progress = |app: &App| {
+        app.world()
+            .get::<BindingInteractions>(hold)
+            .unwrap()
+            .hold_progress()
+    };
+
+    app.press_key(KeyCode::KeyH).step();
+    assert!(!value(&app));
+    assert_eq!(progress(&app), Some(0.0));
+    app.step_frames(4);
+    assert!(!value(&app));
+    assert!(progress(&app).is_some_and(|p| p > 0.5 && p < 1.0));
+
+    app.world_mut().get_mut::<ActionSet>(menu).unwrap().enabled = true;
+    app.step_frames(10);
+    assert!(!value(&app));
+    assert_eq!(progress(&app), None);
+
+    app.world_mut().get_mut::<ActionSet>(menu).unwrap().enabled = false;
+    app.step_frames(5);
+    assert!(
+        !value(&app),
+        "the hold restarted when the key was unblocked"
+    );
+    app.step_frames(3);
+    assert!(value(&app));
+    assert_eq!(progress(&app), Some(1.0));
+
+    app.release_key(KeyCode::KeyH).step();
+    assert!(!value(&app));
+    assert_eq!(progress(&app), None);
+}
+
+/// Presses and releases the key, returns if the action was active on the frame it was pressed.
+fn tap_key(app: &mut App, key: KeyCode, action: Entity) -> bool {
+    app.press_key(key).step();
+    let active = app.action_value::<BoolActionValue>(action).any;
+    app.release_key(key).step();
+    active
+}
+
+#[test]
+fn sequences() {
+    let ms = Duration::from_millis;
+    let mut app = headless_app();
+    let set = spawn_set(&mut app, "core", 0);
+    let mut spawn_sequence = |binding: SequenceBinding| {
+        app.world_mut()
+            .spawn((
+                Action::new("sequence", "Sequence", set),
+                SequenceBindings::new().bind(binding),
+                BoolActionValue::new(),
+            ))
+            .id()
+    };
+    let strict = spawn_sequence(
+        SequenceBinding::new()
+            .then(KeyCode::KeyA)
+            .then(KeyCode::KeyB)
+            .step_timeout(ms(100))
+            .reset_on_wrong_input(),
+    );
+    let loose = spawn_sequence(
+        SequenceBinding::new()
+            .then(KeyCode::KeyC)
+            .then(KeyCode::KeyD)
+            .step_timeout(ms(100)),
+    );
+    let timed = spawn_sequence(
+        SequenceBinding::new()
+            .then(KeyCode::KeyX)
+            .then(KeyCode::KeyY)
+            .then(KeyCode::KeyZ)
+            .step_timeout(ms(100))
+            .timeout(ms(150)),
+    );
+    app.step();
+
+    assert!(!tap_key(&mut app, KeyCode::KeyA, strict));
+    assert!(tap_key(&mut app, KeyCode::KeyB, strict));
+
+    // too long between the steps
+    tap_key(&mut app, KeyCode::KeyA, strict);
+    app.step_frames(6);
+    assert!(!tap_key(&mut app, KeyCode::KeyB, strict));
+    tap_key(&mut app, KeyCode::KeyC, loose);
+    app.step_frames(6);
+    assert!(!tap_key(&mut app, KeyCode::KeyD, loose));
+
+    // keys that aren't part of the sequence reset it
+    tap_key(&mut app, KeyCode::KeyA, strict);
+    tap_key(&mut app, KeyCode::KeyQ, strict);
+    assert!(!tap_key(&mut app, KeyCode::KeyB, strict));
+    tap_key(&mut app, KeyCode::KeyC, loose);
+    tap_key(&mut app, KeyCode::KeyQ, loose);
+    assert!(tap_key(&mut app, KeyCode::KeyD, loose));
+
+    // every step in time, but too long for the whole sequence
+    tap_key(&mut app, KeyCode::KeyX, timed);
+    app.step_frames(3);
+    tap_key(&mut app, KeyCode::KeyY, timed);
+    app.step_frames(3);
+    assert!(!tap_key(&mut app, KeyCode::KeyZ, timed));
+    tap_key(&mut app, KeyCode::KeyX, timed);
+    tap_key(&mut app, KeyCode::KeyY, timed);
+    assert!(tap_key(&mut app, KeyCode::KeyZ, timed));
+}
+
+#[test]
+fn action_state_transitions() {
+    let mut app = headless_app();
+    let set = spawn_set(&mut app, "core", 0);
+    let jump = spawn_key_action(&mut app, set, KeyCode::Space, ButtonInputBeheavior::Pressed);
+    app.step();
+    let state = |app: &App| app.world().get::<BoolActionState>(jump).unwrap().any;
+
+    app.press_key(KeyCode::Space).step();
+    let pressed = state(&app);
+    assert!(pressed.active() && pressed.started() && pressed.changed());
+    assert!(!pressed.ongoing() && !pressed.completed());
+    assert_eq!(pressed.held_for, Duration::ZERO);
+
+    app.step_frames(3);
+    let held = state(&app);
+    assert!(held.active() && held.ongoing());
+    assert!(!held.started() && !held.completed() && !held.changed());
+    assert_eq!(held.held_for, HEADLESS_FRAME_TIME * 3);
+    assert_eq!(held.last_changed, pressed.last_changed);
+
+    app.release_key(KeyCode::Space).step();
+    let released = state(&app);
+    assert!(released.completed() && released.changed());
+    assert!(!released.active() && !released.ongoing());
+    // held_for keeps the duration of the last press
+    assert_eq!(released.held_for, HEADLESS_FRAME_TIME * 3);
+    assert_eq!(released.released_for, Duration::ZERO);
+
+    app.step_frames(2);
+    let idle = state(&app);
+    assert!(!idle.active() && !idle.completed() && !idle.changed());
+    assert_eq!(idle.released_for, HEADLESS_FRAME_TIME * 2);
+
+    app.press_key(KeyCode::Space).step();
+    assert!(state(&app).started());
+    assert_eq!(state(&app).held_for, Duration::ZERO);
+}
+
+#[derive(Resource, Default)]
+struct ActionEventLog(Vec<(&'static str, Option<SubactionPath>, ActionEventValue)>);
+
+#[test]
+fn action_events() {
+    let mut app = headless_app();
+    app.init_resource::<ActionEventLog>();
+    let set = spawn_set(&mut app, "core", 0);
+    let jump = spawn_key_action(&mut app, set, KeyCode::Space, ButtonInputBeheavior::Pressed);
+    app.world_mut()
+        .run_system_once(
+            move |mut cmds: Commands, mut paths: ResMut<SubactionPaths>| {
+                let sub_paths = RequestedSubactionPaths::new()
+                    .mutate(&mut paths, cmds.reborrow())
+                    .push("/keyboard")
+                    .end();
+                cmds.entity(jump).insert(sub_paths);
+            },
+        )
+        .unwrap();
+    app.world_mut()
+        .entity_mut(jump)
+        .observe(
+            |event: On<ActionStarted>, mut log: ResMut<ActionEventLog>| {
+                log.0.push(("started", event.path, event.value));
+            },
+        )
+        .observe(
+            |event: On<ActionOngoing>, mut log: ResMut<ActionEventLog>| {
+                log.0.push(("ongoing", event.path, event.value));
+            },
+        )
+        .observe(
+            |event: On<ActionCompleted>, mut log: ResMut<ActionEventLog>| {
+                log.0.push(("completed", event.path, event.value));
+            },
+        )
+        .observe(
+            |event: On<ActionCanceled>, mut log: ResMut<ActionEventLog>| {
+                log.0.push(("canceled", event.path, event.value));
+            },
+        );
+    app.step();
+    let keyboard = app.world().resource::<SubactionPaths>().get("/keyboard");
+    assert!(keyboard.is_some());
+    // every event of a bool action carries true, the last active value
+    let events = |app: &mut App| {
+        let log = std::mem::take(&mut app.world_mut().resource_mut::<ActionEventLog>().0);
+        assert!(
+            log.iter()
+                .all(|(_, _, value)| *value == ActionEventValue::Bool(true))
+        );
+        log.into_iter()
+            .map(|(name, path, _)| (name, path))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(events(&mut app), []);
+
+    app.press_key(KeyCode::Space).step();
+    assert_eq!(events(&mut app), [("started", None), ("started", keyboard)]);
+    app.step();
+    assert_eq!(events(&mut app), [("ongoing", None), ("ongoing", keyboard)]);
+    app.release_key(KeyCode::Space).step();
+    assert_eq!(
+        events(&mut app),
+        [("completed", None), ("completed", keyboard)]
+    );
+    app.step();
+    assert_eq!(events(&mut app), []);
+
+    app.press_key(KeyCode::Space).step();
+    events(&mut app);
+    app.world_mut().get_mut::<ActionSet>(set).unwrap().enabled = false;
+    app.step();
+    assert_eq!(
+        events(&mut app),
+        [("canceled", None), ("canceled", keyboard)]
+    );
+    app.step();
+    assert_eq!(events(&mut app), []);
+}
+
+fn spawn_recorded_actions(app: &mut App) -> (Entity, Entity) {
+    let set = spawn_set(app, "core", 0);
+    let walk = app
+        .world_mut()
+        .spawn((
+            // escaped in the file
+            Action::new("walk\tabout", "Walk About", set),
+            KeyboardBindings::new().add_dpad(
+                KeyCode::KeyW,
+                KeyCode::KeyS,
+                KeyCode::KeyA,
+                KeyCode::KeyD,
+            ),
+            Vec2ActionValue::new(),
+        ))
+        .id();
+    let jump = spawn_key_action(app, set, KeyCode::Space, ButtonInputBeheavior::Pressed);
+    app.step();
+    (walk, jump)
+}
+
+#[test]
+fn recording_roundtrip() {
+    fn values(app: &App, (walk, jump): (Entity, Entity)) -> (Vec2, bool) {
+        (
+            app.action_value::<Vec2ActionValue>(walk).any,
+            app.action_value::<BoolActionValue>(jump).any,
+        )
+    }
+    let mut app = headless_app();
+    let actions = spawn_recorded_actions(&mut app);
+    app.world_mut()
+        .resource_mut::<InputRecorder>()
+        .start_recording();
+    let mut recorded = Vec::new();
+    app.press_key(KeyCode::KeyW).step();
+    recorded.push(values(&app, actions));
+    app.press_key(KeyCode::KeyD)
+        .press_key(KeyCode::Space)
+        .step();
+    recorded.push(values(&app, actions));
+    app.release_key(KeyCode::KeyW).step();
+    recorded.push(values(&app, actions));
+    app.release_key(KeyCode::KeyD)
+        .release_key(KeyCode::Space)
+        .step();
+    recorded.push(values(&app, actions));
+    let recording = app
+        .world_mut()
+        .resource_mut::<InputRecorder>()
+        .stop_recording()
+        .unwrap();
+    assert_eq!(recording.frames.len(), 4);
+    assert!(
+        recording
+            .frames
+            .iter()
+            .all(|f| f.delta == HEADLESS_FRAME_TIME)
+    );
+    assert_eq!(recorded[1], (Vec2::ONE, true));
+
+    let file = std::env::temp_dir().join(format!("schminput-recording-{}.txt", std::process::id()));
+    recording.save(&file).unwrap();
+    let loaded = InputRecording::load(&file);
+    std::fs::remove_file(&file).unwrap();
+    assert_eq!(loaded.unwrap(), recording);
+    assert!("".parse::<InputRecording>().is_err());
+    assert!(
+        "schminput-recording 1\nbool\tcore\tjump\t*\ttrue"
+            .parse::<InputRecording>()
+            .is_err()
+    );
+
+    // played back in another app, keyboard input is ignored until playback finished
+    let mut app = headless_app();
+    let actions = spawn_recorded_actions(&mut app);
+    app.world_mut()
+        .resource_mut::<InputRecorder>()
+        .start_playback(recording, InputPlaybackSettings::default());
+    app.press_key(KeyCode::KeyA);
+    for values_while_recording in recorded {
+        app.step();
+        assert_eq!(values(&app, actions), values_while_recording);
+    }
+    assert!(!app.world().resource::<InputRecorder>().is_playing());
+    app.step();
+    assert_eq!(values(&app, actions), (Vec2::NEG_X, false));
+}
+
+#[test]
+fn binding_conflict_report() {
+    let mut app = headless_app();
+    let core = spawn_set(&mut app, "core", 0);
+    let ui = spawn_set(&mut app, "ui", 0);
+    let overlay = spawn_set(&mut app, "overlay", 1);
+    let disabled = spawn_set(&mut app, "disabled", 2);
+    app.world_mut()
+        .get_mut::<ActionSet>(disabled)
+        .unwrap()
+        .enabled = false;
+    let spawn_action = |app: &mut App, set: Entity, name: &'static str, key: KeyCode| {
+        app.world_mut()
+            .spawn((
+                Action::new(name, name, set),
+                KeyboardBindings::new().bind(KeyboardBinding::new(key)),
+                BoolActionValue::new(),
+            ))
+            .id()
+    };
+    let jump = spawn_action(&mut app, core, "jump", KeyCode::Space);
+    let hop = spawn_action(&mut app, core, "hop", KeyCode::Space);
+    let back = spawn_action(&mut app, core, "back", KeyCode::Escape);
+    spawn_action(&mut app, core, "kick", KeyCode::KeyK);
+    let confirm = spawn_action(&mut app, ui, "confirm", KeyCode::Space);
+    let pause = spawn_action(&mut app, overlay, "pause", KeyCode::Escape);
+    let skip = spawn_action(&mut app, overlay, "skip", KeyCode::KeyK);
+    spawn_action(&mut app, disabled, "close", KeyCode::Escape);
+    // sets of different players never conflict
+    for slot in 0..2 {
+        let player = app.world_mut().spawn(Player::new(slot)).id();
+        let set = spawn_set(&mut app, "player", 0);
+        app.world_mut()
+            .entity_mut(set)
+            .insert(OwnedByPlayer(player));
+        spawn_action(&mut app, set, "wave", KeyCode::KeyP);
+    }
+    // only blocks while triggered, so kick isn't shadowed
+    app.world_mut()
+        .entity_mut(skip)
+        .insert(BlockingMode::WhenTriggered);
+    app.step();
+
+    let report = app.world().resource::<BindingReport>();
+    assert_eq!(report.issues.len(), 3, "{:?}", report.issues);
+    let same_set = report
+        .issues
+        .iter()
+        .find(|i| matches!(i.kind, BindingIssueKind::SameSet { .. }))
+        .unwrap();
+    let BindingIssueKind::SameSet { set, actions, .. } = &same_set.kind else {
+        unreachable!()
+    };
+    assert_eq!((*set, actions.len()), (core, 2));
+    assert!(actions.contains(&jump) && actions.contains(&hop));
+    let description = same_set.to_string();
+    assert!(description.contains("core/jump") && description.contains("core/hop"));
+    assert!(description.ends_with("in set core"));
+    let equal_priority = report
+        .issues
+        .iter()
+        .find_map(|i| match &i.kind {
+            BindingIssueKind::EqualPriority { actions, .. } => Some(actions.clone()),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(equal_priority.len(), 3);
+    assert!(
+        [jump, hop, confirm]
+            .iter()
+            .all(|a| equal_priority.contains(a))
+    );
+    let shadowed = report
+        .issues
+        .iter()
+        .find(|i| matches!(i.kind, BindingIssueKind::Shadowed { .. }))
+        .unwrap();
+    let BindingIssueKind::Shadowed { action, by, .. } = &shadowed.kind else {
+        unreachable!()
+    };
+    assert_eq!((*action, by.as_slice()), (back, [pause].as_slice()));
+    assert!(
+        shadowed
+            .to_string()
+            .ends_with("of core/back is shadowed by overlay/pause")
+    );
+
+    app.world_mut()
+        .get_mut::<ActionSet>(overlay)
+        .unwrap()
+        .enabled = false;
+    app.world_mut().entity_mut(confirm).despawn();
+    // disabled sets don't shadow, only the conflict within core is left
+    let report = validate_bindings(app.world_mut());
+    assert_eq!(report.issues.len(), 1, "{:?}", report.issues);
+    assert!(matches!(
+        report.issues[0].kind,
+        BindingIssueKind::SameSet { .. }
+    ));
+}
+
+#[test]
+fn explain_blocked_bindings() {
+    let mut app = headless_app();
+    let overlay = spawn_set(&mut app, "overlay", 2);
+    let menu = spawn_set(&mut app, "menu", 1);
+    let game = spawn_set(&mut app, "game", 0);
+    let skip = spawn_key_action(
+        &mut app,
+        overlay,
+        KeyCode::KeyS,
+        ButtonInputBeheavior::Pressed,
+    );
+    app.world_mut()
+        .entity_mut(skip)
+        .insert(BlockingMode::WhenTriggered);
+    let up = spawn_key_action(&mut app, menu, KeyCode::KeyW, ButtonInputBeheavior::Pressed);
+    let walk = app
+        .world_mut()
+        .spawn((
+            Action::new("walk", "Walk", game),
+            KeyboardBindings::new().add_dpad(
+                KeyCode::KeyW,
+                KeyCode::KeyS,
+                KeyCode::KeyA,
+                KeyCode::KeyD,
+            ),
+            Vec2ActionValue::new(),
+        ))
+        .id();
+    app.step();
+
+    let blocked = explain_blocked(app.world_mut(), walk);
+    assert_eq!(blocked.len(), 1, "{blocked:?}");
+    assert_eq!(blocked[0].description, "keyboard:KeyW");
+    assert_eq!(
+        blocked[0].blocked_by,
+        [BlockingAction {
+            set: menu,
+            action: up
+        }]
+    );
+    assert!(blocked[0].consumed_by.is_empty());
+    assert_eq!(
+        blocked[0].describe(app.world()),
+        "the keyboard:KeyW binding of game/walk is blocked by menu/KeyW"
+    );
+    assert!(explain_blocked(app.world_mut(), up).is_empty());
+
+    // the overlay only takes S while it triggers
+    app.press_key(KeyCode::KeyS).step();
+    let blocked = explain_blocked(app.world_mut(), walk);
+    assert_eq!(blocked.len(), 2, "{blocked:?}");
+    assert_eq!(blocked[0].description, "keyboard:KeyS");
+    assert!(blocked[0].blocked_by.is_empty());
+    assert_eq!(
+        blocked[0].consumed_by,
+        [BlockingAction {
+            set: overlay,
+            action: skip
+        }]
+    );
+    assert_eq!(
+        blocked[0].describe(app.world()),
+        "the keyboard:KeyS binding of game/walk is consumed by overlay/KeyS"
+    );
+    assert_eq!(app.action_value::<Vec2ActionValue>(walk).any, Vec2::ZERO);
+    app.release_key(KeyCode::KeyS).step();
+
+    app.world_mut().get_mut::<ActionSet>(menu).unwrap().enabled = false;
+    app.step();
+    assert!(explain_blocked(app.world_mut(), walk).is_empty());
+
+    // held chords consume their members
+    let save = app
+        .world_mut()
+        .spawn((
+            Action::new("save", "Save", overlay),
+            ChordBindings::new()
+                .bind(ChordBinding::new(KeyCode::KeyD).modifier(KeyCode::ControlLeft)),
+            BoolActionValue::new(),
+        ))
+        .id();
+    app.press_key(KeyCode::ControlLeft).step();
+    app.press_key(KeyCode::KeyD).step();
+    let blocked = explain_blocked(app.world_mut(), walk);
+    assert_eq!(blocked.len(), 1, "{blocked:?}");
+    assert_eq!(
+        blocked[0].consumed_by,
+        [BlockingAction {
+            set: overlay,
+            action: save
+        }]
+    );
+    assert_eq!(
+        blocked[0].describe(app.world()),
+        "the keyboard:KeyD binding of game/walk is consumed by overlay/save"
+    );
+}
+
+#[test]
+fn binding_modifications() {
+    let mut app = headless_app();
+    let set = spawn_set(&mut app, "core", 0);
+    let processors = app
+        .world_mut()
+        .spawn(related!(InputProcessors[Scale::splat(2.0)]))
+        .id();
+    let throttle = app
+        .world_mut()
+        .spawn((
+            Action::new("throttle", "Throttle", set),
+            KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::KeyW)),
+            BindingModifications::new().with_all_paths_modification(processors),
+            F32ActionValue::new(),
+        ))
+        .id();
+    app.step();
+
+    app.press_key(KeyCode::KeyW).step();
+    assert_eq!(app.action_value::<F32ActionValue>(throttle).any, 2.0);
+}
+
+#[derive(Component, Clone, Debug)]
+struct AddOne;
+
+impl InputProcessor for AddOne {
+    fn process_f32(&self, value: f32) -> f32 {
+        value + 1.0
+    }
+}
+
+#[test]
+fn custom_processors() {
+    let mut app = headless_app();
+    app.add_input_processor::<AddOne>();
+    let set = spawn_set(&mut app, "core", 0);
+    let processors = app
+        .world_mut()
+        .spawn(related!(InputProcessors[Scale::splat(2.0), AddOne]))
+        .id();
+    let throttle = app
+        .world_mut()
+        .spawn((
+            Action::new("throttle", "Throttle", set),
+            KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::KeyW)),
+            BindingModifications::new().with_all_paths_modification(processors),
+            F32ActionValue::new(),
+        ))
+        .id();
+    app.step();
+
+    // processors run in order
+    app.press_key(KeyCode::KeyW).step();
+    assert_eq!(app.action_value::<F32ActionValue>(throttle).any, 3.0);
+
+    // changes to the processor components apply in the same frame
+    let [scale, add_one] = app.world().get::<InputProcessors>(processors).unwrap()[..] else {
+        unreachable!()
+    };
+    app.world_mut().get_mut::<Scale>(scale).unwrap().0 = Vec2::splat(3.0);
+    app.step();
+    assert_eq!(app.action_value::<F32ActionValue>(throttle).any, 4.0);
+    app.world_mut().despawn(add_one);
+    app.step();
+    assert_eq!(app.action_value::<F32ActionValue>(throttle).any, 3.0);
+    app.world_mut().entity_mut(scale).remove::<Scale>();
+    app.step();
+    assert_eq!(app.action_value::<F32ActionValue>(throttle).any, 1.0);
+}
+
+// the values for all paths are reused for the requested paths that don't select a device,
+// they have to match evaluating every path on its own
+#[test]
+fn path_values_match_per_path_evaluation() {
+    let mut app = headless_app();
+    let set = spawn_set(&mut app, "core", 0);
+    let gamepad_1 = app.spawn_virtual_gamepad("1");
+    let gamepad_2 = app.spawn_virtual_gamepad("2");
+    let [keyboard, all, first, second] =
+        app.world_mut()
+            .resource_scope(|world, mut paths: Mut<SubactionPaths>| {
+                let mut cmds = world.commands();
+                ["/keyboard", "/gamepad/*", "/gamepad/1", "/gamepad/2"]
+                    .map(|path| paths.get_or_create_path(path, &mut cmds))
+            });
+    let processors = app
+        .world_mut()
+        .spawn(related!(InputProcessors[Scale::splat(3.0)]))
+        .id();
+    let jump = app
+        .world_mut()
+        .spawn((
+            Action::new("jump", "Jump", set),
+            KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::Space)),
+            GamepadBindings::new().bind(GamepadBinding::new(GamepadBindingSource::South)),
+            RequestedSubactionPaths(vec![keyboard, all, first, second]),
+            BindingModifications::new().with_path_modification(first, processors),
+            F32ActionValue::new(),
+        ))
+        .id();
+    app.step();
+    let values = |app: &App| {
+        let value = app.action_value::<F32ActionValue>(jump);
+        [keyboard, all, first, second].map(|path| value.get_with_path_or_default(&path))
+    };
+
+    // the modification of /gamepad/1 matches every gamepad binding
+    app.press_key(KeyCode::Space)
+        .set_gamepad_button(gamepad_1, GamepadButton::South, 0.5)
+        .set_gamepad_button(gamepad_2, GamepadButton::South, 0.25)
+        .step();
+    assert_eq!(app.action_value::<F32ActionValue>(jump).any, 3.25);
+    assert_eq!(values(&app), [1.0, 2.25, 1.5, 0.75]);
+
+    // the buffers of the last frame don't leak into this one
+    app.release_key(KeyCode::Space)
+        .release_gamepad_button(gamepad_2, GamepadButton::South)
+        .step();
+    assert_eq!(app.action_value::<F32ActionValue>(jump).any, 1.5);
+    assert_eq!(values(&app), [0.0, 1.5, 1.5, 0.0]);
+
+    // blocked bindings are left out of every path
+    let menu = spawn_set(&mut app, "menu", 1);
+    spawn_key_action(
+        &mut app,
+        menu,
+        KeyCode::Space,
+        ButtonInputBeheavior::Pressed,
+    );
+    app.press_key(KeyCode::Space).step();
+    assert_eq!(app.action_value::<F32ActionValue>(jump).any, 1.5);
+    assert_eq!(values(&app), [0.0, 1.5, 1.5, 0.0]);
+}
+
+// the masks of the per path modifications grow past one word
+#[test]
+fn many_path_modifications() {
+    let mut app = headless_app();
+    let set = spawn_set(&mut app, "core", 0);
+    let keyboard = app
+        .world_mut()
+        .resource_scope(|world, mut paths: Mut<SubactionPaths>| {
+            paths.get_or_create_path("/keyboard", &mut world.commands())
+        });
+    let mut modifications = BindingModifications::new();
+    for _ in 0..70 {
+        let modification = app.world_mut().spawn_empty().id();
+        modifications.path_modification(keyboard, modification);
+    }
+    let processors = app
+        .world_mut()
+        .spawn(related!(InputProcessors[Scale::splat(2.0)]))
+        .id();
+    modifications.path_modification(keyboard, processors);
+    let throttle = app
+        .world_mut()
+        .spawn((
+            Action::new("throttle", "Throttle", set),
+            KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::KeyW)),
+            modifications,
+            F32ActionValue::new(),
+        ))
+        .id();
+    app.step();
+
+    app.press_key(KeyCode::KeyW).step();
+    assert_eq!(app.action_value::<F32ActionValue>(throttle).any, 2.0);
+}
+
+#[test]
+fn processors_run_before_premultiplied_delta() {
+    let mut app = headless_app();
+    let set = spawn_set(&mut app, "core", 0);
+    let gamepad = app.spawn_virtual_gamepad("1");
+    let modification = app
+        .world_mut()
+        .spawn((
+            PremultiplyDeltaSecsModification,
+            related!(InputProcessors[RadialDeadzone::new(0.2)]),
+        ))
+        .id();
+    let movement = app
+        .world_mut()
+        .spawn((
+            Action::new("move", "Move", set),
+            GamepadBindings::new().add_stick(
+                GamepadBindingSource::LeftStickX,
+                GamepadBindingSource::LeftStickY,
+            ),
+            BindingModifications::new().with_all_paths_modification(modification),
+            Vec2ActionValue::new(),
+        ))
+        .id();
+    app.step();
+
+    let delta = HEADLESS_FRAME_TIME.as_secs_f32();
+    app.set_gamepad_axis(gamepad, GamepadAxis::LeftStickX, 0.1)
+        .step();
+    assert_eq!(
+        app.action_value::<Vec2ActionValue>(movement).any,
+        Vec2::ZERO
+    );
+    app.set_gamepad_axis(gamepad, GamepadAxis::LeftStickX, 1.0)
+        .step();
+    let value = app.action_value::<Vec2ActionValue>(movement).any;
+    assert!((value - Vec2::new(delta, 0.0)).length() < 1e-6, "{value}");
+}
+
+#[derive(Resource, Default)]
+struct FixedTicks(Vec<(bool, Vec2)>);
+
+#[test]
+fn fixed_values() {
+    let mut app = headless_app();
+    app.init_resource::<FixedTicks>();
+    // two timesteps every frame
+    app.insert_resource(Time::<Fixed>::from_duration(HEADLESS_FRAME_TIME / 2));
+    let set = spawn_set(&mut app, "core", 0);
+    let gamepad = app.spawn_virtual_gamepad("1");
+    let jump = app
+        .world_mut()
+        .spawn((
+            Action::new("jump", "Jump", set),
+            KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::Space)),
+            BoolActionValue::new(),
+            FixedBoolActionValue::new(),
+        ))
+        .id();
+    let look = app
+        .world_mut()
+        .spawn((
+            Action::new("look", "Look", set),
+            MouseBindings::new().delta_motion(),
+            GamepadBindings::new().add_stick(
+                GamepadBindingSource::RightStickX,
+                GamepadBindingSource::RightStickY,
+            ),
+            Vec2ActionValue::new(),
+            FixedVec2ActionValue::new(),
+        ))
+        .id();
+    app.add_systems(
+        FixedUpdate,
+        move |jump_query: Query<&FixedBoolActionValue>,
+              look_query: Query<&FixedVec2ActionValue>,
+              mut ticks: ResMut<FixedTicks>| {
+            ticks.0.push((
+                jump_query.get(jump).unwrap().any,
+                look_query.get(look).unwrap().any,
+            ));
+        },
+    );
+    app.step_frames(2);
+    let take_ticks =
+        |app: &mut App| std::mem::take(&mut app.world_mut().resource_mut::<FixedTicks>().0);
+    take_ticks(&mut app);
+
+    // held inputs are seen by every timestep, deltas only by the first one
+    app.press_key(KeyCode::Space)
+        .move_mouse(Vec2::new(4.0, 0.0))
+        .set_gamepad_axis(gamepad, GamepadAxis::RightStickX, 0.5)
+        .step();
+    assert_eq!(
+        take_ticks(&mut app),
+        vec![(true, Vec2::new(4.5, 0.0)), (true, Vec2::new(0.5, 0.0))]
+    );
+    app.step();
+    assert_eq!(
+        take_ticks(&mut app),
+        vec![(true, Vec2::new(0.5, 0.0)), (true, Vec2::new(0.5, 0.0))]
+    );
+
+    // one timestep every other frame, presses and deltas of frames without one are kept
+    app.insert_resource(Time::<Fixed>::from_duration(HEADLESS_FRAME_TIME * 2));
+    app.release_key(KeyCode::Space).step_frames(2);
+    take_ticks(&mut app);
+    app.press_key(KeyCode::Space)
+        .move_mouse(Vec2::new(1.0, 0.0))
+        .step();
+    app.release_key(KeyCode::Space)
+        .move_mouse(Vec2::new(2.0, 0.0))
+        .step();
+    assert_eq!(take_ticks(&mut app), vec![(true, Vec2::new(3.5, 0.0))]);
+    app.step_frames(2);
+    assert_eq!(take_ticks(&mut app), vec![(false, Vec2::new(0.5, 0.0))]);
+}
+
+// the delta part can't be taken out of a clamped value, so every timestep sees the latest one
+#[test]
+fn fixed_values_with_clamped_sum() {
+    let mut app = headless_app();
+    app.init_resource::<FixedTicks>();
+    // two timesteps every frame
+    app.insert_resource(Time::<Fixed>::from_duration(HEADLESS_FRAME_TIME / 2));
+    let set = spawn_set(&mut app, "core", 0);
+    let gamepad = app.spawn_virtual_gamepad("1");
+    let look = app
+        .world_mut()
+        .spawn((
+            Action::new("look", "Look", set),
+            MouseBindings::new().delta_motion(),
+            GamepadBindings::new().add_stick(
+                GamepadBindingSource::RightStickX,
+                GamepadBindingSource::RightStickY,
+            ),
+            ValueAggregation::ClampedSum,
+            Vec2ActionValue::new(),
+            FixedVec2ActionValue::new(),
+        ))
+        .id();
+    app.add_systems(
+        FixedUpdate,
+        move |query: Query<&FixedVec2ActionValue>, mut ticks: ResMut<FixedTicks>| {
+            ticks.0.push((false, query.get(look).unwrap().any));
+        },
+    );
+    app.step_frames(2);
+    let take_ticks =
+        |app: &mut App| std::mem::take(&mut app.world_mut().resource_mut::<FixedTicks>().0);
+    take_ticks(&mut app);
+
+    app.move_mouse(Vec2::new(4.0, 0.0))
+        .set_gamepad_axis(gamepad, GamepadAxis::RightStickX, 0.5)
+        .step();
+    assert_eq!(
+        take_ticks(&mut app),
+        vec![(false, Vec2::new(1.0, 0.0)), (false, Vec2::new(1.0, 0.0))]
+    );
+    app.step();
+    assert_eq!(
+        take_ticks(&mut app),
+        vec![(false, Vec2::new(0.5, 0.0)), (false, Vec2::new(0.5, 0.0))]
+    );
+}
+
+#[derive(Resource, Default)]
+struct FixedPresses(Vec<bool>);
+
+#[test]
+fn fixed_presses_are_seen_once() {
+    let mut app = headless_app();
+    app.init_resource::<FixedPresses>();
+    // two timesteps every frame
+    app.insert_resource(Time::<Fixed>::from_duration(HEADLESS_FRAME_TIME / 2));
+    let set = spawn_set(&mut app, "core", 0);
+    let jump = app
+        .world_mut()
+        .spawn((
+            Action::new("jump", "Jump", set),
+            KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::Space).just_pressed()),
+            BoolActionValue::new(),
+            FixedBoolActionValue::new(),
+        ))
+        .id();
+    app.add_systems(
+        FixedUpdate,
+        move |query: Query<&FixedBoolActionValue>, mut presses: ResMut<FixedPresses>| {
+            presses.0.push(query.get(jump).unwrap().any);
+        },
+    );
+    app.step_frames(2);
+    let take_presses =
+        |app: &mut App| std::mem::take(&mut app.world_mut().resource_mut::<FixedPresses>().0);
+    take_presses(&mut app);
+
+    app.press_key(KeyCode::Space).step();
+    assert_eq!(take_presses(&mut app), vec![true, false]);
+    app.step();
+    assert_eq!(take_presses(&mut app), vec![false, false]);
+}
+
+#[derive(Resource, Default)]
+struct ChangedValues(u32);
+
+#[test]
+fn unchanged_values_are_not_marked_changed() {
+    let mut app = headless_app();
+    app.init_resource::<ChangedValues>();
+    app.add_systems(
+        Update,
+        |query: Query<(), Changed<BoolActionValue>>, mut changed: ResMut<ChangedValues>| {
+            changed.0 += query.iter().count() as u32;
+        },
+    );
+    let set = spawn_set(&mut app, "core", 0);
+    app.step();
+    app.world_mut().spawn((
+        Action::new("jump", "Jump", set),
+        KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::Space)),
+        BoolActionValue::new(),
+    ));
+    // new values are changed on the frame they got spawned, even if they stay at the default
+    app.step();
+    assert_eq!(app.world().resource::<ChangedValues>().0, 1);
+    app.step();
+    app.world_mut().resource_mut::<ChangedValues>().0 = 0;
+
+    app.step().step();
+    assert_eq!(app.world().resource::<ChangedValues>().0, 0);
+    app.press_key(KeyCode::Space).step().step().step();
+    assert_eq!(app.world().resource::<ChangedValues>().0, 1);
+    app.release_key(KeyCode::Space).step().step();
+    assert_eq!(app.world().resource::<ChangedValues>().0, 2);
+}
+
+#[derive(Resource, Default)]
+struct ReadByName(Vec<(bool, bool, bool)>);
+
+#[test]
+fn read_actions_by_name() {
+    let mut app = headless_app();
+    app.init_resource::<ReadByName>();
+    app.add_systems(
+        Update,
+        |reader: ActionReader<bool>, mut read: ResMut<ReadByName>| {
+            read.0.push((
+                reader.by_name("core/jump").pressed(),
+                reader.by_name_with_path("core/jump", "/keyboard").pressed(),
+                reader.by_name("core/missing").pressed(),
+            ));
+        },
+    );
+    let set = spawn_set(&mut app, "core", 0);
+    let jump = app
+        .world_mut()
+        .spawn((
+            Action::new("jump", "Jump", set),
+            KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::Space)),
+            BoolActionValue::new(),
+        ))
+        .id();
+    app.world_mut()
+        .run_system_once(
+            move |mut cmds: Commands, mut paths: ResMut<SubactionPaths>| {
+                let sub_paths = RequestedSubactionPaths::new()
+                    .mutate(&mut paths, cmds.reborrow())
+                    .push("/keyboard")
+                    .end();
+                cmds.entity(jump).insert(sub_paths);
+            },
+        )
+        .unwrap();
+    app.step();
+    assert_eq!(
+        app.world().resource::<ActionIndex>().get("core/jump"),
+        Some(jump)
+    );
+
+    app.press_key(KeyCode::Space).step();
+    assert_eq!(
+        app.world().resource::<ReadByName>().0.last(),
+        Some(&(true, true, false))
+    );
+
+    app.world_mut()
+        .entity_mut(jump)
+        .insert(Action::new("hop", "Hop", set));
+    app.step();
+    let index = app.world().resource::<ActionIndex>();
+    assert_eq!(index.get("core/jump"), None);
+    assert_eq!(index.get_in_set("core", "hop"), Some(jump));
+
+    app.world_mut().entity_mut(set).despawn();
+    app.step();
+    assert!(app.world().resource::<ActionIndex>().is_empty());
+}
+
+#[test]
+fn player_copies_are_named_by_slot() {
+    let mut app = headless_app();
+    let set = spawn_set(&mut app, "core", 0);
+    app.world_mut().get_mut::<ActionSet>(set).unwrap().enabled = false;
+    let jump = app
+        .world_mut()
+        .spawn((
+            Action::new("jump", "Jump", set),
+            KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::Space)),
+            BoolActionValue::new(),
+        ))
+        .id();
+    let player = app
+        .world_mut()
+        .spawn((Player::new(1), PlayerDevices::new().with_keyboard()))
+        .id();
+    app.world_mut()
+        .run_system_once(move |mut cmds: Commands| {
+            cmds.copy_action_set_for_player(set, player);
+        })
+        .unwrap();
+    app.step();
+
+    let index = app.world().resource::<ActionIndex>();
+    assert_eq!(index.get("core/jump"), Some(jump));
+    let copy = index.get("core_player_1/jump").unwrap();
+    assert_ne!(copy, jump);
+
+    app.press_key(KeyCode::Space).step();
+    assert!(app.action_value::<BoolActionValue>(copy).any);
+    assert!(!app.action_value::<BoolActionValue>(jump).any);
+}
+
+#[derive(Resource, Default)]
+struct JoinLog(Vec<(u32, JoinDevice)>, Vec<u32>);
+
+#[test]
+fn join_assigns_free_slots() {
+    let mut app = headless_app();
+    app.init_resource::<JoinLog>();
+    app.add_systems(
+        Update,
+        |mut joined: MessageReader<PlayerJoined>,
+         mut left: MessageReader<PlayerLeft>,
+         mut log: ResMut<JoinLog>| {
+            log.0.extend(joined.read().map(|j| (j.slot, j.device)));
+            log.1.extend(left.read().map(|l| l.slot));
+        },
+    );
+    let set = spawn_set(&mut app, "core", 0);
+    app.world_mut().get_mut::<ActionSet>(set).unwrap().enabled = false;
+    app.world_mut().spawn((
+        Action::new("jump", "Jump", set),
+        GamepadBindings::new().bind(GamepadBinding::new(GamepadBindingSource::South)),
+        BoolActionValue::new(),
+    ));
+    app.world_mut().spawn((
+        Action::new("leave", "Leave", set),
+        GamepadBindings::new().bind(GamepadBinding::new(GamepadBindingSource::Select)),
+        KeyboardBindings::new().bind(KeyboardBinding::new(KeyCode::Escape)),
+        BoolActionValue::new(),
+        LeaveAction,
+    ));
+    let first = app.spawn_virtual_gamepad("first");
+    let second = app.spawn_virtual_gamepad("second");
+    app.world_mut().insert_resource(JoinFlow {
+        active: false,
+        max_players: 2,
+        action_sets: vec![set],
+        allow_keyboard_mouse: true,
+    });
+    app.step();
+    let take = |app: &mut App| std::mem::take(&mut *app.world_mut().resource_mut::<JoinLog>());
+    let identifier = |app: &App, gamepad: Entity| {
+        app.world()
+            .get::<GamepadIdentifier>(gamepad)
+            .map(|id| id.0.to_string())
+    };
+
+    app.press_gamepad_button(first, GamepadButton::South).step();
+    assert!(take(&mut app).0.is_empty());
+    app.release_gamepad_button(first, GamepadButton::South)
+        .step();
+
+    app.world_mut().resource_mut::<JoinFlow>().active = true;
+    app.press_gamepad_button(first, GamepadButton::South).step();
+    assert_eq!(take(&mut app).0, [(0, JoinDevice::Gamepad(first))]);
+    assert_eq!(identifier(&app, first).as_deref(), Some("1"));
+    // the joining press already belongs to the player
+    app.step();
+    let jump = app
+        .world()
+        .resource::<ActionIndex>()
+        .get("core_player_0/jump")
+        .unwrap();
+    assert!(app.action_value::<BoolActionValue>(jump).any);
+    // owned devices don't join again
+    app.release_gamepad_button(first, GamepadButton::South)
+        .step();
+    app.press_gamepad_button(first, GamepadButton::South).step();
+    assert!(take(&mut app).0.is_empty());
+
+    app.press_key(KeyCode::KeyJ).step();
+    assert_eq!(take(&mut app).0, [(1, JoinDevice::KeyboardMouse)]);
+    app.press_key(KeyCode::KeyK).step();
+    // every slot is taken
+    app.press_gamepad_button(second, GamepadButton::South)
+        .step();
+    assert!(take(&mut app).0.is_empty());
+    app.release_gamepad_button(second, GamepadButton::South)
+        .step();
+
+    // the first player leaves, the second gamepad gets the free slot and identifier
+    app.press_gamepad_button(first, GamepadButton::Select)
+        .step();
+    let log = take(&mut app);
+    assert_eq!(log.1, [0]);
+    app.press_gamepad_button(second, GamepadButton::South)
+        .step();
+    assert_eq!(take(&mut app).0, [(0, JoinDevice::Gamepad(second))]);
+    assert_eq!(identifier(&app, second).as_deref(), Some("1"));
+    assert_eq!(identifier(&app, first).as_deref(), Some("second"));
+    let mut slots = app
+        .world_mut()
+        .query::<&Player>()
+        .iter(app.world())
+        .map(|p| p.slot)
+        .collect::<Vec<_>>();
+    slots.sort();
+    assert_eq!(slots, [0, 1]);
+}
+
+fn run_commands(app: &mut App, f: impl FnOnce(&mut Commands)) {
+    f(&mut app.world_mut().commands());
+    app.world_mut().flush();
+}
+
+#[test]
+fn contexts_restore_sets() {
+    let mut app = headless_app();
+    let gameplay = spawn_set(&mut app, "gameplay", 0);
+    let menu = spawn_set(&mut app, "menu", 0);
+    let chat = spawn_set(&mut app, "chat", 0);
+    app.world_mut().get_mut::<ActionSet>(menu).unwrap().enabled = false;
+    app.world_mut().get_mut::<ActionSet>(chat).unwrap().enabled = false;
+    let enabled = |app: &App, set| app.world().get::<ActionSet>(set).unwrap().enabled;
+    let layer = |app: &App, set| app.world().get::<ContextLayer>(set).copied();
+
+    let menu_context = InputContext::new("menu", ContextPolicy::Disable).with_set(menu);
+    run_commands(&mut app, move |cmds| cmds.push_context(menu_context));
+    assert!(!enabled(&app, gameplay));
+    assert!(enabled(&app, menu));
+    run_commands(&mut app, |cmds| cmds.pop_context());
+    assert!(enabled(&app, gameplay));
+    assert!(!enabled(&app, menu));
+
+    // sets of the context that were enabled before stay enabled
+    app.world_mut().get_mut::<ActionSet>(menu).unwrap().enabled = true;
+    let menu_context = InputContext::new("menu", ContextPolicy::Disable).with_set(menu);
+    run_commands(&mut app, move |cmds| cmds.push_context(menu_context));
+    run_commands(&mut app, |cmds| cmds.pop_context());
+    assert!(enabled(&app, gameplay));
+    assert!(enabled(&app, menu));
+
+    // a set in two contexts goes back to the layer of the older one
+    let lobby = InputContext::new("lobby", ContextPolicy::Shadow).with_set(chat);
+    let match_context = InputContext::new("match", ContextPolicy::Shadow).with_set(chat);
+    run_commands(&mut app, move |cmds| {
+        cmds.push_context(lobby);
+        cmds.push_context(match_context);
+    });
+    assert_eq!(layer(&app, chat), Some(ContextLayer(2)));
+    run_commands(&mut app, |cmds| cmds.pop_context());
+    assert_eq!(layer(&app, chat), Some(ContextLayer(1)));
+    assert!(enabled(&app, chat));
+    run_commands(&mut app, |cmds| cmds.pop_context());
+    assert_eq!(layer(&app, chat), None);
+    assert!(!enabled(&app, chat));
+    assert!(enabled(&app, gameplay));
+}
+
+#[test]
+fn mouse_scroll() {
+    let mut app = headless_app();
+    app.insert_resource(schminput::mouse::MouseScrollSettings {
+        pixels_per_line: 10.0,
+    });
+    let set = spawn_set(&mut app, "core", 0);
+    let zoom = app
+        .world_mut()
+        .spawn((
+            Action::new("zoom", "Zoom", set),
+            MouseBindings::new().scroll(MouseScrollBinding::new(MouseScrollSource::Vertical)),
+            F32ActionValue::new(),
+        ))
+        .id();
+    let pan = app
+        .world_mut()
+        .spawn((
+            Action::new("pan", "Pan", set),
+            MouseBindings::new().scroll(MouseScrollBinding::new(MouseScrollSource::Both)),
+            Vec2ActionValue::new(),
+        ))
+        .id();
+    let flipped = app
+        .world_mut()
+        .spawn((
+            Action::new("flipped", "Flipped", set),
+            MouseBindings::new().scroll(
+                MouseScrollBinding::new(MouseScrollSource::Both)
+                    .negative_axis_dir()
+                    .multiplier(2.0),
+            ),
+            Vec2ActionValue::new(),
+        ))
+        .id();
+    let step_down = app
+        .world_mut()
+        .spawn((
+            Action::new("step_down", "Step Down", set),
+            MouseBindings::new().scroll(
+                MouseScrollBinding::new(MouseScrollSource::Down)
+                    .y_axis()
+                    .negative_axis_dir()
+                    .multiplier(0.5),
+            ),
+            F32ActionValue::new(),
+        ))
+        .id();
+    let step_back = app
+        .world_mut()
+        .spawn((
+            Action::new("step_back", "Step Back", set),
+            MouseBindings::new()
+                .scroll(MouseScrollBinding::new(MouseScrollSource::Down).multiplier(-1.0)),
+            F32ActionValue::new(),
+        ))
+        .id();
+    let next = app
+        .world_mut()
+        .spawn((
+            Action::new("next", "Next", set),
+            MouseBindings::new()
+                .scroll(MouseScrollBinding::new(MouseScrollSource::Down).just_pressed()),
+            BoolActionValue::new(),
+        ))
+        .id();
+    app.step();
+
+    app.scroll_mouse(Vec2::new(1.0, -2.0));
+    app.world_mut()
+        .write_message(bevy::input::mouse::MouseWheel {
+            unit: bevy::input::mouse::MouseScrollUnit::Pixel,
+            x: 0.0,
+            y: -10.0,
+            window: Entity::PLACEHOLDER,
+        });
+    app.step();
+    assert_eq!(app.action_value::<F32ActionValue>(zoom).any, -3.0);
+    assert_eq!(
+        app.action_value::<Vec2ActionValue>(pan).any,
+        Vec2::new(1.0, -3.0)
+    );
+    assert!(app.action_value::<BoolActionValue>(next).any);
+    assert_eq!(
+        app.action_value::<Vec2ActionValue>(flipped).any,
+        Vec2::new(-2.0, 6.0)
+    );
+    assert_eq!(app.action_value::<F32ActionValue>(step_down).any, -0.5);
+    // the direction doesn't depend on the multiplier
+    assert_eq!(app.action_value::<F32ActionValue>(step_back).any, -1.0);
+
+    // still scrolling down, but not just started
+    app.scroll_mouse(Vec2::NEG_Y).step();
+    assert!(!app.action_value::<BoolActionValue>(next).any);
+    app.step().scroll_mouse(Vec2::NEG_Y).step();
+    assert!(app.action_value::<BoolActionValue>(next).any);
+    app.scroll_mouse(Vec2::Y).step();
+    assert!(!app.action_value::<BoolActionValue>(next).any);
+    assert_eq!(app.action_value::<F32ActionValue>(zoom).any, 1.0);
+}
+
+#[test]
+fn snapshot_bytes() {
+    // the ids have to be the same on every machine and build
+    assert_eq!(ActionSetId::new("core").0, 0xce56_d88e_5547_73d3);
+    assert_ne!(ActionId::new("ab", "c"), ActionId::new("a", "bc"));
+
+    let snapshot = ActionSnapshot {
+        sets: vec![ActionSetId::new("core"), ActionSetId::new("menu")],
+        entries: vec![
+            SnapshotEntry {
+                action: ActionId::new("core", "jump"),
+                path: None,
+                value: SnapshotValue::Bool(true),
+            },
+            SnapshotEntry {
+                action: ActionId::new("core", "jump"),
+                path: Some(SubactionPathId::new("/keyboard")),
+                value: SnapshotValue::Bool(false),
+            },
+            SnapshotEntry {
+                action: ActionId::new("core", "throttle"),
+                path: None,
+                value: SnapshotValue::F32(i32::MIN),
+            },
+            SnapshotEntry {
+                action: ActionId::new("core", "move"),
+                path: Some(SubactionPathId::new("/gamepad/*")),
+                value: SnapshotValue::Vec2(i32::MAX, -1),
+            },
+        ],
+    };
+    let bytes = snapshot.to_bytes();
+    assert_eq!(ActionSnapshot::from_bytes(&bytes), Some(snapshot));
+    assert_eq!(
+        ActionSnapshot::from_bytes(&ActionSnapshot::default().to_bytes()),
+        Some(ActionSnapshot::default())
+    );
+
+    // every truncation is rejected, as are trailing bytes
+    for len in 0..bytes.len() {
+        assert_eq!(ActionSnapshot::from_bytes(&bytes[..len]), None, "{len}");
+    }
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert_eq!(ActionSnapshot::from_bytes(&trailing), None);
+    // unknown path flag on the first entry, after the set count and two set ids
+    let mut bad_tag = bytes.clone();
+    bad_tag[1 + 16 + 1] |= 0b1000;
+    assert_eq!(ActionSnapshot::from_bytes(&bad_tag), None);
+    // varints longer than 10 bytes or with bits past 64
+    assert_eq!(ActionSnapshot::from_bytes(&[0xff; 11]), None);
+    let mut overflow = vec![0x80; 9];
+    overflow.push(0x02);
+    assert_eq!(ActionSnapshot::from_bytes(&overflow), None);
+    // signed values that don't fit an i32
+    let mut too_large = vec![0, 1, 2];
+    too_large.extend_from_slice(&ActionId::new("core", "throttle").0.to_le_bytes());
+    too_large.extend_from_slice(&[0xff, 0xff, 0xff, 0xff, 0x1f]);
+    assert_eq!(ActionSnapshot::from_bytes(&too_large), None);
+}
+
+#[test]
+fn snapshot_capture_and_apply() {
+    let mut app = headless_app();
+    let set = spawn_set(&mut app, "core", 0);
+    let gamepad = app.spawn_virtual_gamepad("1");
+    let throttle = app
+        .world_mut()
+        .spawn((
+            Action::new("throttle", "Throttle", set),
+            GamepadBindings::new().bind(GamepadBinding::new(GamepadBindingSource::LeftStickX)),
+            F32ActionValue::new(),
+        ))
+        .id();
+    app.step();
+
+    app.set_gamepad_axis(gamepad, GamepadAxis::LeftStickX, 0.3)
+        .step();
+    let exact = app.action_value::<F32ActionValue>(throttle).any;
+    let snapshot = app
+        .world_mut()
+        .run_system_once(move |mut snapshots: ActionSnapshots| {
+            let snapshot = snapshots.capture(&[set]);
+            snapshots.apply(&snapshot);
+            snapshot
+        })
+        .unwrap();
+    let quantized = (exact * QUANTIZATION_STEPS).round() as i32;
+    assert_eq!(
+        snapshot.entries,
+        vec![SnapshotEntry {
+            action: ActionId::new("core", "throttle"),
+            path: None,
+            value: SnapshotValue::F32(quantized),
+        }]
+    );
+    // applying the snapshot locally gives the value the peers see
+    assert_eq!(
+        app.action_value::<F32ActionValue>(throttle).any,
+        quantized as f32 / QUANTIZATION_STEPS
+    );
+    assert_eq!(
+        ActionSnapshot::from_bytes(&snapshot.to_bytes()),
+        Some(snapshot)
+    );
+
+    // sets in the snapshot without entries are reset
+    app.world_mut()
+        .write_message(ApplyActionSnapshot(ActionSnapshot {
+            sets: vec![ActionSetId::new("core")],
+            entries: Vec::new(),
+        }));
+    app.step();
+    assert_eq!(app.action_value::<F32ActionValue>(throttle).any, 0.0);
+}
+
+#[cfg(feature = "manifest")]
+#[test]
+fn manifest_reload_in_place() {
+    use schminput::manifest::{
+        ActionManifest, ActionManifestRoot, ManifestEntities, ManifestPlugin,
+    };
+
+    let manifest: ActionManifest =
+        ron::de::from_str(include_str!("../assets/basic.actions.ron")).unwrap();
+    assert_eq!(manifest.sets.len(), 1);
+    assert_eq!(manifest.sets[0].actions.len(), 3);
+
+    let mut app = headless_app();
+    app.add_plugins((AssetPlugin::default(), ManifestPlugin));
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<ActionManifest>>()
+        .add(manifest);
+    let root = app
+        .world_mut()
+        .spawn(ActionManifestRoot(handle.clone()))
+        .id();
+    app.step_frames(2);
+
+    let entities = app.world().get::<ManifestEntities>(root).unwrap().clone();
+    let set = entities.set("core").unwrap();
+    let jump = entities.action("core", "jump").unwrap();
+    let look = entities.action("core", "look").unwrap();
+    let modification = app
+        .world()
+        .get::<BindingModifications>(look)
+        .unwrap()
+        .per_path[0]
+        .1
+        .0;
+    assert!(
+        app.world()
+            .entity(modification)
+            .contains::<PremultiplyDeltaSecsModification>()
+    );
+    app.press_key(KeyCode::Space).step();
+    assert!(app.action_value::<BoolActionValue>(jump).any);
+    app.release_key(KeyCode::Space).step();
+
+    app.world_mut()
+        .resource_mut::<Assets<ActionManifest>>()
+        .get_mut(&handle)
+        .unwrap()
+        .sets[0]
+        .actions[2]
+        .keyboard[0]
+        .key = KeyCode::KeyJ;
+    app.step_frames(2);
+    let reloaded = app.world().get::<ManifestEntities>(root).unwrap();
+    assert_eq!(reloaded.set("core"), Some(set));
+    assert_eq!(reloaded.action("core", "jump"), Some(jump));
+    assert_eq!(reloaded.action("core", "look"), Some(look));
+    assert_eq!(
+        app.world()
+            .get::<BindingModifications>(look)
+            .unwrap()
+            .per_path[0]
+            .1
+            .0,
+        modification
+    );
+    app.press_key(KeyCode::KeyJ).step();
+    assert!(app.action_value::<BoolActionValue>(jump).any);
+
+    app.world_mut().entity_mut(root).despawn();
+    app.step();
+    assert!(app.world().get_entity(jump).is_err());
+    assert!(app.world().get_entity(modification).is_err());
+}
+
+#[cfg(feature = "derive")]
+#[derive(Resource, ActionSet)]
+struct HTTPActions {
+    #[action_set]
+    set: Entity,
+    #[action(key = KeyCode::Space, key = KeyCode::Enter)]
+    jump_high: BoolAction,
+    #[action(name = "go", localized = "Go!", key = KeyCode::KeyG)]
+    move_: F32Action,
+    _look_around_: Vec2Action,
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn derive_action_set_defaults() {
+    let mut app = headless_app();
+    app.add_action_set::<HTTPActions>();
+    app.step();
+
+    let actions = app.world().resource::<HTTPActions>();
+    let (set, jump_high, move_, look) = (
+        actions.set,
+        actions.jump_high,
+        actions.move_,
+        actions._look_around_,
+    );
+    let set_info = app.world().get::<ActionSet>(set).unwrap();
+    assert_eq!(set_info.name, "http_actions");
+    assert_eq!(set_info.localized_name, "HTTPActions");
+    assert_eq!(set_info.priority, 0);
+
+    let index = app.world().resource::<ActionIndex>();
+    assert_eq!(
+        index.get("http_actions/jump_high"),
+        Some(jump_high.entity())
+    );
+    assert_eq!(index.get("http_actions/go"), Some(move_.entity()));
+    assert_eq!(index.get("http_actions/look_around"), Some(look.entity()));
+    let localized = |action: Entity| {
+        let action = app.world().get::<Action>(action).unwrap();
+        assert_eq!(action.set, set);
+        action.localized_name.to_string()
+    };
+    assert_eq!(localized(jump_high.entity()), "Jump High");
+    assert_eq!(localized(move_.entity()), "Go!");
+    assert_eq!(localized(look.entity()), "Look Around");
+
+    let read = |app: &mut App| {
+        app.world_mut()
+            .run_system_once(|actions: HTTPActionsParam| {
+                (
+                    actions.jump_high().just_pressed(),
+                    actions.move_().value(),
+                    actions.handles().set,
+                )
+            })
+            .unwrap()
+    };
+    app.press_key(KeyCode::Enter)
+        .press_key(KeyCode::KeyG)
+        .step();
+    assert_eq!(read(&mut app), (true, 1.0, set));
+    app.release_key(KeyCode::Enter).step();
+    app.press_key(KeyCode::Space).step();
+    assert_eq!(read(&mut app), (true, 1.0, set));
+}
+
+#[test]
+fn action_debug_info_and_dump() {
+    let mut app = headless_app();
+    app.insert_resource(LogActionValues(true));
+    let game = spawn_set(&mut app, "game", 0);
+    let menu = spawn_set(&mut app, "menu", 1);
+    app.world_mut().get_mut::<ActionSet>(menu).unwrap().enabled = false;
+    spawn_key_action(
+        &mut app,
+        menu,
+        KeyCode::Enter,
+        ButtonInputBeheavior::Pressed,
+    );
+    let jump = spawn_key_action(
+        &mut app,
+        game,
+        KeyCode::Space,
+        ButtonInputBeheavior::Pressed,
+    );
+    let keyboard = app
+        .world_mut()
+        .resource_scope(|world, mut paths: Mut<SubactionPaths>| {
+            paths.get_or_create_path("/keyboard", &mut world.commands())
+        });
+    let walk = app
+        .world_mut()
+        .spawn((
+            Action::new("walk", "Walk", game),
+            KeyboardBindings::new().add_dpad(
+                KeyCode::KeyW,
+                KeyCode::KeyS,
+                KeyCode::KeyA,
+                KeyCode::KeyD,
+            ),
+            RequestedSubactionPaths(vec![keyboard]),
+            Vec2ActionValue::new(),
+        ))
+        .id();
+    app.step();
+    assert!(app.world().get::<ContributingBindings>(walk).is_some());
+
+    app.press_key(KeyCode::KeyW)
+        .press_key(KeyCode::Space)
+        .step();
+    let info = action_debug_info(app.world_mut());
+    assert_eq!(
+        info.iter()
+            .map(|set| (set.set, set.enabled, set.actions.len()))
+            .collect::<Vec<_>>(),
+        [(menu, false, 1), (game, true, 2)]
+    );
+    // actions are sorted by name
+    let [jump_info, walk_info] = &info[1].actions[..] else {
+        unreachable!()
+    };
+    assert_eq!(jump_info.action, jump);
+    assert_eq!(jump_info.contributing, ["keyboard:Space"]);
+    assert_eq!(
+        walk_info.values,
+        [
+            PathDebugValue {
+                path: None,
+                value: DebugValue::Vec2(Vec2::Y),
+            },
+            PathDebugValue {
+                path: Some("/keyboard".into()),
+                value: DebugValue::Vec2(Vec2::Y),
+            },
+        ]
+    );
+    assert_eq!(walk_info.contributing, ["keyboard:KeyW"]);
+    assert_eq!(
+        dump_actions(app.world_mut()),
+        "menu (priority 1, disabled)\n  \
+         Enter: any=false\n\
+         game (priority 0)\n  \
+         Space: any=true [keyboard:Space]\n  \
+         walk: any=(0.000, 1.000) /keyboard=(0.000, 1.000) [keyboard:KeyW]\n"
+    );
+
+    app.release_key(KeyCode::KeyW).step();
+    let info = action_debug_info(app.world_mut());
+    assert!(info[1].actions[1].contributing.is_empty());
+    assert_eq!(info[1].actions[0].contributing, ["keyboard:Space"]);
+}